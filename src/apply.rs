@@ -1,16 +1,43 @@
-use crate::config::default_file_path;
+mod cache;
+mod clean;
+mod confirm;
+mod hooks;
+mod manifest;
+mod templating;
+
+use crate::config::{
+    check_strict, config_dir, deep_merge, default_config_from_matches, directories_mapping, ensure_relative_keys, is_absolute_key,
+    quiet_from_matches,
+    resolve_includes, resolve_path, resolve_template_path, strict_from_matches,
+    template_dir_from_matches, working_dir_from_matches,
+};
 use crate::errors::SkeletorError;
-use crate::output::{DefaultReporter, Reporter, SimpleApplyResult};
-use crate::tasks::{create_files_and_directories, traverse_structure, Task};
+use crate::output::{DefaultReporter, OutputFormat, Reporter, SilentReporter, SimpleApplyResult};
+use crate::tasks::{
+    create_files_and_directories_parallel, create_files_and_directories_with,
+    ensure_output_root, ensure_within_output, file_ref_path, load_keep_matcher, resolve_file_refs,
+    traverse_structure, traverse_structure_allowing_escape, CreationResult, FileWriteOptions,
+    LineEndings, NewlinePolicy, Task,
+};
+use crate::tasks::expand::{braces_enabled, expand_braces};
+use crate::tasks::mtime::restore_mtimes;
+use crate::tasks::variables::{collect_variables, substitute_variables_with_env};
+use crate::utils::ConfigFormat;
 use clap::ArgMatches;
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use log::info;
 use serde_yaml::Value;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
+use self::cache::DryRunCache;
+use self::clean::{remove_stale, stale_files};
+use self::confirm::{ask_overwrite_on_terminal, confirm_overwrites};
+use self::hooks::{post_apply_hooks, run_hooks};
+use self::manifest::{ask_on_terminal, AppliedManifest, LocalEditPlan, OnLocalEdit};
+use self::templating::{render_tasks, template_context};
 
 /// Extract binary files list from YAML if present
-fn extract_binary_files_from_yaml(yaml_config: &Value) -> Vec<String> {
+pub fn extract_binary_files_from_yaml(yaml_config: &Value) -> Vec<String> {
     if let Some(binary_files) = yaml_config.get("binary_files") {
         if let Some(array) = binary_files.as_sequence() {
             return array
@@ -37,9 +64,83 @@ fn extract_ignore_patterns_from_yaml(yaml_config: &Value) -> Vec<String> {
     Vec::new()
 }
 
+/// Resolves the config's optional top-level `target:` relative to the config file.
+///
+/// A template chooses its own `target:`, so one that is absolute or climbs out with `..`
+/// is refused unless `allow_escape` (`--allow-escape`) is set; `-o` sidesteps it entirely.
+fn config_target(yaml_doc: &Value, config_path: &Path, allow_escape: bool) -> Result<Option<PathBuf>, SkeletorError> {
+    let Some(target) = yaml_doc.get("target").and_then(Value::as_str) else {
+        return Ok(None);
+    };
+    let escapes = is_absolute_key(target) || target.split(['/', '\\']).any(|segment| segment == "..");
+    if escapes && !allow_escape {
+        return Err(SkeletorError::Config(format!(
+            "The config's target '{}' leads outside the config's directory; pass -o to choose the output directory, or --allow-escape to use it",
+            target
+        )));
+    }
+    let base = config_path.parent().unwrap_or_else(|| Path::new(""));
+    Ok(Some(base.join(target)))
+}
+
+/// Extract recorded stats (files, directories) from YAML if present
+fn extract_stats_from_yaml(yaml_config: &Value) -> Option<(usize, usize)> {
+    let stats = yaml_config.get("stats")?;
+    let files = stats.get("files")?.as_u64()? as usize;
+    let directories = stats.get("directories")?.as_u64()? as usize;
+    Some((files, directories))
+}
+
+/// Counts the (files, directories) the template defines, from its planned tasks
+/// before ignore patterns or `--skip-hidden` filter any out
+fn planned_counts(tasks: &[Task]) -> (usize, usize) {
+    tasks.iter().fold((0, 0), |(files, dirs), task| match task {
+        Task::File(_, _) => (files + 1, dirs),
+        Task::Dir(_) => (files, dirs + 1),
+        Task::Symlink(_, _) => (files, dirs),
+    })
+}
+
+/// Compares the recorded stats and the creation counts against the planned task
+/// counts, returning one message per divergence (empty when everything materialized).
+/// Files already holding the template's content count as materialized.
+fn stats_divergence_warnings(
+    recorded: Option<(usize, usize)>,
+    expected: (usize, usize),
+    result: &CreationResult,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let (expected_files, expected_dirs) = expected;
+
+    if let Some((recorded_files, recorded_dirs)) = recorded {
+        if recorded != Some(expected) {
+            warnings.push(format!(
+                "Recorded stats ({} files, {} directories) do not match the config tree ({} files, {} directories)",
+                recorded_files, recorded_dirs, expected_files, expected_dirs
+            ));
+        }
+    }
+
+    let files_materialized = result.files_created + result.files_unchanged;
+    if files_materialized != expected_files {
+        warnings.push(format!(
+            "Expected {} files but created {} ({} skipped)",
+            expected_files, files_materialized, result.files_skipped
+        ));
+    }
+
+    if result.dirs_created != expected_dirs {
+        warnings.push(format!(
+            "Expected {} directories but created {}",
+            expected_dirs, result.dirs_created
+        ));
+    }
+
+    warnings
+}
+
 /// Handles dry-run output display using the Reporter system for consistent formatting
-fn display_dry_run_output(tasks: &[Task], verbose: bool, binary_files: &[String], ignore_patterns: &[String]) {
-    let reporter = DefaultReporter::new();
+fn display_dry_run_output(reporter: &DefaultReporter, tasks: &[Task], verbose: bool, binary_files: &[String], ignore_patterns: &[String]) {
     reporter.dry_run_preview_comprehensive(tasks, verbose, binary_files, ignore_patterns, "applied");
 }
 
@@ -78,10 +179,8 @@ fn filter_tasks_by_ignore(
     tasks
         .iter()
         .filter_map(|task| {
-            let (path, is_dir) = match task {
-                Task::Dir(path) => (path, true),
-                Task::File(path, _) => (path, false),
-            };
+            let path = task.path();
+            let is_dir = matches!(task, Task::Dir(_));
 
             let relative = path
                 .strip_prefix(output_dir)
@@ -97,57 +196,393 @@ fn filter_tasks_by_ignore(
                 return None;
             }
 
-            Some(match task {
-                Task::Dir(path) => Task::Dir(path.clone()),
-                Task::File(path, content) => Task::File(path.clone(), content.clone()),
-            })
+            Some(task.clone())
         })
         .collect()
 }
 
+/// Renames the only top-level entry of `directories` to `name`, keeping its position.
+///
+/// Fails unless there is exactly one top-level entry and it is a directory.
+fn rename_root(directories: &Value, name: &str) -> Result<Value, SkeletorError> {
+    let mapping = directories.as_mapping();
+    let Some((old_name, root)) = mapping.filter(|mapping| mapping.len() == 1).and_then(|mapping| mapping.iter().next()) else {
+        return Err(SkeletorError::invalid_config_entry(
+            "directories",
+            format!(
+                "--root-name needs exactly one top-level directory to rename, found {} entries",
+                mapping.map_or(0, |mapping| mapping.len())
+            ),
+        ));
+    };
+    if !matches!(root, Value::Mapping(_)) || file_ref_path(root).is_some() {
+        return Err(SkeletorError::invalid_config_entry(
+            format!("directories.{}", old_name.as_str().unwrap_or_default()),
+            "--root-name can only rename a directory",
+        ));
+    }
+    if name.is_empty() || name.contains(['/', '\\']) || name == "." || name == ".." {
+        return Err(SkeletorError::Config(format!(
+            "--root-name '{}' must be a single directory name",
+            name
+        )));
+    }
+
+    let mut renamed = serde_yaml::Mapping::new();
+    renamed.insert(Value::String(name.to_string()), root.clone());
+    Ok(Value::Mapping(renamed))
+}
+
+/// Splits out tasks whose path (relative to the output dir) contains a dotfile or
+/// dot-directory component, returning the kept tasks and the skipped paths
+fn split_hidden_tasks(tasks: &[Task], output_dir: &Path) -> (Vec<Task>, Vec<String>) {
+    let mut kept = Vec::new();
+    let mut skipped = Vec::new();
+
+    for task in tasks {
+        let path = task.path();
+
+        let is_hidden = path
+            .strip_prefix(output_dir)
+            .unwrap_or(path)
+            .components()
+            .any(|component| match component {
+                std::path::Component::Normal(name) => name.to_string_lossy().starts_with('.'),
+                _ => false,
+            });
+
+        if is_hidden {
+            skipped.push(path.display().to_string());
+        } else {
+            kept.push(task.clone());
+        }
+    }
+
+    (kept, skipped)
+}
+
+fn display_paths(paths: &[PathBuf]) -> Vec<String> {
+    paths.iter().map(|path| path.display().to_string()).collect()
+}
+
+/// Stands in for the config file name with `--stdin`, so relative paths resolve against the working directory
+const STDIN_CONFIG_NAME: &str = "<stdin>";
+
 /// Parses CLI arguments and extracts apply-specific configuration
+#[derive(Debug)]
 struct ApplyConfig {
-    pub input_path: std::path::PathBuf,
-    pub output_dir: std::path::PathBuf,
+    /// Config files whose `directories` are merged left to right; the first, or a `<stdin>`
+    /// placeholder in the working directory with `--stdin`, supplies every other setting
+    pub input_paths: Vec<PathBuf>,
+    pub from_stdin: bool,
+    pub output_dir: PathBuf,
+    pub explicit_output: bool,
     pub overwrite: bool,
     pub dry_run: bool,
     pub verbose: bool,
+    pub verify_stats: bool,
+    pub fail_on_skip: bool,
+    pub strict: bool,
+    /// `--quiet`: report nothing but errors
+    pub quiet: bool,
+    /// Set the modification times recorded in `!file` nodes on the files written
+    pub preserve_mtime: bool,
+    pub on_local_edit: Option<OnLocalEdit>,
+    /// `--interactive`: ask before replacing each existing file
+    pub interactive: bool,
+    /// `--backup`: suffix of the copy each replaced file is saved to
+    pub backup: Option<String>,
+    pub update: bool,
+    pub parallel: bool,
+    pub skip_hidden: bool,
+    /// `--run-hooks`: run the config's post-apply commands, which are otherwise only listed
+    pub run_hooks: bool,
+    /// `--clean`: delete output files the template does not define
+    pub clean: bool,
+    /// `--fail-fast`: stop at the first entry that cannot be created
+    pub fail_fast: bool,
+    /// `--line-endings`: convert every written file's line endings
+    pub line_endings: LineEndings,
+    /// `--root-name`: new name for the template's single top-level directory
+    pub root_name: Option<String>,
+    /// `--allow-escape`: accept entries that resolve outside the output directory
+    pub allow_escape: bool,
+    /// `--templating`: render file contents with Tera
+    pub templating: bool,
+    /// `--context-file`: JSON object of extra data for `--templating`
+    pub context_file: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+    pub format: OutputFormat,
+    pub vars: Vec<String>,
+    /// `--env-vars`: unresolved placeholders fall back to environment variables
+    pub env_vars: bool,
 }
 
 impl ApplyConfig {
     fn from_matches(matches: &ArgMatches) -> Self {
+        // Relative paths resolve against --working-dir without changing the process CWD
+        let working_dir = working_dir_from_matches(matches);
         let output_dir = matches
             .get_one::<String>("output")
-            .map(std::path::PathBuf::from)
-            .unwrap_or_else(|| std::path::PathBuf::from("."));
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("."));
         
+        let from_stdin = matches.get_flag("stdin");
+        let input_paths = match matches.get_many::<String>("config") {
+            _ if from_stdin => vec![PathBuf::from(STDIN_CONFIG_NAME)],
+            Some(paths) => {
+                let template_dir = template_dir_from_matches(matches);
+                paths.map(|path| resolve_template_path(path, template_dir.as_deref())).collect()
+            }
+            None => vec![default_config_from_matches(matches)],
+        };
+
         Self {
-            input_path: default_file_path(matches.get_one::<String>("config")),
-            output_dir,
+            input_paths: input_paths
+                .into_iter()
+                .map(|path| resolve_path(working_dir.as_deref(), path))
+                .collect(),
+            from_stdin,
+            output_dir: resolve_path(working_dir.as_deref(), output_dir),
+            explicit_output: matches.get_one::<String>("output").is_some(),
             overwrite: *matches.get_one::<bool>("overwrite").unwrap_or(&false),
             dry_run: matches.get_flag("dry_run"),
             verbose: matches.get_flag("verbose"),
+            verify_stats: matches.get_flag("verify_stats"),
+            fail_on_skip: matches.get_flag("fail_on_skip"),
+            strict: strict_from_matches(matches),
+            quiet: quiet_from_matches(matches),
+            preserve_mtime: matches.get_flag("preserve_mtime"),
+            on_local_edit: matches
+                .get_one::<String>("on_local_edit")
+                .and_then(|mode| OnLocalEdit::parse(mode)),
+            interactive: matches.get_flag("interactive"),
+            backup: matches.get_one::<String>("backup").cloned(),
+            update: matches.get_flag("update"),
+            parallel: matches.get_flag("parallel"),
+            skip_hidden: matches.get_flag("skip_hidden"),
+            run_hooks: matches.get_flag("run_hooks"),
+            clean: matches.get_flag("clean"),
+            fail_fast: matches.get_flag("fail_fast"),
+            line_endings: matches
+                .get_one::<String>("line_endings")
+                .and_then(|style| LineEndings::parse(style))
+                .unwrap_or(LineEndings::Preserve),
+            root_name: matches.get_one::<String>("root_name").cloned(),
+            allow_escape: matches.get_flag("allow_escape"),
+            templating: matches.get_flag("templating"),
+            context_file: matches
+                .get_one::<String>("context_file")
+                .map(|path| resolve_path(working_dir.as_deref(), PathBuf::from(path))),
+            cache_dir: matches
+                .get_one::<String>("cache")
+                .map(|dir| resolve_path(working_dir.as_deref(), PathBuf::from(dir))),
+            format: matches
+                .get_one::<String>("format")
+                .and_then(|name| name.parse().ok())
+                .unwrap_or(OutputFormat::Pretty),
+            vars: matches
+                .get_many::<String>("var")
+                .map(|vals| vals.map(|v| v.to_string()).collect())
+                .unwrap_or_default(),
+            env_vars: matches.get_flag("env_vars"),
         }
     }
+
+    /// The first config, which supplies everything but the overlays' `directories`
+    fn input_path(&self) -> &Path {
+        &self.input_paths[0]
+    }
+}
+
+/// Deep-merges the `directories` of each overlay config into `directories`, left to right.
+///
+/// Each overlay's includes and file references are resolved against its own location.
+/// Returns every path an overlay replaced, with the file that supplied it.
+fn merge_overlays<'a>(
+    directories: &mut Value,
+    overlays: &'a [PathBuf],
+) -> Result<Vec<(String, &'a Path)>, SkeletorError> {
+    let mut overridden = Vec::new();
+    for path in overlays {
+        let content = crate::utils::read_file_to_string(path)?;
+        let mut yaml_doc = crate::utils::parse_config_string(&content, ConfigFormat::from_path(path))?;
+        resolve_includes(&mut yaml_doc, path)?;
+        let overlay = resolve_file_refs(&Value::Mapping(directories_mapping(&yaml_doc)?.clone()), config_dir(path))?;
+        overridden.extend(
+            deep_merge(directories, overlay)
+                .into_iter()
+                .map(|entry| (entry, path.as_path())),
+        );
+    }
+    Ok(overridden)
+}
+
+/// A progress bar for the task loop, when a non-verbose apply writes pretty output to a terminal
+#[cfg(feature = "progress-bar")]
+fn progress_reporter(config: &ApplyConfig) -> Option<crate::output::ProgressReporter> {
+    use std::io::IsTerminal;
+    let interactive = config.format == OutputFormat::Pretty && !config.quiet && std::io::stdout().is_terminal();
+    (interactive && !config.verbose).then(crate::output::ProgressReporter::new)
+}
+
+/// Without the `progress-bar` feature, the task loop stays silent unless `--verbose`
+#[cfg(not(feature = "progress-bar"))]
+fn progress_reporter(_config: &ApplyConfig) -> Option<SilentReporter> {
+    None
+}
+
+/// What an apply did, as data rather than printed output
+#[derive(Debug, Clone)]
+pub struct ApplySummary {
+    /// Counts, per-outcome file lists and duration
+    pub result: SimpleApplyResult,
+    /// `--verify-stats` mismatches between the config's `stats` and what was created
+    pub stats_warnings: Vec<String>,
+    /// Where the files went, after any `target:` in the config
+    pub output_dir: PathBuf,
+    /// The config's post-apply commands, not yet run
+    pub hooks: Vec<String>,
 }
 
 /// Runs the apply subcommand: reads the YAML config and creates files/directories.
 /// In dry-run mode, the tasks are printed without performing any filesystem changes.
 pub fn run_apply(matches: &ArgMatches) -> Result<(), SkeletorError> {
-    let config = ApplyConfig::from_matches(matches);
+    let mut config = ApplyConfig::from_matches(matches);
+    let Some(summary) = execute(&mut config)? else {
+        return Ok(());
+    };
+    let reporter = DefaultReporter::with_format(config.format).quiet(config.quiet);
+    let apply_result = &summary.result;
+    reporter.apply_complete(apply_result, config.verbose);
+
+    if !summary.stats_warnings.is_empty() {
+        for warning in &summary.stats_warnings {
+            reporter.warning(warning);
+        }
+        reporter.tip("Entries may have been skipped or filtered by ignore_patterns; use --verbose for details");
+    }
 
-    info!("Reading input file: {:?}", config.input_path);
+    if !apply_result.failed_files_list.is_empty() {
+        return Err(SkeletorError::FilesFailed {
+            count: apply_result.failed_files_list.len(),
+        });
+    }
+    if config.fail_on_skip && !config.overwrite && apply_result.files_skipped > 0 {
+        return Err(SkeletorError::FilesSkipped {
+            count: apply_result.files_skipped,
+        });
+    }
+    // Counted from the result, since skipped entries only reach the reporter
+    // as per-task warnings under --verbose
+    check_strict(config.strict, apply_result.files_skipped + summary.stats_warnings.len())?;
+
+    if !config.run_hooks {
+        if !summary.hooks.is_empty() {
+            reporter.tip(&format!(
+                "The template has {} post-apply hook(s), not run; review them and rerun with --run-hooks to run them",
+                summary.hooks.len()
+            ));
+        }
+        return Ok(());
+    }
+    run_hooks(&summary.hooks, &summary.output_dir, config.format == OutputFormat::Json, &reporter)
+}
+
+/// Does the work of [`run_apply`] and returns what happened instead of printing the summary,
+/// so embedders and tests can inspect it; `None` for a dry run. Entries that failed or were
+/// skipped are reported in the summary, not as errors, and the hooks are left to the caller.
+#[allow(dead_code)]
+pub fn execute_apply(matches: &ArgMatches) -> Result<Option<ApplySummary>, SkeletorError> {
+    execute(&mut ApplyConfig::from_matches(matches))
+}
+
+/// [`execute_apply`] with the arguments already parsed, so [`run_apply`] can read the
+/// same settings afterwards; a `target:` in the config replaces `config.output_dir`
+fn execute(config: &mut ApplyConfig) -> Result<Option<ApplySummary>, SkeletorError> {
+
+    info!("Reading input files: {:?}", config.input_paths);
     info!("Overwrite flag: {:?}", config.overwrite);
+    if let Some(suffix) = &config.backup {
+        if suffix.is_empty() || suffix.contains(std::path::is_separator) {
+            return Err(SkeletorError::Config(format!(
+                "--backup needs a suffix for the file name, such as .bak; got '{}'",
+                suffix
+            )));
+        }
+    }
+
+    let mut full_yaml_doc = if config.from_stdin {
+        let content = std::io::read_to_string(std::io::stdin())
+            .map_err(|e| SkeletorError::Config(format!("Failed to read config from stdin: {}", e)))?;
+        crate::utils::parse_yaml_string(&content)?
+    } else {
+        let content = crate::utils::read_file_to_string(config.input_path())?;
+        crate::utils::parse_config_string(&content, ConfigFormat::from_path(config.input_path()))?
+    };
+    resolve_includes(&mut full_yaml_doc, config.input_path())?;
+    if !config.explicit_output {
+        if let Some(target) = config_target(&full_yaml_doc, config.input_path(), config.allow_escape)? {
+            info!("Using output directory from config target: {:?}", target);
+            config.output_dir = target;
+        }
+    }
+    let yaml_config = directories_mapping(&full_yaml_doc)?;
+    let mut yaml_config = resolve_file_refs(
+        &Value::Mapping(yaml_config.clone()),
+        config_dir(config.input_path()),
+    )?;
+    let overridden = merge_overlays(&mut yaml_config, &config.input_paths[1..])?;
 
-    let full_yaml_doc: Value = crate::utils::read_yaml_file(&config.input_path)?;
-    let yaml_config = full_yaml_doc
-        .get("directories")
-        .and_then(Value::as_mapping)
-        .ok_or_else(|| SkeletorError::missing_config_key("directories"))?;
-    let yaml_config = Value::Mapping(yaml_config.clone());
+    // Placeholders are only substituted when variables are supplied (or the environment
+    // is), so literal `${...}` in snapshotted sources (shell, JS templates) applies unchanged
+    let variables = collect_variables(&full_yaml_doc, &config.vars)?;
+    let yaml_config = if variables.is_empty() && !config.env_vars {
+        yaml_config
+    } else {
+        substitute_variables_with_env(&yaml_config, &variables, config.env_vars)?
+    };
+    let yaml_config = if braces_enabled(&full_yaml_doc)? {
+        expand_braces(&yaml_config)?
+    } else {
+        yaml_config
+    };
+    let yaml_config = match &config.root_name {
+        Some(name) => rename_root(&yaml_config, name)?,
+        None => yaml_config,
+    };
+    if !config.allow_escape {
+        ensure_relative_keys(&yaml_config)?;
+    }
+    let hooks = post_apply_hooks(&full_yaml_doc)?;
+    let mut newline_policy = full_yaml_doc
+        .get("newline_policy")
+        .map(|policy| NewlinePolicy::from_yaml(policy, &config.output_dir))
+        .transpose()?;
+    if config.line_endings != LineEndings::Preserve {
+        let policy = newline_policy.unwrap_or_else(|| NewlinePolicy::new(&config.output_dir));
+        newline_policy = Some(policy.with_line_endings(config.line_endings));
+    }
 
     let start_time = Instant::now();
-    let tasks = traverse_structure(&config.output_dir, &yaml_config)?;
+    let reporter = DefaultReporter::with_format(config.format).quiet(config.quiet);
+    if config.verbose {
+        for (path, source) in &overridden {
+            reporter.operation_start("override", &format!("{} from {}", path, source.display()));
+        }
+    }
+    let tasks = if config.allow_escape {
+        traverse_structure_allowing_escape(&config.output_dir, &yaml_config)?
+    } else {
+        let tasks = traverse_structure(&config.output_dir, &yaml_config)?;
+        ensure_within_output(&config.output_dir, &tasks)?;
+        tasks
+    };
+    let tasks = if config.templating {
+        render_tasks(tasks, &template_context(&variables, config.context_file.as_deref())?)?
+    } else {
+        tasks
+    };
     
     // Extract binary files and ignore patterns from the full YAML document
     let binary_files = extract_binary_files_from_yaml(&full_yaml_doc);
@@ -158,46 +593,175 @@ pub fn run_apply(matches: &ArgMatches) -> Result<(), SkeletorError> {
 
     let ignore_matcher = build_ignore_matcher(&ignore_patterns, &config.output_dir)?;
     let filtered_tasks = filter_tasks_by_ignore(&tasks, &config.output_dir, ignore_matcher.as_ref());
+    let (filtered_tasks, hidden_skipped) = if config.skip_hidden {
+        split_hidden_tasks(&filtered_tasks, &config.output_dir)
+    } else {
+        (filtered_tasks, Vec::new())
+    };
 
-    if filtered_tasks.len() != tasks.len() {
+    if filtered_tasks.len() + hidden_skipped.len() != tasks.len() {
         info!(
             "Ignored {} task(s) via ignore patterns",
-            tasks.len().saturating_sub(filtered_tasks.len())
+            tasks.len().saturating_sub(filtered_tasks.len() + hidden_skipped.len())
         );
     }
+    if !hidden_skipped.is_empty() {
+        info!("Skipped {} hidden task(s) via --skip-hidden", hidden_skipped.len());
+    }
 
     if config.dry_run {
-        display_dry_run_output(&filtered_tasks, config.verbose, &binary_files, &ignore_patterns);
+        let cache = config.cache_dir.as_ref().map(|cache_dir| {
+            DryRunCache::new(
+                cache_dir,
+                config.input_path(),
+                // With includes resolved; overlays only contribute entries, covered by the tasks
+                &full_yaml_doc,
+                &config.output_dir,
+                // After substitution and rendering, so environment values, `!source` and
+                // `!file_ref` contents and the template context all count
+                &filtered_tasks,
+                // Every option, so any flag that could change the preview misses the cache
+                &format!("{:?}", config),
+                config.clean,
+            )
+        });
+
+        if let Some(cache) = cache.as_ref().filter(|cache| cache.is_fresh()) {
+            reporter.dry_run_unchanged(cache.entry_path());
+            return Ok(None);
+        }
+
+        display_dry_run_output(&reporter, &filtered_tasks, config.verbose, &binary_files, &ignore_patterns);
+        if config.clean {
+            let keep_matcher = load_keep_matcher(&config.output_dir)?;
+            let stale = stale_files(&config.output_dir, &tasks, ignore_matcher.as_ref(), keep_matcher.as_ref())?;
+            reporter.clean_preview(&display_paths(&stale), true);
+        }
+        reporter.hooks_preview(&hooks, config.run_hooks);
+
+        if let Some(cache) = cache {
+            cache.store()?;
+        }
+        return Ok(None);
+    }
+
+    if config.verbose {
+        reporter.verbose_operation_preview(&filtered_tasks);
     } else {
-        let reporter = DefaultReporter::new();
-        
-        if config.verbose {
-            reporter.verbose_operation_preview(&filtered_tasks);
-        } else {
-            reporter.operation_start("apply", &format!("Creating {} tasks", filtered_tasks.len()));
+        reporter.operation_start("apply", &format!("Creating {} tasks", filtered_tasks.len()));
+    }
+    
+    ensure_output_root(&config.output_dir)?;
+    let keep_matcher = load_keep_matcher(&config.output_dir)?;
+    let create = if config.parallel {
+        create_files_and_directories_parallel
+    } else {
+        create_files_and_directories_with
+    };
+
+    let mut manifest = match config.on_local_edit {
+        Some(_) => Some(AppliedManifest::load(&config.output_dir)?),
+        None => None,
+    };
+    let mut declined = Vec::new();
+    let plan = match (&manifest, config.on_local_edit) {
+        (Some(manifest), Some(on_local_edit)) => {
+            manifest.plan(filtered_tasks.clone(), on_local_edit, &mut ask_on_terminal)
         }
-        
-        let creation_result = create_files_and_directories(&filtered_tasks, config.overwrite)?;
-        let duration = start_time.elapsed();
-        
-        let apply_result = SimpleApplyResult::with_skipped_and_overwritten(
-            creation_result.files_created,
-            creation_result.dirs_created,
-            duration,
-            filtered_tasks.len(),
-            creation_result.files_skipped,
-            creation_result.skipped_files_list,
-            creation_result.files_overwritten,
-            creation_result.overwritten_files_list,
-        );
-        reporter.apply_complete(&apply_result, config.verbose);
+        // Asked before anything is written, so quitting leaves the output untouched
+        _ if config.interactive => {
+            let confirmed = confirm_overwrites(filtered_tasks.clone(), &mut ask_overwrite_on_terminal)?;
+            declined = confirmed.declined;
+            LocalEditPlan {
+                tasks: confirmed.tasks,
+                upgrades: confirmed.overwrites,
+                kept: Vec::new(),
+            }
+        }
+        _ => LocalEditPlan {
+            tasks: filtered_tasks.clone(),
+            upgrades: Vec::new(),
+            kept: Vec::new(),
+        },
+    };
+
+    let mut creation_result = {
+        // Per-task lines would drown out the summary unless asked for; a progress
+        // bar is cleared when it goes out of scope, before the summary prints
+        let progress_bar = progress_reporter(config);
+        let task_reporter: &dyn Reporter = match &progress_bar {
+            _ if config.verbose => &reporter,
+            Some(bar) => bar,
+            None => &SilentReporter,
+        };
+
+        let options = FileWriteOptions {
+            overwrite: config.overwrite,
+            update: config.update,
+            atomic: true,
+            fail_fast: config.fail_fast,
+            keep: keep_matcher.as_ref(),
+            newline: newline_policy.as_ref(),
+            backup: config.backup.as_deref(),
+        };
+        let mut result = create(&plan.tasks, &options, task_reporter)?;
+        if !plan.upgrades.is_empty() {
+            let upgrades = FileWriteOptions { overwrite: true, ..options };
+            result.merge(create(&plan.upgrades, &upgrades, task_reporter)?);
+        }
+        result
+    };
+    creation_result.files_preserved += plan.kept.len();
+    creation_result.preserved_files_list.extend(plan.kept);
+    creation_result.files_skipped += declined.len();
+    creation_result.skipped_files_list.extend(declined);
+    if let Some(manifest) = manifest.as_mut() {
+        manifest.record(&filtered_tasks, &creation_result)?;
     }
+    if config.preserve_mtime {
+        let restored = restore_mtimes(&config.output_dir, &yaml_config, &creation_result)?;
+        info!("Restored {} modification time(s)", restored);
+    }
+    if config.clean {
+        // Every template path counts as defined, including ignored and hidden ones left unwritten
+        let stale = stale_files(&config.output_dir, &tasks, ignore_matcher.as_ref(), keep_matcher.as_ref())?;
+        reporter.clean_preview(&display_paths(&stale), false);
+        remove_stale(&stale)?;
+        info!("Removed {} file(s) not in the template", stale.len());
+    }
+    let duration = start_time.elapsed();
 
-    Ok(())
+    let stats_warnings = if config.verify_stats {
+        stats_divergence_warnings(
+            extract_stats_from_yaml(&full_yaml_doc),
+            planned_counts(&tasks),
+            &creation_result,
+        )
+    } else {
+        Vec::new()
+    };
+    
+    let mut apply_result = creation_result.into_apply_result(duration, filtered_tasks.len());
+    apply_result.hidden_skipped_list = hidden_skipped;
+    apply_result.binary_files_list = binary_files
+        .into_iter()
+        .filter(|file| {
+            let path = config.output_dir.join(file);
+            filtered_tasks.iter().any(|task| matches!(task, Task::File(p, _) if *p == path))
+        })
+        .collect();
+
+    Ok(Some(ApplySummary {
+        result: apply_result,
+        stats_warnings,
+        output_dir: config.output_dir.clone(),
+        hooks,
+    }))
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::output::OutputFormat;
     use crate::test_utils::helpers::*;
 
     #[test]
@@ -340,40 +904,31 @@ mod tests {
     #[test]
     fn test_apply_with_verbose_flag() {
         let fs = TestFileSystem::new();
-        
-        let _guard = cwd_lock();
-        // CRITICAL SAFETY: Change to temp directory to avoid overwriting project files
-        let original_dir = std::env::current_dir().expect("Failed to get current directory");
-        std::env::set_current_dir(&fs.root_path).expect("Failed to change to temp directory");
-        
         let config_file = fs.create_test_config("verbose.yml");
         
+        // --working-dir keeps the default output inside the temp directory
         let args = vec![
-            config_file.to_str().unwrap(),
+            "verbose.yml",
             "--verbose",
+            "--working-dir",
+            fs.root_path.to_str().unwrap(),
         ];
         
         if let Some(sub_m) = create_apply_matches(args) {
             assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
         }
-        
-        // CRITICAL SAFETY: Restore original directory
-        std::env::set_current_dir(original_dir).expect("Failed to restore original directory");
+        assert!(config_file.exists());
+        assert!(fs.root_path.join("test_output/hello.rs").is_file());
     }
 
     #[test]
     fn test_apply_with_overwrite_flag() {
         let fs = TestFileSystem::new();
-        
-        let _guard = cwd_lock();
-        // CRITICAL SAFETY: Change to temp directory to avoid overwriting project files
-        let original_dir = std::env::current_dir().expect("Failed to get current directory");
-        std::env::set_current_dir(&fs.root_path).expect("Failed to change to temp directory");
-        
         let config_file = fs.create_test_config("overwrite.yml");
+        let working_dir = fs.root_path.to_str().unwrap();
         
         // Run once to create files
-        let args = vec![config_file.to_str().unwrap()];
+        let args = vec![config_file.to_str().unwrap(), "--working-dir", working_dir];
         if let Some(sub_m) = create_apply_matches(args) {
             assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
         }
@@ -382,16 +937,13 @@ mod tests {
         let args = vec![
             config_file.to_str().unwrap(),
             "--overwrite",
+            "--working-dir",
+            working_dir,
         ];
         
         if let Some(sub_m) = create_apply_matches(args) {
             assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
         }
-        
-        // CRITICAL SAFETY: Restore original directory BEFORE fs goes out of scope
-        std::env::set_current_dir(&original_dir).expect("Failed to restore original directory");
-        
-        // fs will be dropped here, but we've already restored the directory
     }
 
     #[test]
@@ -404,20 +956,35 @@ mod tests {
         
         if let Some(sub_m) = create_apply_matches(args) {
             let config = super::ApplyConfig::from_matches(&sub_m);
-            assert_eq!(config.input_path.to_str().unwrap(), "test.yml");
+            assert_eq!(config.input_path().to_str().unwrap(), "test.yml");
             assert!(config.overwrite);
             assert!(config.verbose);
             assert!(!config.dry_run);
         }
     }
 
+    #[test]
+    fn test_apply_config_from_stdin() {
+        let sub_m = create_apply_matches(vec!["--stdin", "--working-dir", "/tmp/project"])
+            .expect("Apply subcommand not found");
+        let config = super::ApplyConfig::from_matches(&sub_m);
+        assert!(config.from_stdin);
+        // Relative target: and !file_ref paths resolve against the working dir
+        assert_eq!(config.input_path(), std::path::Path::new("/tmp/project").join(super::STDIN_CONFIG_NAME));
+
+        let error = crate::build_cli()
+            .try_get_matches_from(["skeletor", "apply", "template.yml", "--stdin"])
+            .unwrap_err();
+        assert_eq!(error.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
     #[test]
     fn test_apply_config_defaults() {
         let args = vec!["basic.yml"];
         
         if let Some(sub_m) = create_apply_matches(args) {
             let config = super::ApplyConfig::from_matches(&sub_m);
-            assert_eq!(config.input_path.to_str().unwrap(), "basic.yml");
+            assert_eq!(config.input_path().to_str().unwrap(), "basic.yml");
             assert!(!config.overwrite);
             assert!(!config.verbose);
             assert!(!config.dry_run);
@@ -484,6 +1051,711 @@ ignore_patterns:
         assert!(!output_dir.join("root/ignored.txt").exists());
     }
 
+    #[test]
+    fn test_stats_divergence_warnings() {
+        use crate::tasks::CreationResult;
+
+        let mut result = CreationResult::new();
+        result.files_created = 2;
+        result.dirs_created = 1;
+
+        // Everything materialized and matches the recorded stats
+        assert!(super::stats_divergence_warnings(Some((2, 1)), (2, 1), &result).is_empty());
+        assert!(super::stats_divergence_warnings(None, (2, 1), &result).is_empty());
+
+        // Recorded stats drifted from the config tree
+        let warnings = super::stats_divergence_warnings(Some((5, 1)), (2, 1), &result);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Recorded stats"));
+
+        // A file was skipped so fewer were created than expected
+        result.files_created = 1;
+        result.files_skipped = 1;
+        let warnings = super::stats_divergence_warnings(Some((2, 1)), (2, 1), &result);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("1 skipped"));
+
+        // A file already holding the template's content was materialized, not skipped
+        result.files_skipped = 0;
+        result.files_unchanged = 1;
+        assert!(super::stats_divergence_warnings(Some((2, 1)), (2, 1), &result).is_empty());
+    }
+
+    #[test]
+    fn test_planned_counts_cover_every_template_entry() {
+        use crate::tasks::Task;
+        use std::path::PathBuf;
+
+        let tasks = vec![
+            Task::Dir(PathBuf::from("out/src")),
+            Task::File(PathBuf::from("out/src/main.rs"), String::new()),
+            Task::File(PathBuf::from("out/README.md"), String::new()),
+            Task::Symlink(PathBuf::from("out/link"), "README.md".to_string()),
+        ];
+        assert_eq!(super::planned_counts(&tasks), (2, 1));
+    }
+
+    #[test]
+    fn test_verify_stats_compares_against_planned_tasks() {
+        let fs = TestFileSystem::new();
+        let output_dir = fs.path("output");
+        let config_file = fs.create_config_from_content(
+            "verify.yml",
+            "directories:\n  root:\n    keep.txt: \"keep\"\n    ignored.txt: \"ignore\"\nstats:\n  files: 2\n  directories: 1\nignore_patterns:\n  - \"root/ignored.txt\"\n",
+        );
+        let args = vec![config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap(), "--verify-stats"];
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+
+        // The recorded stats agree with the template; only the ignored file went missing
+        let summary = crate::apply::execute_apply(&sub_m).unwrap().expect("not a dry run");
+        assert_eq!(summary.stats_warnings, vec!["Expected 2 files but created 1 (0 skipped)".to_string()]);
+
+        // Updating over identical files leaves them as they are, which is not a divergence of its own
+        let args = vec![config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap(), "--verify-stats", "--update"];
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        let summary = crate::apply::execute_apply(&sub_m).unwrap().unwrap();
+        assert_eq!(summary.stats_warnings, vec!["Expected 2 files but created 1 (0 skipped)".to_string()]);
+    }
+
+    #[test]
+    fn test_apply_with_verify_stats() {
+        let fs = TestFileSystem::new();
+        let output_dir = fs.path("output");
+        let config_content = r#"
+directories:
+  root:
+    keep.txt: "keep"
+    ignored.txt: "ignore"
+stats:
+  files: 2
+  directories: 1
+ignore_patterns:
+  - "root/ignored.txt"
+"#;
+        let config_file = fs.create_config_from_content("verify.yml", config_content);
+
+        let args = vec![
+            config_file.to_str().unwrap(),
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--verify-stats",
+        ];
+
+        if let Some(sub_m) = create_apply_matches(args) {
+            assert!(super::ApplyConfig::from_matches(&sub_m).verify_stats);
+            // Divergence only warns; the apply itself still succeeds
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        }
+
+        assert!(output_dir.join("root/keep.txt").exists());
+    }
+
+    #[test]
+    fn test_split_hidden_tasks() {
+        use crate::tasks::Task;
+        use std::path::{Path, PathBuf};
+
+        let output_dir = Path::new("out");
+        let tasks = vec![
+            Task::Dir(PathBuf::from("out/root")),
+            Task::File(PathBuf::from("out/root/.gitignore"), "target/".to_string()),
+            Task::Dir(PathBuf::from("out/root/.github")),
+            Task::File(PathBuf::from("out/root/.github/ci.yml"), "".to_string()),
+            Task::File(PathBuf::from("out/root/main.rs"), "".to_string()),
+        ];
+
+        let (kept, skipped) = super::split_hidden_tasks(&tasks, output_dir);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(skipped.len(), 3);
+        assert!(skipped.iter().any(|p| p.ends_with(".gitignore")));
+    }
+
+    #[test]
+    fn test_apply_with_skip_hidden() {
+        let fs = TestFileSystem::new();
+        let output_dir = fs.path("output");
+        let config_content = r#"
+directories:
+  root:
+    .gitignore: "target/"
+    main.rs: "fn main() {}"
+"#;
+        let config_file = fs.create_config_from_content("hidden.yml", config_content);
+
+        let args = vec![
+            config_file.to_str().unwrap(),
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--skip-hidden",
+        ];
+
+        if let Some(sub_m) = create_apply_matches(args) {
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        }
+
+        assert!(output_dir.join("root/main.rs").exists());
+        assert!(!output_dir.join("root/.gitignore").exists());
+    }
+
+    #[test]
+    fn test_execute_apply_returns_skipped_and_overwritten_files() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_test_config("summary.yml");
+        let output = fs.path("output");
+        let hello = output.join("test_output/hello.rs");
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap()];
+
+        let sub_m = create_apply_matches(args.clone()).expect("Apply subcommand not found");
+        let summary = crate::apply::execute_apply(&sub_m).unwrap().expect("not a dry run");
+        assert_eq!((summary.result.files_created, summary.result.dirs_created), (3, 2));
+        assert_eq!(summary.output_dir, output);
+
+        // Without --overwrite every existing file is skipped, which is not an error here
+        let sub_m = create_apply_matches(args.clone()).expect("Apply subcommand not found");
+        let summary = crate::apply::execute_apply(&sub_m).unwrap().unwrap();
+        assert_eq!(summary.result.files_created, 0);
+        assert_eq!(summary.result.files_skipped, 3);
+        assert!(summary.result.skipped_files_list.contains(&hello.display().to_string()));
+
+        fs.create_file("output/test_output/hello.rs", "// edited");
+        let mut args = args;
+        args.push("--overwrite");
+        let sub_m = create_apply_matches(args.clone()).expect("Apply subcommand not found");
+        let summary = crate::apply::execute_apply(&sub_m).unwrap().unwrap();
+        assert_eq!(summary.result.files_skipped, 0);
+        assert!(summary.result.overwritten_files_list.contains(&hello.display().to_string()));
+        assert!(summary.result.failed_files_list.is_empty());
+        assert_file_content(&hello, "fn main() {\n    println!(\"Hello, world!\");\n}\n");
+
+        // A dry run changes nothing, so there is no summary
+        args.push("--dry-run");
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        assert!(crate::apply::execute_apply(&sub_m).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_apply_backup_saves_each_replaced_file() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_test_config("backup.yml");
+        let output = fs.path("output");
+        let hello = output.join("test_output/hello.rs");
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap()];
+        let sub_m = create_apply_matches(args.clone()).expect("Apply subcommand not found");
+        crate::apply::execute_apply(&sub_m).unwrap();
+        fs.create_file("output/test_output/hello.rs", "// edited");
+        std::fs::remove_file(output.join("test_files/sample.rs")).unwrap();
+
+        let mut args = args;
+        args.extend(["--overwrite", "--backup"]);
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        let summary = crate::apply::execute_apply(&sub_m).unwrap().unwrap();
+
+        let backup = output.join("test_output/hello.rs.bak");
+        assert_file_content(&backup, "// edited");
+        assert_file_content(&hello, "fn main() {\n    println!(\"Hello, world!\");\n}\n");
+        assert!(summary
+            .result
+            .overwritten_files_list
+            .contains(&format!("{} (backup: {})", hello.display(), backup.display())));
+        // A file the run creates afresh has nothing to back up
+        assert!(output.join("test_files/sample.rs").exists());
+        assert!(!output.join("test_files/sample.rs.bak").exists());
+
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap(), "--overwrite", "--backup=/x"];
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        assert!(matches!(crate::apply::execute_apply(&sub_m), Err(crate::errors::SkeletorError::Config(_))));
+    }
+
+    #[test]
+    fn test_apply_interactive_keeps_files_without_a_terminal() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_test_config("interactive.yml");
+        let output = fs.path("output");
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap()];
+        let sub_m = create_apply_matches(args.clone()).expect("Apply subcommand not found");
+        assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        let edited = fs.create_file("output/test_output/hello.rs", "// edited");
+
+        // Test stdin is not a terminal, so the prompt answers no even with --overwrite
+        let mut args = args;
+        args.extend(["--interactive", "--overwrite"]);
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        let summary = crate::apply::execute_apply(&sub_m).unwrap().unwrap();
+        assert_eq!(summary.result.skipped_files_list, vec![edited.display().to_string()]);
+        assert_eq!(summary.result.files_overwritten, 2);
+        assert_file_content(&edited, "// edited");
+    }
+
+    #[test]
+    fn test_apply_fail_on_skip() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_test_config("skip.yml");
+        let output_dir = fs.path("output");
+        let output = output_dir.to_str().unwrap();
+
+        let args = vec![config_file.to_str().unwrap(), "-o", output, "--fail-on-skip"];
+        if let Some(sub_m) = create_apply_matches(args.clone()) {
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        }
+
+        // Second run skips every existing file and reports it with exit code 3
+        if let Some(sub_m) = create_apply_matches(args) {
+            let error = crate::apply::run_apply(&sub_m).unwrap_err();
+            assert!(matches!(error, crate::errors::SkeletorError::FilesSkipped { .. }));
+            assert_eq!(error.exit_code(), 3);
+        }
+
+        // --overwrite disables the check
+        let args = vec![config_file.to_str().unwrap(), "-o", output, "--fail-on-skip", "--overwrite"];
+        if let Some(sub_m) = create_apply_matches(args) {
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        }
+    }
+
+    #[test]
+    fn test_apply_strict_fails_on_skipped_files() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_test_config("strict.yml");
+        let output = fs.path("output");
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap(), "--strict"];
+
+        let sub_m = create_apply_matches(args.clone()).expect("Apply subcommand not found");
+        assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+
+        // Skipped files are only warnings, which --strict turns into a failure
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        let error = crate::apply::run_apply(&sub_m).unwrap_err();
+        assert!(matches!(error, crate::errors::SkeletorError::StrictWarnings { count } if count > 0));
+        assert_eq!(error.exit_code(), 1);
+    }
+
+    #[test]
+    fn test_apply_fails_when_entries_cannot_be_created() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_file("blocked.yml", "directories:\n  src:\n    main.rs: fn main() {}\n  README.md: hi\n");
+        fs.create_file("output/src", "a file where a directory belongs");
+        let output = fs.path("output");
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap()];
+
+        // The rest is still created, but the apply reports the failures
+        let sub_m = create_apply_matches(args.clone()).expect("Apply subcommand not found");
+        let error = crate::apply::run_apply(&sub_m).unwrap_err();
+        assert!(matches!(error, crate::errors::SkeletorError::FilesFailed { count: 2 }));
+        assert_file_content(output.join("README.md"), "hi");
+
+        let mut args = args;
+        args.push("--fail-fast");
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        let error = crate::apply::run_apply(&sub_m).unwrap_err();
+        assert!(matches!(error, crate::errors::SkeletorError::TaskFailed { ref path, .. } if path.ends_with("src")));
+    }
+
+    #[test]
+    fn test_apply_converts_line_endings() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_file(
+            "crlf.yml",
+            "directories:\n  run.sh: \"echo one\\r\\necho two\\r\\n\"\n  data.bin: \"a\\r\\n\\0b\"\n",
+        );
+        let output = fs.path("output");
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap(), "--line-endings", "lf"];
+
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        crate::apply::run_apply(&sub_m).unwrap();
+        assert_file_content(output.join("run.sh"), "echo one\necho two\n");
+        assert_file_content(output.join("data.bin"), "a\r\n\0b");
+    }
+
+    #[test]
+    fn test_apply_with_root_name() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_file("project.yml", "directories:\n  old-name:\n    src:\n      main.rs: fn main() {}\n");
+        let output = fs.path("output");
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap(), "--root-name", "new-name"];
+
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        crate::apply::run_apply(&sub_m).unwrap();
+        assert_file_content(output.join("new-name/src/main.rs"), "fn main() {}");
+        assert!(!output.join("old-name").exists());
+
+        let two_roots: serde_yaml::Value = serde_yaml::from_str("a: {}\nb: {}\n").unwrap();
+        assert!(matches!(
+            crate::apply::rename_root(&two_roots, "new-name"),
+            Err(crate::errors::SkeletorError::InvalidConfigEntry { path, .. }) if path == "directories"
+        ));
+        let one_file: serde_yaml::Value = serde_yaml::from_str("README.md: hi\n").unwrap();
+        assert!(crate::apply::rename_root(&one_file, "new-name").is_err());
+        let one_dir: serde_yaml::Value = serde_yaml::from_str("old: {}\n").unwrap();
+        assert!(crate::apply::rename_root(&one_dir, "../escape").is_err());
+    }
+
+    #[test]
+    fn test_apply_rejects_entries_escaping_the_output_dir() {
+        let fs = TestFileSystem::new();
+        let output = fs.path("project");
+        let outside = fs.path("outside.txt");
+        let absolute = fs.path("elsewhere/abs.txt");
+        let config_file = fs.create_file(
+            "escape.yml",
+            &format!("directories:\n  ../outside.txt: parent\n  {:?}: absolute\n", absolute.to_str().unwrap()),
+        );
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap()];
+
+        let sub_m = create_apply_matches(args.clone()).expect("Apply subcommand not found");
+        match crate::apply::run_apply(&sub_m) {
+            Err(crate::errors::SkeletorError::Config(message)) => assert!(message.contains("absolute path"), "{}", message),
+            other => panic!("Expected the absolute key to be rejected, got {:?}", other),
+        }
+        assert!(!outside.exists());
+
+        let parent_only = fs.create_file("parent.yml", "directories:\n  ../outside.txt: parent\n");
+        let sub_m = create_apply_matches(vec![parent_only.to_str().unwrap(), "-o", output.to_str().unwrap()])
+            .expect("Apply subcommand not found");
+        assert!(matches!(
+            crate::apply::run_apply(&sub_m),
+            Err(crate::errors::SkeletorError::InvalidPath { .. })
+        ));
+        assert!(!outside.exists());
+
+        let mut allowed = args;
+        allowed.push("--allow-escape");
+        let sub_m = create_apply_matches(allowed).expect("Apply subcommand not found");
+        crate::apply::run_apply(&sub_m).unwrap();
+        assert_file_content(&outside, "parent");
+        assert_file_content(&absolute, "absolute");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_apply_rejects_paths_under_symlinks_leading_outside() {
+        let fs = TestFileSystem::new();
+        let output = fs.path("project");
+        std::fs::create_dir_all(fs.path("shared")).unwrap();
+        std::fs::create_dir_all(&output).unwrap();
+        std::os::unix::fs::symlink(fs.path("shared"), output.join("linked")).unwrap();
+        let config_file = fs.create_file("linked.yml", "directories:\n  linked:\n    secret.txt: leaked\n  ok.txt: fine\n");
+        let args = vec![config_file.to_str().unwrap(), "-o", output.to_str().unwrap()];
+
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        match crate::apply::run_apply(&sub_m) {
+            Err(crate::errors::SkeletorError::Config(message)) => assert!(message.starts_with("'linked' resolves to"), "{}", message),
+            other => panic!("Expected an escape error, got {:?}", other),
+        }
+        assert!(!fs.path("shared/secret.txt").exists());
+        assert!(!output.join("ok.txt").exists());
+    }
+
+    #[test]
+    fn test_apply_templating_renders_contents_when_built_with_the_feature() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_file(
+            "modules.yml",
+            "directories:\n  src:\n    lib.rs: \"// {{ crate_name }}\\n{% for m in modules %}pub mod {{ m }};\\n{% endfor %}\"\n",
+        );
+        let data = fs.create_file("data.json", r#"{"modules": ["auth", "billing"]}"#);
+        let output = fs.path("output");
+        let args = vec![
+            config_file.to_str().unwrap(),
+            "-o",
+            output.to_str().unwrap(),
+            "--templating",
+            "--context-file",
+            data.to_str().unwrap(),
+            "--var",
+            "crate_name=demo",
+        ];
+
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        let result = crate::apply::run_apply(&sub_m);
+        if cfg!(feature = "templating") {
+            result.unwrap();
+            assert_file_content(output.join("src/lib.rs"), "// demo\npub mod auth;\npub mod billing;\n");
+        } else {
+            assert!(matches!(result, Err(crate::errors::SkeletorError::Config(message)) if message.contains("templating")));
+            assert!(!output.join("src/lib.rs").exists());
+        }
+    }
+
+    #[test]
+    fn test_apply_merges_overlay_configs_left_to_right() {
+        let fs = TestFileSystem::new();
+        let base = fs.create_file(
+            "base.yml",
+            "target: ./from-base\ndirectories:\n  src:\n    main.rs: base main\n    lib.rs: base lib\n  README.md: base readme\n",
+        );
+        let overlay = fs.create_file("overlays/rust.yml", "directories:\n  src:\n    main.rs: overlay main\n  LICENSE: !source ./LICENSE.txt\n");
+        fs.create_file("overlays/LICENSE.txt", "MIT");
+        let last = fs.create_file("last.yml", "directories:\n  README.md: last readme\n");
+
+        let args = vec![base.to_str().unwrap(), overlay.to_str().unwrap(), last.to_str().unwrap(), "--verbose"];
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        crate::apply::run_apply(&sub_m).unwrap();
+
+        // Settings such as `target:` come from the first config
+        let output = fs.path("from-base");
+        assert_file_content(output.join("src/main.rs"), "overlay main");
+        assert_file_content(output.join("src/lib.rs"), "base lib");
+        assert_file_content(output.join("README.md"), "last readme");
+        assert_file_content(output.join("LICENSE"), "MIT");
+
+        let mut directories: serde_yaml::Value = serde_yaml::from_str("src:\n  main.rs: base\n").unwrap();
+        let overlays = [overlay.clone()];
+        let overridden = super::merge_overlays(&mut directories, &overlays).unwrap();
+        assert_eq!(overridden, vec![("src/main.rs".to_string(), overlay.as_path())]);
+    }
+
+    #[test]
+    fn test_apply_uses_config_target() {
+        let fs = TestFileSystem::new();
+        let config_content = r#"
+target: ./generated
+directories:
+  src:
+    main.rs: "fn main() {}"
+"#;
+        let config_file = fs.create_config_from_content("templates/target.yml", config_content);
+
+        // Without --output, files land in `target` relative to the config file
+        let args = vec![config_file.to_str().unwrap()];
+        if let Some(sub_m) = create_apply_matches(args) {
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        }
+        assert!(fs.path("templates/generated/src/main.rs").exists());
+
+        // An explicit --output always wins
+        let output_dir = fs.path("explicit");
+        let args = vec![config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap()];
+        if let Some(sub_m) = create_apply_matches(args) {
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        }
+        assert!(output_dir.join("src/main.rs").exists());
+    }
+
+    #[test]
+    fn test_apply_rejects_config_target_outside_config_dir() {
+        let fs = TestFileSystem::new();
+        for target in ["../../escaped", "/tmp/skeletor-escaped"] {
+            let config_content = format!("target: {}\ndirectories:\n  main.rs: \"fn main() {{}}\"\n", target);
+            let config_file = fs.create_config_from_content("templates/deep/escape.yml", &config_content);
+
+            let sub_m = create_apply_matches(vec![config_file.to_str().unwrap()]).expect("Apply subcommand not found");
+            assert!(
+                matches!(crate::apply::run_apply(&sub_m), Err(crate::errors::SkeletorError::Config(message)) if message.contains("--allow-escape")),
+                "{} should be refused",
+                target
+            );
+            assert!(!fs.path("escaped").exists());
+
+            // -o takes precedence over the target, however it is written
+            let output_dir = fs.path("explicit");
+            let sub_m = create_apply_matches(vec![config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap()])
+                .expect("Apply subcommand not found");
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+            assert!(output_dir.join("main.rs").exists());
+        }
+
+        // Opting in uses the target as written
+        let config_file =
+            fs.create_config_from_content("templates/deep/escape.yml", "target: ../../escaped\ndirectories:\n  main.rs: x\n");
+        let sub_m = create_apply_matches(vec![config_file.to_str().unwrap(), "--allow-escape"]).expect("Apply subcommand not found");
+        assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        assert!(fs.path("escaped/main.rs").exists());
+    }
+
+    #[test]
+    fn test_apply_with_variables() {
+        let fs = TestFileSystem::new();
+        let output_dir = fs.path("output");
+        let config_content = r#"
+variables:
+  project_name: from-config
+directories:
+  ${module}:
+    mod.rs: "// ${project_name}"
+"#;
+        let config_file = fs.create_config_from_content("vars.yml", config_content);
+
+        // --var overrides config variables and fills in the rest
+        let args = vec![
+            config_file.to_str().unwrap(),
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--var",
+            "module=parser",
+            "--var",
+            "project_name=demo",
+        ];
+        if let Some(sub_m) = create_apply_matches(args) {
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        }
+        assert_file_content(output_dir.join("parser/mod.rs"), "// demo");
+
+        // A missing variable fails before anything is written
+        let missing_dir = fs.path("missing");
+        let args = vec![config_file.to_str().unwrap(), "-o", missing_dir.to_str().unwrap()];
+        if let Some(sub_m) = create_apply_matches(args) {
+            match crate::apply::run_apply(&sub_m) {
+                Err(crate::errors::SkeletorError::Config(message)) => assert!(message.contains("module")),
+                other => panic!("Expected Config error, got {:?}", other),
+            }
+        }
+        assert!(!missing_dir.exists());
+    }
+
+    #[test]
+    fn test_apply_dry_run_with_cache() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_test_config("cached.yml");
+        let cache_dir = fs.path("cache");
+        let output_dir = fs.path("output");
+
+        let args = vec![
+            config_file.to_str().unwrap(),
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--dry-run",
+            "--cache",
+            cache_dir.to_str().unwrap(),
+        ];
+
+        // First run populates the cache, second run short-circuits
+        for _ in 0..2 {
+            if let Some(sub_m) = create_apply_matches(args.clone()) {
+                assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+            }
+        }
+
+        let entries: Vec<_> = std::fs::read_dir(&cache_dir).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+        assert!(!output_dir.exists());
+    }
+
+    #[test]
+    fn test_apply_expands_braces_when_config_opts_in() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_config_from_content(
+            "braces.yml",
+            "expand_braces: true\ndirectories:\n  \"{src,tests}\":\n    \"mod_{1..2}.rs\": \"// module\"\n",
+        );
+        let output_dir = fs.path("output");
+        let sub_m = create_apply_matches(vec![config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap()])
+            .expect("Apply subcommand not found");
+        crate::apply::run_apply(&sub_m).unwrap();
+        for path in ["src/mod_1.rs", "src/mod_2.rs", "tests/mod_1.rs", "tests/mod_2.rs"] {
+            assert_file_content(output_dir.join(path), "// module");
+        }
+    }
+
+    #[test]
+    fn test_apply_dry_run_cache_sees_included_files() {
+        let fs = TestFileSystem::new();
+        fs.create_config_from_content("base.yml", "directories:\n  shared.txt: v1\n");
+        let config_file = fs.create_config_from_content("cached.yml", "include: [base.yml]\ndirectories:\n  own.txt: own\n");
+        let cache_dir = fs.path("cache");
+        let output_dir = fs.path("output");
+        let args = vec![
+            config_file.to_str().unwrap(),
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--dry-run",
+            "--cache",
+            cache_dir.to_str().unwrap(),
+        ];
+        let fingerprint = || {
+            let sub_m = create_apply_matches(args.clone()).expect("Apply subcommand not found");
+            crate::apply::run_apply(&sub_m).unwrap();
+            let entry = std::fs::read_dir(&cache_dir).unwrap().next().unwrap().unwrap().path();
+            std::fs::read_to_string(entry).unwrap()
+        };
+
+        let first = fingerprint();
+        assert_eq!(fingerprint(), first);
+        // Only the included file changes; the top-level config is untouched
+        fs.create_config_from_content("base.yml", "directories:\n  shared.txt: v2\n");
+        assert_ne!(fingerprint(), first);
+    }
+
+    #[test]
+    fn test_apply_dry_run_cache_sees_environment_and_line_endings() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_config_from_content("env.yml", "directories:\n  home.txt: ${SKELETOR_TEST_CACHE_HOME}\n");
+        let cache_dir = fs.path("cache");
+        let output_dir = fs.path("output");
+        let fingerprint = |extra: &[&str]| {
+            let mut args = vec![
+                config_file.to_str().unwrap(),
+                "-o",
+                output_dir.to_str().unwrap(),
+                "--dry-run",
+                "--env-vars",
+                "--cache",
+                cache_dir.to_str().unwrap(),
+            ];
+            args.extend_from_slice(extra);
+            let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+            crate::apply::run_apply(&sub_m).unwrap();
+            let entry = std::fs::read_dir(&cache_dir).unwrap().next().unwrap().unwrap().path();
+            std::fs::read_to_string(entry).unwrap()
+        };
+
+        std::env::set_var("SKELETOR_TEST_CACHE_HOME", "/home/one");
+        let first = fingerprint(&[]);
+        assert_eq!(fingerprint(&[]), first);
+        // The placeholder's value comes from the environment, not the config
+        std::env::set_var("SKELETOR_TEST_CACHE_HOME", "/home/two");
+        let second = fingerprint(&[]);
+        assert_ne!(second, first);
+        // Line endings are converted at write time, so only the option records them
+        assert_ne!(fingerprint(&["--line-endings", "crlf"]), second);
+    }
+
+    #[test]
+    fn test_apply_overwrite_respects_skeletorkeep() {
+        let fs = TestFileSystem::new();
+        let output_dir = fs.create_dir("output");
+        fs.create_file("output/.skeletorkeep", ".env\n");
+        fs.create_file("output/.env", "SECRET=local");
+        let config_file = fs.create_config_from_content(
+            "keep.yml",
+            "directories:\n  .env: \"SECRET=template\"\n  app.txt: \"app\"\n",
+        );
+
+        let args = vec![
+            config_file.to_str().unwrap(),
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--overwrite",
+        ];
+        if let Some(sub_m) = create_apply_matches(args) {
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        }
+
+        assert_file_content(output_dir.join(".env"), "SECRET=local");
+        assert_file_content(output_dir.join("app.txt"), "app");
+    }
+
+    #[test]
+    fn test_apply_with_json_format() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_test_config("json.yml");
+        let output_dir = fs.path("output");
+
+        let args = vec![
+            config_file.to_str().unwrap(),
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--format",
+            "json",
+        ];
+
+        if let Some(sub_m) = create_apply_matches(args) {
+            assert_eq!(super::ApplyConfig::from_matches(&sub_m).format, OutputFormat::Json);
+            assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        }
+
+        assert!(output_dir.join("test_output/hello.rs").exists());
+    }
+
     #[test]
     fn test_apply_with_output_directory() {
         let fs = TestFileSystem::new();
@@ -504,6 +1776,35 @@ ignore_patterns:
         }
     }
 
+    #[test]
+    fn test_apply_creates_missing_output_root() {
+        let fs = TestFileSystem::new();
+        let output_dir = fs.path("new/deep/path");
+        let config_file = fs.create_config_from_content("fresh.yml", r#"
+directories:
+  README.md: "hello"
+  src:
+    main.rs: "fn main() {}"
+stats:
+  files: 2
+  directories: 1
+"#);
+
+        // Under --strict, --verify-stats fails if the root were counted as a config directory
+        let args = vec![
+            "--strict",
+            config_file.to_str().unwrap(),
+            "-o",
+            output_dir.to_str().unwrap(),
+            "--verify-stats",
+        ];
+        let sub_m = create_apply_matches(args).unwrap();
+        assert_command_succeeds(|| super::run_apply(&sub_m));
+
+        assert_file_content(output_dir.join("README.md"), "hello");
+        assert_file_content(output_dir.join("src/main.rs"), "fn main() {}");
+    }
+
     #[test]
     fn test_apply_with_long_output_flag() {
         let fs = TestFileSystem::new();
@@ -0,0 +1,221 @@
+use super::clean::VCS_DIRS;
+use crate::errors::SkeletorError;
+use crate::tasks::Task;
+use serde_yaml::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Opt-in cache that lets a repeated, identical `apply --dry-run` short-circuit.
+///
+/// The SHA-256 fingerprint covers the config with its includes merged in, the apply
+/// options, every resolved task (kind, path and contents) and the state (existence,
+/// kind, size, mtime) of every path the template targets, so editing the config or
+/// anything it includes, or touching any targeted file, invalidates the entry. With `--clean` the whole output tree is walked too,
+/// since any file appearing there changes what would be removed.
+pub struct DryRunCache {
+    entry_path: PathBuf,
+    fingerprint: String,
+}
+
+impl DryRunCache {
+    pub fn new(
+        cache_dir: &Path,
+        config_path: &Path,
+        config: &Value,
+        output_dir: &Path,
+        tasks: &[Task],
+        options: &str,
+        clean: bool,
+    ) -> Self {
+        // One entry per (config, output dir) pair
+        let mut key_hasher = Sha256::new();
+        update_path(&mut key_hasher, &absolute(config_path));
+        update_path(&mut key_hasher, &absolute(output_dir));
+        let entry_path = cache_dir.join(format!("dry-run-{}.cache", &hex(key_hasher)[..16]));
+
+        let mut hasher = Sha256::new();
+        update(&mut hasher, serde_yaml::to_string(config).unwrap_or_default().as_bytes());
+        update(&mut hasher, options.as_bytes());
+        hash_path_state(output_dir, &mut hasher);
+        for task in tasks {
+            let (kind, content) = match task {
+                Task::Dir(_) => ("dir", ""),
+                Task::File(_, content) => ("file", content.as_str()),
+                Task::Symlink(_, target) => ("symlink", target.as_str()),
+            };
+            update(&mut hasher, kind.as_bytes());
+            update_path(&mut hasher, task.path());
+            update(&mut hasher, content.as_bytes());
+            hash_path_state(task.path(), &mut hasher);
+        }
+        if clean {
+            hash_output_tree(output_dir, &absolute(cache_dir), &mut hasher);
+        }
+
+        Self {
+            entry_path,
+            fingerprint: hex(hasher),
+        }
+    }
+
+    /// Returns true when the stored fingerprint matches the current state
+    pub fn is_fresh(&self) -> bool {
+        fs::read_to_string(&self.entry_path)
+            .map(|stored| stored.trim() == self.fingerprint)
+            .unwrap_or(false)
+    }
+
+    /// Records the current fingerprint for the next run
+    pub fn store(&self) -> Result<(), SkeletorError> {
+        if let Some(parent) = self.entry_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| SkeletorError::from_io_with_context(e, parent.to_path_buf()))?;
+        }
+        crate::utils::write_string_to_file(&self.entry_path, &self.fingerprint)
+    }
+
+    pub fn entry_path(&self) -> &Path {
+        &self.entry_path
+    }
+}
+
+/// Canonical form of `path`, resolving its nearest existing ancestor when the
+/// path itself does not exist yet (as the cache directory on a first run)
+fn absolute(path: &Path) -> PathBuf {
+    if let Ok(resolved) = fs::canonicalize(path) {
+        return resolved;
+    }
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => absolute(parent).join(name),
+        _ => fs::canonicalize(".")
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf()),
+    }
+}
+
+/// Feeds `bytes` length-prefixed, so adjacent fields cannot run into each other
+fn update(hasher: &mut Sha256, bytes: &[u8]) {
+    hasher.update((bytes.len() as u64).to_le_bytes());
+    hasher.update(bytes);
+}
+
+fn update_path(hasher: &mut Sha256, path: &Path) {
+    update(hasher, path.to_string_lossy().as_bytes());
+}
+
+fn hex(hasher: Sha256) -> String {
+    hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hash_path_state(path: &Path, hasher: &mut Sha256) {
+    match fs::symlink_metadata(path) {
+        // A directory previews the same whatever it holds
+        Ok(metadata) if metadata.is_dir() => update(hasher, b"dir"),
+        Ok(metadata) => {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |duration| duration.as_nanos());
+            update(hasher, b"entry");
+            hasher.update(metadata.len().to_le_bytes());
+            hasher.update(modified.to_le_bytes());
+        }
+        Err(_) => update(hasher, b"missing"),
+    }
+}
+
+/// Hashes every entry under `dir` in name order, leaving out version control
+/// directories (which `--clean` never touches), the cache itself and the
+/// directories leading to it, which only exist once the cache has been written.
+fn hash_output_tree(dir: &Path, cache_dir: &Path, hasher: &mut Sha256) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    paths.sort();
+    for path in paths {
+        if path.file_name().and_then(|name| name.to_str()).is_some_and(|name| VCS_DIRS.contains(&name)) {
+            continue;
+        }
+        let resolved = absolute(&path);
+        if resolved == cache_dir {
+            continue;
+        }
+        let is_dir = fs::symlink_metadata(&path).map(|metadata| metadata.is_dir()).unwrap_or(false);
+        if is_dir {
+            if !cache_dir.starts_with(&resolved) {
+                update_path(hasher, &path);
+            }
+            hash_output_tree(&path, cache_dir, hasher);
+        } else {
+            update_path(hasher, &path);
+            hash_path_state(&path, hasher);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_dry_run_cache_invalidation() {
+        let fs = TestFileSystem::new();
+        let cache_dir = fs.path("cache");
+        let output_dir = fs.create_dir("output");
+        let config_path = fs.path("config.yml");
+        let tasks = vec![Task::File(output_dir.join("a.txt"), "a".to_string())];
+        let v1 = Value::from("v1");
+
+        let cache = DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &tasks, "", false);
+        assert!(!cache.is_fresh());
+        cache.store().unwrap();
+        assert!(cache.is_fresh());
+
+        // Config content change invalidates the entry
+        let v2 = Value::from("v2");
+        let changed = DryRunCache::new(&cache_dir, &config_path, &v2, &output_dir, &tasks, "", false);
+        assert_eq!(changed.entry_path(), cache.entry_path());
+        assert!(!changed.is_fresh());
+
+        // A targeted file appearing in the output dir invalidates the entry
+        fs.create_file("output/a.txt", "a");
+        let touched = DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &tasks, "", false);
+        assert!(!touched.is_fresh());
+    }
+
+    #[test]
+    fn test_dry_run_cache_covers_contents_and_clean_tree() {
+        let fs = TestFileSystem::new();
+        let cache_dir = fs.path("output/.cache");
+        let output_dir = fs.create_dir("output");
+        let config_path = fs.path("config.yml");
+        let tasks = vec![Task::File(output_dir.join("a.txt"), "a".to_string())];
+        let v1 = Value::from("v1");
+
+        let cache = DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &tasks, "", true);
+        cache.store().unwrap();
+        // Writing the cache inside the output tree does not count as a change
+        let again = DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &tasks, "", true);
+        assert!(again.is_fresh());
+
+        // Same path, different resolved contents
+        let edited = vec![Task::File(output_dir.join("a.txt"), "b".to_string())];
+        assert!(!DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &edited, "", true).is_fresh());
+        assert!(!DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &tasks, "x", true).is_fresh());
+
+        // An untargeted file only matters when --clean would remove it
+        fs.create_file("output/stray.txt", "");
+        assert!(!DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &tasks, "", true).is_fresh());
+        fs.create_file("output/.git/HEAD", "");
+        let clean = DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &tasks, "", true);
+        clean.store().unwrap();
+        fs.create_file("output/.git/ORIG_HEAD", "");
+        assert!(DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &tasks, "", true).is_fresh());
+        assert!(!DryRunCache::new(&cache_dir, &config_path, &v1, &output_dir, &tasks, "", false).is_fresh());
+    }
+}
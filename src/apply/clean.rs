@@ -0,0 +1,146 @@
+use crate::errors::SkeletorError;
+use crate::tasks::{is_kept, Task, KEEP_FILE_NAME};
+use ignore::gitignore::Gitignore;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory under the output root where skeletor keeps its own state
+const STATE_DIR: &str = ".skeletor";
+
+/// Version control directories, never looked into wherever they appear
+pub(super) const VCS_DIRS: [&str; 3] = [".git", ".hg", ".svn"];
+
+/// Lists the files and symlinks under `output_dir` that no task in `tasks` creates, sorted.
+///
+/// Only the output root and directories the template defines are looked into, so build
+/// output, dependencies and version control directories (`.git`, `.hg`, `.svn`) the
+/// template knows nothing about are never touched.
+/// Symlinks are listed rather than followed, so nothing outside `output_dir` is visited.
+/// Paths matched by the template's ignore patterns or by `.skeletorkeep` are left out,
+/// as are the `.skeletorkeep` file and skeletor's `.skeletor/` state directory.
+pub fn stale_files(
+    output_dir: &Path,
+    tasks: &[Task],
+    ignore: Option<&Gitignore>,
+    keep: Option<&Gitignore>,
+) -> Result<Vec<PathBuf>, SkeletorError> {
+    if !output_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let defined: HashSet<&Path> = tasks.iter().map(Task::path).collect();
+    let mut stale = Vec::new();
+    collect_stale(output_dir, output_dir, &defined, ignore, keep, &mut stale)?;
+    stale.sort();
+    Ok(stale)
+}
+
+fn collect_stale(
+    output_dir: &Path,
+    dir: &Path,
+    defined: &HashSet<&Path>,
+    ignore: Option<&Gitignore>,
+    keep: Option<&Gitignore>,
+    stale: &mut Vec<PathBuf>,
+) -> Result<(), SkeletorError> {
+    let entries = fs::read_dir(dir).map_err(|e| SkeletorError::from_io_with_context(e, dir.to_path_buf()))?;
+    for entry in entries {
+        let entry = entry?;
+        let path = entry.path();
+        // `DirEntry::file_type` does not follow symlinks
+        let is_dir = entry.file_type()?.is_dir();
+        let relative = path.strip_prefix(output_dir).unwrap_or(&path);
+
+        if relative == Path::new(STATE_DIR) || relative == Path::new(KEEP_FILE_NAME) {
+            continue;
+        }
+        if ignore.is_some_and(|matcher| matcher.matched_path_or_any_parents(relative, is_dir).is_ignore()) {
+            continue;
+        }
+        if is_dir {
+            let vcs = entry.file_name().to_str().is_some_and(|name| VCS_DIRS.contains(&name));
+            if !vcs && defined.contains(path.as_path()) {
+                collect_stale(output_dir, &path, defined, ignore, keep, stale)?;
+            }
+        } else if !defined.contains(path.as_path()) && !is_kept(keep, &path) {
+            stale.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Deletes each listed file or symlink; a symlink's target is never touched.
+pub fn remove_stale(paths: &[PathBuf]) -> Result<(), SkeletorError> {
+    for path in paths {
+        fs::remove_file(path).map_err(|e| SkeletorError::from_io_with_context(e, path.clone()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::{load_keep_matcher, traverse_structure};
+    use crate::test_utils::helpers::*;
+    use serde_yaml::Value;
+
+    #[test]
+    fn test_stale_files_skips_template_kept_and_state_paths() {
+        let fs = TestFileSystem::new();
+        let output = fs.path("project");
+        fs.create_file("project/src/main.rs", "fn main() {}");
+        fs.create_file("project/src/old.rs", "// removed from the template");
+        fs.create_file("project/notes.txt", "stray");
+        fs.create_file("project/local.env", "SECRET=1");
+        fs.create_file("project/.skeletorkeep", "local.env\n");
+        fs.create_file("project/.skeletor/applied.json", "{}");
+
+        let structure: Value = serde_yaml::from_str("src:\n  main.rs: fn main() {}\n").unwrap();
+        let tasks = traverse_structure(&output, &structure).unwrap();
+        let keep = load_keep_matcher(&output).unwrap();
+
+        let stale = stale_files(&output, &tasks, None, keep.as_ref()).unwrap();
+        assert_eq!(stale, vec![output.join("notes.txt"), output.join("src/old.rs")]);
+
+        remove_stale(&stale).unwrap();
+        assert!(!output.join("notes.txt").exists());
+        assert!(output.join("src/main.rs").exists());
+        assert!(output.join("local.env").exists());
+    }
+
+    #[test]
+    fn test_stale_files_leaves_vcs_and_undefined_directories_alone() {
+        let fs = TestFileSystem::new();
+        let output = fs.path("project");
+        fs.create_file("project/.git/HEAD", "ref: refs/heads/main\n");
+        fs.create_file("project/src/.git/config", "[core]\n");
+        fs.create_file("project/node_modules/dep/index.js", "");
+        fs.create_file("project/src/stray.rs", "");
+
+        let structure: Value = serde_yaml::from_str("src:\n  main.rs: fn main() {}\n").unwrap();
+        let tasks = traverse_structure(&output, &structure).unwrap();
+
+        let stale = stale_files(&output, &tasks, None, None).unwrap();
+        assert_eq!(stale, vec![output.join("src/stray.rs")]);
+        remove_stale(&stale).unwrap();
+        assert_file_content(output.join(".git/HEAD"), "ref: refs/heads/main\n");
+        assert!(output.join("node_modules/dep/index.js").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_stale_files_does_not_follow_symlinks() {
+        let fs = TestFileSystem::new();
+        let outside = fs.create_file("outside/keep-me.txt", "not ours");
+        let output = fs.path("project");
+        std::fs::create_dir_all(&output).unwrap();
+        std::os::unix::fs::symlink(fs.path("outside"), output.join("linked")).unwrap();
+
+        let stale = stale_files(&output, &[], None, None).unwrap();
+        assert_eq!(stale, vec![output.join("linked")]);
+
+        remove_stale(&stale).unwrap();
+        assert!(!output.join("linked").exists());
+        assert_file_content(outside, "not ours");
+    }
+}
@@ -0,0 +1,148 @@
+use crate::errors::SkeletorError;
+use crate::tasks::Task;
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+
+/// An answer to "overwrite this file?" under `--interactive`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverwriteAnswer {
+    Yes,
+    No,
+    /// Yes to this file and every one after it
+    All,
+    /// Stop the apply before anything is written
+    Quit,
+}
+
+/// Tasks split by the answers given under `--interactive`
+pub struct OverwritePlan {
+    /// New files, directories, symlinks and files already up to date
+    pub tasks: Vec<Task>,
+    /// Existing files the user agreed to replace
+    pub overwrites: Vec<Task>,
+    /// Existing files the user declined to replace
+    pub declined: Vec<String>,
+}
+
+/// Asks `ask` about every file task whose file exists with different contents, in task order.
+///
+/// Answering [`OverwriteAnswer::All`] accepts the rest without asking, and
+/// [`OverwriteAnswer::Quit`] fails with [`SkeletorError::Aborted`] naming the file.
+pub fn confirm_overwrites(
+    tasks: Vec<Task>,
+    ask: &mut dyn FnMut(&Path) -> OverwriteAnswer,
+) -> Result<OverwritePlan, SkeletorError> {
+    let mut plan = OverwritePlan {
+        tasks: Vec::new(),
+        overwrites: Vec::new(),
+        declined: Vec::new(),
+    };
+    let mut all = false;
+
+    for task in tasks {
+        let Task::File(path, content) = &task else {
+            plan.tasks.push(task);
+            continue;
+        };
+        match fs::read(path) {
+            Ok(current) if current != content.as_bytes() => {}
+            _ => {
+                plan.tasks.push(task);
+                continue;
+            }
+        }
+        let answer = if all { OverwriteAnswer::Yes } else { ask(path) };
+        match answer {
+            OverwriteAnswer::Yes => plan.overwrites.push(task),
+            OverwriteAnswer::All => {
+                all = true;
+                plan.overwrites.push(task);
+            }
+            OverwriteAnswer::No => plan.declined.push(path.display().to_string()),
+            OverwriteAnswer::Quit => {
+                return Err(SkeletorError::Aborted {
+                    path: path.display().to_string(),
+                })
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Asks on the terminal whether to overwrite `path`; without a terminal the answer is no.
+pub fn ask_overwrite_on_terminal(path: &Path) -> OverwriteAnswer {
+    if !std::io::stdin().is_terminal() {
+        return OverwriteAnswer::No;
+    }
+    loop {
+        print!("Overwrite {}? [y/N/a/q] ", path.display());
+        let _ = std::io::stdout().flush();
+        let mut answer = String::new();
+        if std::io::stdin().lock().read_line(&mut answer).is_err() {
+            return OverwriteAnswer::No;
+        }
+        match answer.trim() {
+            "y" | "Y" | "yes" => return OverwriteAnswer::Yes,
+            "" | "n" | "N" | "no" => return OverwriteAnswer::No,
+            "a" | "A" | "all" => return OverwriteAnswer::All,
+            "q" | "Q" | "quit" => return OverwriteAnswer::Quit,
+            _ => println!("Please answer y (yes), n (no), a (all) or q (quit)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::*;
+
+    fn template(fs: &TestFileSystem) -> Vec<Task> {
+        vec![
+            Task::Dir(fs.path("src")),
+            Task::File(fs.path("src/a.rs"), "// a v2".to_string()),
+            Task::File(fs.path("src/b.rs"), "// b v2".to_string()),
+            Task::File(fs.path("src/c.rs"), "// c v2".to_string()),
+            Task::File(fs.path("src/new.rs"), "// new".to_string()),
+            Task::File(fs.path("src/same.rs"), "// same".to_string()),
+        ]
+    }
+
+    fn existing(fs: &TestFileSystem) {
+        for name in ["a", "b", "c"] {
+            fs.create_file(format!("src/{}.rs", name), &format!("// {} v1", name));
+        }
+        fs.create_file("src/same.rs", "// same");
+    }
+
+    #[test]
+    fn test_confirm_overwrites_asks_only_about_changed_files() {
+        let fs = TestFileSystem::new();
+        existing(&fs);
+
+        let mut asked = Vec::new();
+        let mut answers = vec![OverwriteAnswer::No, OverwriteAnswer::All].into_iter();
+        let plan = confirm_overwrites(template(&fs), &mut |path| {
+            asked.push(path.to_path_buf());
+            answers.next().unwrap()
+        })
+        .unwrap();
+
+        // `all` on b.rs also accepts c.rs without asking
+        assert_eq!(asked, vec![fs.path("src/a.rs"), fs.path("src/b.rs")]);
+        assert_eq!(plan.declined, vec![fs.path("src/a.rs").display().to_string()]);
+        let overwritten: Vec<&Path> = plan.overwrites.iter().map(Task::path).collect();
+        assert_eq!(overwritten, vec![fs.path("src/b.rs"), fs.path("src/c.rs")]);
+        assert_eq!(plan.tasks.len(), 3);
+    }
+
+    #[test]
+    fn test_confirm_overwrites_quit_aborts() {
+        let fs = TestFileSystem::new();
+        existing(&fs);
+
+        let result = confirm_overwrites(template(&fs), &mut |_| OverwriteAnswer::Quit);
+        assert!(matches!(result, Err(SkeletorError::Aborted { path }) if path.ends_with("a.rs")));
+    }
+}
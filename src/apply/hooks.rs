@@ -0,0 +1,118 @@
+use crate::errors::SkeletorError;
+use crate::output::Reporter;
+use serde_yaml::Value;
+use std::io;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// Reads the `hooks.post_apply` list of shell commands from a config document.
+///
+/// A single string is accepted as a one-command list; a missing section has no hooks.
+pub fn post_apply_hooks(yaml_doc: &Value) -> Result<Vec<String>, SkeletorError> {
+    let Some(hooks) = yaml_doc.get("hooks").and_then(|hooks| hooks.get("post_apply")) else {
+        return Ok(Vec::new());
+    };
+    match hooks {
+        Value::String(command) => Ok(vec![command.clone()]),
+        Value::Sequence(commands) => commands
+            .iter()
+            .enumerate()
+            .map(|(i, command)| {
+                command.as_str().map(str::to_string).ok_or_else(|| {
+                    SkeletorError::invalid_config_entry(
+                        format!("hooks.post_apply.{}", i),
+                        "hook commands must be strings",
+                    )
+                })
+            })
+            .collect(),
+        _ => Err(SkeletorError::invalid_config_entry(
+            "hooks.post_apply",
+            "expected a list of shell commands",
+        )),
+    }
+}
+
+/// Runs each command through the platform shell in `dir`, in order, announcing it to
+/// `reporter` and stopping at the first one that fails.
+///
+/// Output streams through as the commands run; with `stdout_to_stderr`, their stdout
+/// is forwarded to stderr so it cannot mix with machine-readable output.
+pub fn run_hooks(
+    commands: &[String],
+    dir: &Path,
+    stdout_to_stderr: bool,
+    reporter: &dyn Reporter,
+) -> Result<(), SkeletorError> {
+    for command in commands {
+        reporter.operation_start("hook", command);
+        let mut shell = shell_command(command);
+        shell.current_dir(dir);
+        if stdout_to_stderr {
+            shell.stdout(Stdio::piped());
+        }
+
+        let mut child = shell.spawn().map_err(|e| SkeletorError::HookFailed {
+            command: command.clone(),
+            status: e.to_string(),
+        })?;
+        if let Some(mut stdout) = child.stdout.take() {
+            io::copy(&mut stdout, &mut io::stderr())?;
+        }
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(SkeletorError::HookFailed {
+                command: command.clone(),
+                status: status.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(windows)]
+fn shell_command(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::output::SilentReporter;
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_hooks_run_in_order_and_stop_at_failure() {
+        let fs = TestFileSystem::new();
+        let doc: Value = serde_yaml::from_str(
+            "hooks:\n  post_apply:\n    - echo one > log.txt\n    - echo two >> log.txt\n    - exit 3\n    - echo never >> log.txt\n",
+        )
+        .unwrap();
+        let hooks = post_apply_hooks(&doc).unwrap();
+        assert_eq!(hooks.len(), 4);
+
+        match run_hooks(&hooks, &fs.root_path, true, &SilentReporter) {
+            Err(SkeletorError::HookFailed { command, status }) => {
+                assert_eq!(command, "exit 3");
+                assert!(status.contains('3'));
+            }
+            other => panic!("Expected HookFailed error, got {:?}", other),
+        }
+        assert_file_content(fs.path("log.txt"), "one\ntwo\n");
+
+        let doc: Value = serde_yaml::from_str("hooks:\n  post_apply: [git init, 3]\n").unwrap();
+        assert!(matches!(
+            post_apply_hooks(&doc),
+            Err(SkeletorError::InvalidConfigEntry { path, .. }) if path == "hooks.post_apply.1"
+        ));
+    }
+}
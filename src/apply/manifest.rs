@@ -0,0 +1,245 @@
+use crate::errors::SkeletorError;
+use crate::tasks::{CreationResult, Task};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+/// Manifest location, relative to the output directory
+pub const MANIFEST_PATH: &str = ".skeletor/applied.json";
+
+/// What to do with a file that was edited locally since skeletor last wrote it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnLocalEdit {
+    Skip,
+    Overwrite,
+    Prompt,
+}
+
+impl OnLocalEdit {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "skip" => Some(Self::Skip),
+            "overwrite" => Some(Self::Overwrite),
+            "prompt" => Some(Self::Prompt),
+            _ => None,
+        }
+    }
+}
+
+/// Content hashes of the files the last apply wrote, keyed by path relative to the output directory.
+///
+/// A file whose current hash still matches is untouched since then, so a new template
+/// version may replace it; a mismatch means it was edited locally.
+pub struct AppliedManifest {
+    output_dir: PathBuf,
+    files: BTreeMap<String, String>,
+}
+
+/// Tasks split by how a re-apply treats them
+pub struct LocalEditPlan {
+    /// Run with the usual overwrite rules
+    pub tasks: Vec<Task>,
+    /// Existing files that are replaced regardless of `--overwrite`
+    pub upgrades: Vec<Task>,
+    /// Locally edited files left alone
+    pub kept: Vec<String>,
+}
+
+impl AppliedManifest {
+    /// Loads the manifest in `output_dir`; a missing manifest is empty.
+    pub fn load(output_dir: &Path) -> Result<Self, SkeletorError> {
+        let path = output_dir.join(MANIFEST_PATH);
+        let files = if path.is_file() {
+            let content = crate::utils::read_file_to_string(&path)?;
+            let document: serde_json::Value = serde_json::from_str(&content)
+                .map_err(|e| SkeletorError::Config(format!("Invalid {}: {}", path.display(), e)))?;
+            document
+                .get("files")
+                .and_then(serde_json::Value::as_object)
+                .map(|files| {
+                    files
+                        .iter()
+                        .filter_map(|(file, hash)| Some((file.clone(), hash.as_str()?.to_string())))
+                        .collect()
+                })
+                .unwrap_or_default()
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Self {
+            output_dir: output_dir.to_path_buf(),
+            files,
+        })
+    }
+
+    /// Splits `tasks` into upgrades of untouched files, locally edited files to keep,
+    /// and everything else. `ask` decides edited files under [`OnLocalEdit::Prompt`].
+    pub fn plan(
+        &self,
+        tasks: Vec<Task>,
+        on_local_edit: OnLocalEdit,
+        ask: &mut dyn FnMut(&Path) -> bool,
+    ) -> LocalEditPlan {
+        let mut plan = LocalEditPlan {
+            tasks: Vec::new(),
+            upgrades: Vec::new(),
+            kept: Vec::new(),
+        };
+
+        for task in tasks {
+            let Task::File(path, content) = &task else {
+                plan.tasks.push(task);
+                continue;
+            };
+            let (Some(recorded), Ok(current)) = (self.files.get(&self.key(path)), fs::read(path)) else {
+                plan.tasks.push(task);
+                continue;
+            };
+            if current == content.as_bytes() {
+                plan.tasks.push(task);
+            } else if *recorded == content_hash(&current) {
+                plan.upgrades.push(task);
+            } else {
+                let replace = match on_local_edit {
+                    OnLocalEdit::Skip => false,
+                    OnLocalEdit::Overwrite => true,
+                    OnLocalEdit::Prompt => ask(path),
+                };
+                if replace {
+                    plan.upgrades.push(task);
+                } else {
+                    plan.kept.push(path.display().to_string());
+                }
+            }
+        }
+
+        plan
+    }
+
+    /// Records the on-disk hash of every file `tasks` wrote or found already up to date,
+    /// then saves the manifest. Skipped, kept and failed files keep their previous entry.
+    pub fn record(&mut self, tasks: &[Task], result: &CreationResult) -> Result<(), SkeletorError> {
+        let untouched: HashSet<&str> = result
+            .skipped_files_list
+            .iter()
+            .chain(&result.preserved_files_list)
+            .chain(result.failed_files_list.iter().map(|(path, _)| path))
+            .map(String::as_str)
+            .collect();
+
+        for task in tasks {
+            if let Task::File(path, _) = task {
+                if untouched.contains(path.display().to_string().as_str()) {
+                    continue;
+                }
+                if let Ok(current) = fs::read(path) {
+                    self.files.insert(self.key(path), content_hash(&current));
+                }
+            }
+        }
+
+        let path = self.output_dir.join(MANIFEST_PATH);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| SkeletorError::from_io_with_context(e, parent.to_path_buf()))?;
+        }
+        let document = serde_json::json!({ "version": 1, "files": self.files });
+        crate::utils::write_string_to_file(&path, &format!("{:#}\n", document))
+    }
+
+    fn key(&self, path: &Path) -> String {
+        path.strip_prefix(&self.output_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+/// Asks on the terminal whether to replace a locally edited file; without a terminal the file is kept.
+pub fn ask_on_terminal(path: &Path) -> bool {
+    if !std::io::stdin().is_terminal() {
+        return false;
+    }
+    print!("{} was edited since the last apply; overwrite it? [y/N] ", path.display());
+    let _ = std::io::stdout().flush();
+    let mut answer = String::new();
+    std::io::stdin().lock().read_line(&mut answer).is_ok()
+        && matches!(answer.trim(), "y" | "Y" | "yes")
+}
+
+/// FNV-1a, so manifests stay comparable across Rust versions (unlike `DefaultHasher`)
+fn content_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf29ce484222325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3)
+    });
+    format!("fnv1a64:{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::create_files_and_directories;
+    use crate::test_utils::helpers::*;
+
+    fn template(output: &Path, version: &str) -> Vec<Task> {
+        vec![
+            Task::File(output.join("unchanged.txt"), "same in every version".to_string()),
+            Task::File(output.join("edited.txt"), format!("edited {}", version)),
+            Task::File(output.join("upgraded.txt"), format!("upgraded {}", version)),
+        ]
+    }
+
+    fn apply(output: &Path, tasks: Vec<Task>, on_local_edit: OnLocalEdit) -> LocalEditPlan {
+        let mut manifest = AppliedManifest::load(output).unwrap();
+        let all = tasks.clone();
+        let plan = manifest.plan(tasks, on_local_edit, &mut |_| panic!("not prompting"));
+        let mut result = create_files_and_directories(&plan.tasks, false).unwrap();
+        result.merge(create_files_and_directories(&plan.upgrades, true).unwrap());
+        result.preserved_files_list.extend(plan.kept.iter().cloned());
+        manifest.record(&all, &result).unwrap();
+        plan
+    }
+
+    #[test]
+    fn test_reapply_upgrades_untouched_files_and_keeps_local_edits() {
+        let fs = TestFileSystem::new();
+        let output = fs.path("project");
+
+        apply(&output, template(&output, "v1"), OnLocalEdit::Skip);
+        assert!(output.join(MANIFEST_PATH).is_file());
+        fs.create_file("project/edited.txt", "my local change");
+
+        let plan = apply(&output, template(&output, "v2"), OnLocalEdit::Skip);
+        assert_eq!(plan.upgrades.len(), 1);
+        assert_eq!(plan.kept.len(), 1);
+        assert_file_content(output.join("unchanged.txt"), "same in every version");
+        assert_file_content(output.join("upgraded.txt"), "upgraded v2");
+        assert_file_content(output.join("edited.txt"), "my local change");
+
+        // The kept file is still recognized as edited on the next upgrade
+        let plan = apply(&output, template(&output, "v3"), OnLocalEdit::Overwrite);
+        assert_eq!(plan.upgrades.len(), 2);
+        assert_file_content(output.join("upgraded.txt"), "upgraded v3");
+        assert_file_content(output.join("edited.txt"), "edited v3");
+    }
+
+    #[test]
+    fn test_prompt_decides_edited_files() {
+        let fs = TestFileSystem::new();
+        let output = fs.path("project");
+        apply(&output, template(&output, "v1"), OnLocalEdit::Skip);
+        fs.create_file("project/edited.txt", "my local change");
+
+        let manifest = AppliedManifest::load(&output).unwrap();
+        let mut asked = Vec::new();
+        let plan = manifest.plan(template(&output, "v2"), OnLocalEdit::Prompt, &mut |path| {
+            asked.push(path.to_path_buf());
+            true
+        });
+        assert_eq!(asked, vec![output.join("edited.txt")]);
+        assert_eq!(plan.upgrades.len(), 2);
+        assert!(plan.kept.is_empty());
+    }
+}
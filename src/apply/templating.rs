@@ -0,0 +1,129 @@
+use crate::errors::SkeletorError;
+use crate::tasks::Task;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Builds the data file contents are rendered with: the JSON object in `context_file`,
+/// if any, with the template's variables (config `variables` and `--var`) on top.
+pub fn template_context(
+    variables: &HashMap<String, String>,
+    context_file: Option<&Path>,
+) -> Result<Map<String, JsonValue>, SkeletorError> {
+    let mut context = match context_file {
+        Some(path) => {
+            let content = crate::utils::read_file_to_string(path)?;
+            match serde_json::from_str(&content) {
+                Ok(JsonValue::Object(object)) => object,
+                Ok(_) => {
+                    return Err(SkeletorError::Config(format!(
+                        "{}: the template context must be a JSON object",
+                        path.display()
+                    )))
+                }
+                Err(e) => {
+                    return Err(SkeletorError::Config(format!(
+                        "Invalid template context {}: {}",
+                        path.display(),
+                        e
+                    )))
+                }
+            }
+        }
+        None => Map::new(),
+    };
+    for (name, value) in variables {
+        context.insert(name.clone(), JsonValue::String(value.clone()));
+    }
+    Ok(context)
+}
+
+/// Renders each file task's content as a Tera template with `context`.
+///
+/// Referring to a variable the context does not define is an error, as is invalid
+/// syntax; both fail with [`SkeletorError::Config`] naming the file.
+#[cfg(feature = "templating")]
+pub fn render_tasks(tasks: Vec<Task>, context: &Map<String, JsonValue>) -> Result<Vec<Task>, SkeletorError> {
+    let context = tera::Context::from_serialize(context).map_err(|e| SkeletorError::Config(e.to_string()))?;
+    tasks
+        .into_iter()
+        .map(|task| match task {
+            Task::File(path, content) => match tera::Tera::one_off(&content, &context, false) {
+                Ok(rendered) => Ok(Task::File(path, rendered)),
+                Err(e) => Err(SkeletorError::Config(format!(
+                    "Could not render {} as a template: {}",
+                    path.display(),
+                    error_chain(&e)
+                ))),
+            },
+            other => Ok(other),
+        })
+        .collect()
+}
+
+/// Without the `templating` feature there is no engine to render with
+#[cfg(not(feature = "templating"))]
+pub fn render_tasks(_tasks: Vec<Task>, _context: &Map<String, JsonValue>) -> Result<Vec<Task>, SkeletorError> {
+    Err(SkeletorError::Config(
+        "--templating needs skeletor built with the `templating` cargo feature".to_string(),
+    ))
+}
+
+/// Tera's top-level message only says which template failed; the cause is in its sources
+#[cfg(feature = "templating")]
+fn error_chain(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = std::error::Error::source(error);
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_template_context_layers_variables_over_context_file() {
+        let fs = TestFileSystem::new();
+        let data = fs.create_file("data.json", r#"{"name": "from-file", "modules": ["a", "b"]}"#);
+        let variables = HashMap::from([("name".to_string(), "from-var".to_string())]);
+
+        let context = template_context(&variables, Some(&data)).unwrap();
+        assert_eq!(context["name"], "from-var");
+        assert_eq!(context["modules"], serde_json::json!(["a", "b"]));
+
+        let list = fs.create_file("list.json", "[1, 2]");
+        assert!(matches!(template_context(&variables, Some(&list)), Err(SkeletorError::Config(_))));
+    }
+
+    #[cfg(feature = "templating")]
+    #[test]
+    fn test_render_tasks_supports_loops_and_reports_the_file() {
+        let fs = TestFileSystem::new();
+        let data = fs.create_file("data.json", r#"{"modules": ["auth", "billing"]}"#);
+        let context = template_context(&HashMap::new(), Some(&data)).unwrap();
+
+        let tasks = vec![
+            Task::Dir(fs.path("src")),
+            Task::File(
+                fs.path("src/lib.rs"),
+                "{% for m in modules %}pub mod {{ m }};\n{% endfor %}".to_string(),
+            ),
+        ];
+        let rendered = render_tasks(tasks, &context).unwrap();
+        assert_eq!(rendered[1], Task::File(fs.path("src/lib.rs"), "pub mod auth;\npub mod billing;\n".to_string()));
+
+        let undefined = vec![Task::File(fs.path("README.md"), "{{ missing }}".to_string())];
+        match render_tasks(undefined, &context) {
+            Err(SkeletorError::Config(message)) => {
+                assert!(message.contains("README.md") && message.contains("missing"), "{}", message)
+            }
+            other => panic!("Expected a render error, got {:?}", other),
+        }
+    }
+}
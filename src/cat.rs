@@ -0,0 +1,130 @@
+use crate::config::{
+    config_dir, describe_entries, directories_mapping, find_entry, resolve_includes, resolve_path,
+    working_dir_from_matches,
+};
+use crate::errors::SkeletorError;
+use crate::tasks::{file_content, file_ref_path, resolve_file_refs};
+use crate::tasks::variables::{collect_variables, substitute_variables_with_env};
+use clap::ArgMatches;
+use serde_yaml::Value;
+use std::path::{Path, PathBuf};
+
+/// Runs the cat subcommand: prints one embedded file's content without applying the config.
+pub fn run_cat(matches: &ArgMatches) -> Result<(), SkeletorError> {
+    let config_path = resolve_path(
+        working_dir_from_matches(matches).as_deref(),
+        PathBuf::from(matches.get_one::<String>("config").unwrap()),
+    );
+    let file_path = matches.get_one::<String>("path").unwrap();
+    let vars: Vec<String> = matches
+        .get_many::<String>("var")
+        .map(|vals| vals.map(|v| v.to_string()).collect())
+        .unwrap_or_default();
+
+    let mut yaml_doc = crate::utils::read_config_file(&config_path)?;
+    resolve_includes(&mut yaml_doc, &config_path)?;
+    let env_vars = matches.get_flag("env_vars");
+    let content = extract_file_content(&yaml_doc, config_dir(&config_path), file_path, &vars, env_vars)?;
+    print!("{}", content);
+
+    Ok(())
+}
+
+/// Resolves `path` to a file leaf in the config and returns its content,
+/// substituting `${name}` placeholders when variables are defined or `env_vars`
+/// lets them fall back to the environment.
+/// `!file_ref` contents are read relative to `config_dir`.
+fn extract_file_content(
+    yaml_doc: &Value,
+    config_dir: &Path,
+    path: &str,
+    vars: &[String],
+    env_vars: bool,
+) -> Result<String, SkeletorError> {
+    directories_mapping(yaml_doc)?;
+    let directories = &yaml_doc["directories"];
+
+    let variables = collect_variables(yaml_doc, vars)?;
+
+    let substituted;
+    let substitutes = !variables.is_empty() || env_vars;
+    let directories = if substitutes {
+        substituted = substitute_variables_with_env(directories, &variables, env_vars)?;
+        &substituted
+    } else {
+        directories
+    };
+
+    match find_entry(directories, path)? {
+        entry if file_content(entry).is_some() => Ok(file_content(entry).unwrap_or_default().to_string()),
+        entry if file_ref_path(entry).is_some() => {
+            // Sidecar contents are substituted like inline ones
+            let mut content = resolve_file_refs(entry, config_dir)?;
+            if substitutes {
+                content = substitute_variables_with_env(&content, &variables, env_vars)?;
+            }
+            Ok(content.as_str().unwrap_or_default().to_string())
+        }
+        entry => Err(SkeletorError::Config(format!(
+            "'{}' is not a file in the config; it contains: {}",
+            path,
+            describe_entries(entry)
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::*;
+
+    const CONFIG: &str = r#"
+variables:
+  project_name: demo
+directories:
+  src:
+    main.rs: "// ${project_name}"
+    utils:
+      mod.rs: ""
+"#;
+
+    #[test]
+    fn test_extract_file_content_substitutes_variables() {
+        let yaml_doc: Value = serde_yaml::from_str(CONFIG).unwrap();
+
+        let content = extract_file_content(&yaml_doc, Path::new("."), "src/main.rs", &[], false).unwrap();
+        assert_eq!(content, "// demo");
+
+        let content =
+            extract_file_content(&yaml_doc, Path::new("."), "src/main.rs", &["project_name=other".to_string()], false)
+                .unwrap();
+        assert_eq!(content, "// other");
+    }
+
+    #[test]
+    fn test_extract_non_file_lists_entries() {
+        let yaml_doc: Value = serde_yaml::from_str(CONFIG).unwrap();
+
+        match extract_file_content(&yaml_doc, Path::new("."), "src", &[], false) {
+            Err(SkeletorError::Config(message)) => assert!(message.contains("main.rs, utils/")),
+            other => panic!("Expected Config error, got {:?}", other),
+        }
+        match extract_file_content(&yaml_doc, Path::new("."), "src/missing.rs", &[], false) {
+            Err(SkeletorError::Config(message)) => assert!(message.contains("main.rs, utils/")),
+            other => panic!("Expected Config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_run_cat() {
+        let fs = TestFileSystem::new();
+        let config_path = fs.create_config_from_content("template.yml", CONFIG);
+
+        let args = vec![config_path.to_str().unwrap(), "src/main.rs"];
+        if let Some(sub_m) = create_cat_matches(args) {
+            assert_command_succeeds(|| run_cat(&sub_m));
+        } else {
+            panic!("Cat subcommand not found");
+        }
+    }
+}
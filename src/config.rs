@@ -1,5 +1,11 @@
 use crate::errors::SkeletorError;
+use crate::tasks::{file_ref_path, resolve_file_refs};
+use crate::tasks::variables::variables_from_value;
+use clap::ArgMatches;
+use log::info;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
 use serde_yaml::Value;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
 
 /// Configuration for Skeletor scaffolding operations
@@ -8,6 +14,8 @@ use std::path::{Path, PathBuf};
 pub struct SkeletorConfig {
     pub directories: Value,
     pub metadata: Option<SkeletorMetadata>,
+    /// Values for `${name}` placeholders, from the config's `variables:` section
+    pub variables: HashMap<String, String>,
 }
 
 /// Metadata associated with a Skeletor configuration
@@ -17,10 +25,51 @@ pub struct SkeletorMetadata {
     pub created: Option<String>,
     pub updated: Option<String>,
     pub generated_comments: Option<String>,
+    /// Skeletor version that wrote the snapshot
+    pub generated_with: Option<String>,
     pub stats: Option<(usize, usize)>, // (files, directories)
     pub ignore_patterns: Option<Vec<String>>,
 }
 
+impl SkeletorMetadata {
+    /// Reads the metadata fields of a config document; fields that are missing or malformed are `None`
+    pub fn from_value(yaml_doc: &Value) -> Self {
+        Self {
+            created: yaml_doc.get("created").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            updated: yaml_doc.get("updated").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            generated_comments: yaml_doc.get("generated_comments").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            generated_with: yaml_doc.get("generated_with").and_then(|v| v.as_str()).map(|s| s.to_string()),
+            stats: yaml_doc.get("stats").and_then(|stats| {
+                let files = stats.get("files")?.as_u64()? as usize;
+                let directories = stats.get("directories")?.as_u64()? as usize;
+                Some((files, directories))
+            }),
+            ignore_patterns: yaml_doc.get("ignore_patterns").and_then(|v| {
+                v.as_sequence()?.iter()
+                    .map(|item| item.as_str().map(|s| s.to_string()))
+                    .collect::<Option<Vec<_>>>()
+            }),
+        }
+    }
+}
+
+/// Every field is written, absent ones as `null`, and `stats` as `{ directories, files }`
+impl Serialize for SkeletorMetadata {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let stats = self.stats.map(|(files, directories)| {
+            BTreeMap::from([("directories", directories), ("files", files)])
+        });
+        let mut state = serializer.serialize_struct("SkeletorMetadata", 6)?;
+        state.serialize_field("created", &self.created)?;
+        state.serialize_field("updated", &self.updated)?;
+        state.serialize_field("generated_comments", &self.generated_comments)?;
+        state.serialize_field("generated_with", &self.generated_with)?;
+        state.serialize_field("stats", &stats)?;
+        state.serialize_field("ignore_patterns", &self.ignore_patterns)?;
+        state.end()
+    }
+}
+
 #[allow(dead_code)]
 impl SkeletorConfig {
     /// Create a new configuration from a YAML value
@@ -28,61 +77,292 @@ impl SkeletorConfig {
         Self {
             directories,
             metadata: None,
+            variables: HashMap::new(),
         }
     }
 
     /// Create a configuration from a YAML string
     pub fn from_yaml_str(yaml: &str) -> Result<Self, SkeletorError> {
         let yaml_doc: Value = crate::utils::parse_yaml_string(yaml)?;
+        Self::from_value(&yaml_doc)
+    }
 
+    /// Create a configuration from a parsed config document
+    ///
+    /// Brace groups in keys are expanded when the document sets `expand_braces: true`.
+    pub fn from_value(yaml_doc: &Value) -> Result<Self, SkeletorError> {
         let directories = yaml_doc
             .get("directories")
             .ok_or_else(|| SkeletorError::missing_config_key("directories"))?
             .clone();
 
-        let metadata = Self::extract_metadata(&yaml_doc);
+        let directories = if crate::tasks::expand::braces_enabled(yaml_doc)? {
+            crate::tasks::expand::expand_braces(&directories)?
+        } else {
+            directories
+        };
+        ensure_relative_keys(&directories)?;
+
+        let metadata = Self::extract_metadata(yaml_doc);
+        let variables = yaml_doc
+            .get("variables")
+            .map(variables_from_value)
+            .transpose()?
+            .unwrap_or_default();
 
         Ok(Self {
             directories,
             metadata,
+            variables,
         })
     }
 
-    /// Create a configuration from a file
+    /// Create a configuration from a YAML, JSON or TOML file (chosen by extension)
+    ///
+    /// `include:` files are merged in and `!file_ref` entries are loaded from their
+    /// sidecar files next to the config.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SkeletorError> {
         let path = path.as_ref();
-        let content = crate::utils::read_file_to_string(path)?;
-        Self::from_yaml_str(&content)
+        let mut yaml_doc = crate::utils::read_config_file(path)?;
+        resolve_includes(&mut yaml_doc, path)?;
+        let mut config = Self::from_value(&yaml_doc)?;
+        config.directories = resolve_file_refs(&config.directories, config_dir(path))?;
+        Ok(config)
     }
 
     fn extract_metadata(yaml_doc: &Value) -> Option<SkeletorMetadata> {
-        Some(SkeletorMetadata {
-            created: yaml_doc.get("created").and_then(|v| v.as_str()).map(|s| s.to_string()),
-            updated: yaml_doc.get("updated").and_then(|v| v.as_str()).map(|s| s.to_string()),
-            generated_comments: yaml_doc.get("generated_comments").and_then(|v| v.as_str()).map(|s| s.to_string()),
-            stats: yaml_doc.get("stats").and_then(|stats| {
-                let files = stats.get("files")?.as_u64()? as usize;
-                let directories = stats.get("directories")?.as_u64()? as usize;
-                Some((files, directories))
-            }),
-            ignore_patterns: yaml_doc.get("ignore_patterns").and_then(|v| {
-                v.as_sequence()?.iter()
-                    .map(|item| item.as_str().map(|s| s.to_string()))
-                    .collect::<Option<Vec<_>>>()
-            }),
-        })
+        Some(SkeletorMetadata::from_value(yaml_doc))
     }
 }
 
+#[allow(dead_code)]
 pub fn read_config(path: &Path) -> Result<Value, SkeletorError> {
-    let yaml_doc: Value = crate::utils::read_yaml_file(path)?;
+    let mut yaml_doc: Value = crate::utils::read_config_file(path)?;
+    resolve_includes(&mut yaml_doc, path)?;
+
+    let directories = Value::Mapping(directories_mapping(&yaml_doc)?.clone());
+    ensure_relative_keys(&directories)?;
+    Ok(directories)
+}
+
+/// Whether a `directories` key is an absolute path in Unix (`/etc/x`) or Windows
+/// (`C:\x`, `C:/x`, `\\server\share`) form, whichever platform reads the config
+pub fn is_absolute_key(key: &str) -> bool {
+    let bytes = key.as_bytes();
+    key.starts_with(['/', '\\']) || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+}
 
+/// Fails with [`SkeletorError::Config`] on the first absolute key under `directories`.
+///
+/// `Path::join` with an absolute path discards the base, so such a key would be
+/// written at that path rather than inside the output directory.
+pub fn ensure_relative_keys(directories: &Value) -> Result<(), SkeletorError> {
+    fn check(node: &Value, path: &str) -> Result<(), SkeletorError> {
+        let Some(mapping) = node.as_mapping().filter(|_| file_ref_path(node).is_none()) else {
+            return Ok(());
+        };
+        for (key, value) in mapping {
+            let Some(name) = key.as_str() else {
+                continue;
+            };
+            if is_absolute_key(name) {
+                return Err(SkeletorError::Config(format!(
+                    "'{}' in {} is an absolute path; keys must be relative to the output directory",
+                    name, path
+                )));
+            }
+            check(value, &format!("{}.{}", path, name))?;
+        }
+        Ok(())
+    }
+    check(directories, "directories")
+}
+
+/// Returns the document's `directories` mapping, failing with
+/// [`SkeletorError::MissingConfigKey`] when it is absent and
+/// [`SkeletorError::InvalidConfigEntry`] when it is not a mapping.
+pub fn directories_mapping(yaml_doc: &Value) -> Result<&serde_yaml::Mapping, SkeletorError> {
     let directories = yaml_doc
         .get("directories")
-        .and_then(Value::as_mapping)
         .ok_or_else(|| SkeletorError::missing_config_key("directories"))?;
+    directories.as_mapping().ok_or_else(|| {
+        SkeletorError::invalid_config_entry(
+            "directories",
+            format!("expected a mapping, found {}", type_name(directories)),
+        )
+    })
+}
+
+/// Names a YAML value's type for error messages
+pub fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "float",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Sequence(_) => "sequence",
+        Value::Mapping(_) => "mapping",
+        Value::Tagged(_) => "tagged value",
+    }
+}
+
+/// Merges the files listed under a top-level `include:` key into `yaml_doc`'s `directories`.
+///
+/// Includes resolve relative to the including file and may include others in turn.
+/// Later includes override earlier ones key by key, and the document's own
+/// `directories` override them all. Each included file's `!file_ref` entries are
+/// resolved against its own directory. The `include` key is removed afterwards.
+///
+/// Returns the path of every file merged in, in the order it was read: each include
+/// comes before the files it includes in turn.
+pub fn resolve_includes(yaml_doc: &mut Value, config_path: &Path) -> Result<Vec<PathBuf>, SkeletorError> {
+    let mut resolved = Vec::new();
+    resolve_includes_from(yaml_doc, config_path, &mut Vec::new(), &mut resolved)?;
+    Ok(resolved)
+}
+
+fn resolve_includes_from(
+    yaml_doc: &mut Value,
+    config_path: &Path,
+    chain: &mut Vec<PathBuf>,
+    resolved: &mut Vec<PathBuf>,
+) -> Result<(), SkeletorError> {
+    let Some(includes) = yaml_doc.as_mapping_mut().and_then(|doc| doc.remove("include")) else {
+        return Ok(());
+    };
+    let includes = match includes {
+        Value::String(path) => vec![path],
+        Value::Sequence(items) => items
+            .into_iter()
+            .map(|item| match item {
+                Value::String(path) => Ok(path),
+                _ => Err(SkeletorError::Config("'include' entries must be file paths".to_string())),
+            })
+            .collect::<Result<_, _>>()?,
+        _ => return Err(SkeletorError::Config("'include' must be a list of file paths".to_string())),
+    };
+
+    // Canonical paths so the same file reached by different spellings is still caught
+    chain.push(config_path.canonicalize().unwrap_or_else(|_| config_path.to_path_buf()));
+    let mut merged = Value::Mapping(serde_yaml::Mapping::new());
+    for include in includes {
+        let include_path = config_dir(config_path).join(&include);
+        let canonical = include_path
+            .canonicalize()
+            .map_err(|e| SkeletorError::from_io_with_context(e, include_path.clone()))?;
+        if chain.contains(&canonical) {
+            return Err(SkeletorError::Config(format!(
+                "Circular include: {} includes {}, which is already being included",
+                config_path.display(),
+                include_path.display()
+            )));
+        }
+
+        let mut included = crate::utils::read_config_file(&include_path)?;
+        resolved.push(include_path.clone());
+        resolve_includes_from(&mut included, &include_path, chain, resolved)?;
+        if let Some(directories) = included.get("directories") {
+            deep_merge(&mut merged, resolve_file_refs(directories, config_dir(&include_path))?);
+        }
+    }
+    chain.pop();
+
+    if let Some(own) = yaml_doc.get("directories") {
+        deep_merge(&mut merged, own.clone());
+    }
+    if let Some(doc) = yaml_doc.as_mapping_mut() {
+        doc.insert(Value::String("directories".to_string()), merged);
+    }
+    Ok(())
+}
+
+/// Merges `overlay` into `base`: mappings merge recursively, anything else replaces.
+///
+/// Returns the `/`-separated paths of the entries in `base` that the overlay replaced.
+pub fn deep_merge(base: &mut Value, overlay: Value) -> Vec<String> {
+    let mut overridden = Vec::new();
+    merge_at(base, overlay, "", &mut overridden);
+    overridden
+}
 
-    Ok(Value::Mapping(directories.clone()))
+fn merge_at(base: &mut Value, overlay: Value, path: &str, overridden: &mut Vec<String>) {
+    match (base, overlay) {
+        (Value::Mapping(base), Value::Mapping(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => {
+                        let name = key.as_str().unwrap_or_default();
+                        let child = if path.is_empty() {
+                            name.to_string()
+                        } else {
+                            format!("{}/{}", path, name)
+                        };
+                        merge_at(existing, value, &child, overridden);
+                    }
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => {
+            overridden.push(path.to_string());
+            *base = overlay;
+        }
+    }
+}
+
+/// Navigates a `directories` structure to the entry at a `/`-separated path.
+///
+/// Unknown segments fail with an error listing the entries available at that level.
+pub fn find_entry<'a>(directories: &'a Value, path: &str) -> Result<&'a Value, SkeletorError> {
+    let mut current = directories;
+    let mut walked: Vec<&str> = Vec::new();
+
+    for segment in path.split('/').filter(|s| !s.is_empty() && *s != ".") {
+        let next = current
+            .as_mapping()
+            .and_then(|mapping| mapping.get(Value::String(segment.to_string())));
+        match next {
+            Some(value) => current = value,
+            None => {
+                return Err(SkeletorError::Config(format!(
+                    "'{}' not found in config; entries under '{}/': {}",
+                    path,
+                    walked.join("/"),
+                    describe_entries(current)
+                )))
+            }
+        }
+        walked.push(segment);
+    }
+
+    Ok(current)
+}
+
+/// Lists the names in a directory node, suffixing subdirectories with `/`
+pub fn describe_entries(node: &Value) -> String {
+    let names: Vec<String> = node
+        .as_mapping()
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            key.as_str().map(|name| {
+                if matches!(value, Value::Mapping(_)) && file_ref_path(value).is_none() {
+                    format!("{}/", name)
+                } else {
+                    name.to_string()
+                }
+            })
+        })
+        .collect();
+
+    if names.is_empty() {
+        "(none)".to_string()
+    } else {
+        names.join(", ")
+    }
 }
 
 /// Returns the provided file path or defaults to ".skeletorrc".
@@ -94,6 +374,108 @@ pub fn default_file_path(arg: Option<&String>) -> PathBuf {
     }
 }
 
+/// The `.skeletorrc` to read when no config is named: the one in `start`, else, with
+/// `ascend`, the nearest one in a parent directory, the way git finds `.git`. When there is
+/// none, `start/.skeletorrc` is returned so the error names the expected location.
+pub fn locate_default_config(start: &Path, ascend: bool) -> PathBuf {
+    let here = start.join(".skeletorrc");
+    if !ascend || here.is_file() {
+        return here;
+    }
+    match start.ancestors().skip(1).map(|dir| dir.join(".skeletorrc")).find(|path| path.is_file()) {
+        Some(found) => {
+            info!("Using {:?} found above {:?}", found, start);
+            found
+        }
+        None => here,
+    }
+}
+
+/// [`locate_default_config`] from `--working-dir` (else the current directory), ascending unless `--no-ascend`
+pub fn default_config_from_matches(matches: &ArgMatches) -> PathBuf {
+    let start = working_dir_from_matches(matches)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_else(|| PathBuf::from("."));
+    locate_default_config(&start, !matches.get_flag("no_ascend"))
+}
+
+/// Where bare template names are looked up: `--template-dir`, then `$SKELETOR_TEMPLATE_DIR`,
+/// then `~/.config/skeletor/templates`. A relative directory resolves against `--working-dir`.
+pub fn template_dir_from_matches(matches: &ArgMatches) -> Option<PathBuf> {
+    let dir = matches
+        .get_one::<String>("template_dir")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("SKELETOR_TEMPLATE_DIR").map(PathBuf::from))
+        .or_else(|| {
+            let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+            Some(Path::new(&home).join(".config/skeletor/templates"))
+        })?;
+    Some(resolve_path(working_dir_from_matches(matches).as_deref(), dir))
+}
+
+/// Resolves a config argument, looking a bare name such as `rust` (no path separator
+/// or extension) up as `<name>.yml` in `template_dir`. Other arguments, and names
+/// with no such template, are used as paths.
+pub fn resolve_template_path(arg: &str, template_dir: Option<&Path>) -> PathBuf {
+    let bare = !arg.chars().any(std::path::is_separator) && Path::new(arg).extension().is_none();
+    if let Some(dir) = template_dir.filter(|_| bare) {
+        let template = dir.join(format!("{}.yml", arg));
+        if template.is_file() {
+            return template;
+        }
+    }
+    PathBuf::from(arg)
+}
+
+/// Reads the global `--working-dir` option as an absolute path.
+///
+/// Making it absolute keeps resolution idempotent: a path already resolved
+/// against it is returned unchanged if it is resolved again.
+pub fn working_dir_from_matches(matches: &ArgMatches) -> Option<PathBuf> {
+    let dir = PathBuf::from(matches.get_one::<String>("working_dir")?);
+    if dir.is_relative() {
+        std::env::current_dir().map(|cwd| cwd.join(&dir)).ok()
+    } else {
+        Some(dir)
+    }
+}
+
+/// Whether the global `--strict` flag asks for warnings to fail the command.
+pub fn strict_from_matches(matches: &ArgMatches) -> bool {
+    matches.get_flag("strict")
+}
+
+/// Whether the global `--quiet` flag asks for all non-error output to be suppressed.
+pub fn quiet_from_matches(matches: &ArgMatches) -> bool {
+    matches.get_flag("quiet")
+}
+
+/// Fails with [`SkeletorError::StrictWarnings`] when `strict` is set and any warnings were reported.
+pub fn check_strict(strict: bool, warnings: usize) -> Result<(), SkeletorError> {
+    if strict && warnings > 0 {
+        Err(SkeletorError::StrictWarnings { count: warnings })
+    } else {
+        Ok(())
+    }
+}
+
+/// Resolves a relative `path` against `working_dir`; absolute paths, or any
+/// path when no working directory is set, are returned unchanged.
+pub fn resolve_path(working_dir: Option<&Path>, path: PathBuf) -> PathBuf {
+    match working_dir {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path,
+    }
+}
+
+/// Directory that a config's relative references (such as `!file_ref` sidecars) resolve against.
+pub fn config_dir(config_path: &Path) -> &Path {
+    match config_path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,6 +496,21 @@ mod tests {
         assert_eq!(path, PathBuf::from("custom.yml"));
     }
 
+    #[test]
+    fn test_resolve_template_path_prefers_templates_for_bare_names() {
+        let fs = TestFileSystem::new();
+        let templates = fs.path("templates");
+        let rust = fs.create_file("templates/rust.yml", "directories: {}\n");
+        fs.create_file("templates/web.yaml", "directories: {}\n");
+
+        assert_eq!(resolve_template_path("rust", Some(&templates)), rust);
+        // Names with a separator or extension, and names with no template, stay paths
+        assert_eq!(resolve_template_path("rust.yml", Some(&templates)), PathBuf::from("rust.yml"));
+        assert_eq!(resolve_template_path("./rust", Some(&templates)), PathBuf::from("./rust"));
+        assert_eq!(resolve_template_path("web", Some(&templates)), PathBuf::from("web"));
+        assert_eq!(resolve_template_path("rust", None), PathBuf::from("rust"));
+    }
+
     #[test]
     fn test_skeletor_config_new() {
         let yaml_value = Value::String("test".to_string());
@@ -186,6 +583,26 @@ mod tests {
         assert_eq!(metadata.ignore_patterns, None);
     }
 
+    #[test]
+    fn test_metadata_serializes_absent_fields_as_null() {
+        let doc: Value = serde_yaml::from_str(
+            "created: '2023-01-01'\nstats:\n  files: 5\n  directories: 3\ndirectories: {}\n",
+        )
+        .unwrap();
+        let json = serde_json::to_value(SkeletorMetadata::from_value(&doc)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "created": "2023-01-01",
+                "updated": null,
+                "generated_comments": null,
+                "generated_with": null,
+                "stats": { "files": 5, "directories": 3 },
+                "ignore_patterns": null,
+            })
+        );
+    }
+
     #[test]
     fn test_skeletor_config_from_file_not_found() {
         let result = SkeletorConfig::from_file("nonexistent.yml");
@@ -219,6 +636,80 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_read_config_points_at_non_mapping_directories() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_file("config.yml", "directories: [src, docs]\n");
+
+        match read_config(&config_file) {
+            Err(SkeletorError::InvalidConfigEntry { path, reason }) => {
+                assert_eq!(path, "directories");
+                assert_eq!(reason, "expected a mapping, found sequence");
+            }
+            other => panic!("Expected InvalidConfigEntry error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_read_config_rejects_absolute_keys() {
+        let fs = TestFileSystem::new();
+        for key in ["/usr/local/bin/tool", "C:\\\\Windows\\\\tool.exe", "D:/tools/tool", "\\\\\\\\server\\\\share"] {
+            let config_file = fs.create_file("config.yml", &format!("directories:\n  bin:\n    \"{}\": x\n", key));
+            match read_config(&config_file) {
+                Err(SkeletorError::Config(message)) => {
+                    assert!(message.contains("directories.bin") && message.contains("must be relative"), "{}", message)
+                }
+                other => panic!("Expected {} to be rejected, got {:?}", key, other),
+            }
+        }
+
+        // Colons and backslashes later in a key are just part of the name
+        assert!(!is_absolute_key("notes:2024.md"));
+        assert!(!is_absolute_key("src\\lib.rs"));
+    }
+
+    #[test]
+    fn test_read_config_expands_anchors_and_aliases() {
+        let fs = TestFileSystem::new();
+        let config_file = fs.create_file(
+            "config.yml",
+            "shared: &license \"MIT\\n\"\ndirectories:\n  a:\n    LICENSE: *license\n    util: &util\n      mod.rs: '// util'\n  b:\n    LICENSE: *license\n    util: *util\n",
+        );
+
+        let directories = read_config(&config_file).unwrap();
+        assert_eq!(directories["b"]["LICENSE"], Value::String("MIT\n".to_string()));
+        assert_eq!(directories["b"]["util"], directories["a"]["util"]);
+
+        let tasks = crate::tasks::traverse_structure(Path::new("out"), &directories).unwrap();
+        assert!(tasks.contains(&crate::tasks::Task::File(PathBuf::from("out/b/util/mod.rs"), "// util".to_string())));
+    }
+
+    #[test]
+    fn test_read_config_rejects_alias_bombs() {
+        let fs = TestFileSystem::new();
+        // Each alias repeats a 70 KB anchor, expanding ~75 KB of YAML past 64 MiB
+        let mut yaml = format!("big: &big \"{}\"\ndirectories:\n  copies:\n", "x".repeat(70_000));
+        for i in 0..1000 {
+            yaml.push_str(&format!("    f{}: *big\n", i));
+        }
+        let config_file = fs.create_file("bomb.yml", &yaml);
+
+        match read_config(&config_file) {
+            Err(SkeletorError::Config(message)) => assert!(message.contains("aliases"), "{}", message),
+            other => panic!("Expected Config error, got {:?}", other.map(|_| ())),
+        }
+
+        // Exponential nesting trips the parser's own limits, reported the same way
+        let mut yaml = String::from("l0: &l0 [x, x, x, x, x, x, x, x, x]\n");
+        for level in 1..9 {
+            let prev = format!("*l{}", level - 1);
+            yaml.push_str(&format!("l{}: &l{} [{}]\n", level, level, vec![prev; 9].join(", ")));
+        }
+        yaml.push_str("directories:\n  x: *l8\n");
+        let config_file = fs.create_file("laughs.yml", &yaml);
+        assert!(matches!(read_config(&config_file), Err(SkeletorError::Config(_))));
+    }
+
     #[test]
     fn test_read_config_invalid() {
         let fs = TestFileSystem::new();
@@ -250,4 +741,64 @@ mod tests {
             panic!("Expected a YAML mapping");
         }
     }
+
+    #[test]
+    fn test_read_config_merges_includes() {
+        let fs = TestFileSystem::new();
+        fs.create_file("shared/base.yml", "include: [license.yml]\ndirectories:\n  .gitignore: target/\n  src:\n    lib.rs: base\n    util.rs: base\n");
+        fs.create_file("shared/license.yml", "directories:\n  LICENSE: MIT\n  .gitignore: overridden by base\n");
+        fs.create_file("shared/rust.yml", "directories:\n  src:\n    util.rs: rust\n");
+        let config = fs.create_file(
+            "project/.skeletorrc",
+            "include:\n  - ../shared/base.yml\n  - ../shared/rust.yml\ndirectories:\n  src:\n    lib.rs: local\n",
+        );
+
+        let directories = read_config(&config).unwrap();
+        assert_eq!(directories["LICENSE"].as_str(), Some("MIT"));
+
+        let mut yaml_doc = crate::utils::read_config_file(&config).unwrap();
+        let resolved = resolve_includes(&mut yaml_doc, &config).unwrap();
+        let names: Vec<_> = resolved.iter().map(|path| path.file_name().unwrap().to_str().unwrap()).collect();
+        assert_eq!(names, ["base.yml", "license.yml", "rust.yml"]);
+        assert_eq!(directories[".gitignore"].as_str(), Some("target/"));
+        assert_eq!(directories["src"]["util.rs"].as_str(), Some("rust"));
+        assert_eq!(directories["src"]["lib.rs"].as_str(), Some("local"));
+    }
+
+    #[test]
+    fn test_circular_include_is_an_error() {
+        let fs = TestFileSystem::new();
+        let config = fs.create_file("a.yml", "include: [b.yml]\ndirectories:\n  a.txt: a\n");
+        fs.create_file("b.yml", "include: [a.yml]\ndirectories:\n  b.txt: b\n");
+
+        match read_config(&config) {
+            Err(SkeletorError::Config(message)) => assert!(message.contains("Circular include")),
+            other => panic!("expected a circular include error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_locate_default_config_ascends_to_a_parent() {
+        let fs = TestFileSystem::new();
+        let config = fs.create_file(".skeletorrc", "directories: {}\n");
+        let nested = fs.create_dir("crates/core/src");
+
+        assert_eq!(locate_default_config(&nested, true), config);
+        assert_eq!(locate_default_config(&nested, false), nested.join(".skeletorrc"));
+
+        // The nearest one wins
+        let closer = fs.create_file("crates/.skeletorrc", "directories: {}\n");
+        assert_eq!(locate_default_config(&nested, true), closer);
+    }
+
+    #[test]
+    fn test_locate_default_config_not_found_names_the_start() {
+        let fs = TestFileSystem::new();
+        let nested = fs.create_dir("a/b");
+        // A stray .skeletorrc above the temp directory would be found, which is correct
+        if fs.root_path.ancestors().any(|dir| dir.join(".skeletorrc").is_file()) {
+            return;
+        }
+        assert_eq!(locate_default_config(&nested, true), nested.join(".skeletorrc"));
+    }
 }
@@ -0,0 +1,224 @@
+use crate::config::{config_dir, directories_mapping, resolve_includes, resolve_path, working_dir_from_matches};
+use crate::errors::SkeletorError;
+use crate::tasks::expand::{braces_enabled, expand_braces};
+use crate::tasks::variables::{collect_variables, substitute_variables_with_env};
+use crate::tasks::{resolve_file_refs, traverse_structure, Task};
+use clap::ArgMatches;
+use serde_yaml::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How one path planned by a template compares with the tree on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffStatus {
+    /// Nothing exists at the path yet
+    Missing,
+    /// The path exists with the template's content (or, for directories, exists)
+    Identical,
+    /// Something else exists at the path
+    Differs,
+}
+
+/// The classification of a single planned path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffEntry {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub status: DiffStatus,
+}
+
+/// Counts of a diff's entries, as printed in its summary line
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DiffSummary {
+    pub new_files: usize,
+    pub changed: usize,
+    pub identical: usize,
+    pub dirs_to_create: usize,
+}
+
+impl DiffSummary {
+    pub fn from_entries(entries: &[DiffEntry]) -> Self {
+        let mut summary = Self::default();
+        for entry in entries {
+            match (entry.is_dir, entry.status) {
+                (true, DiffStatus::Missing) => summary.dirs_to_create += 1,
+                (true, DiffStatus::Identical) => {}
+                (false, DiffStatus::Missing) => summary.new_files += 1,
+                (false, DiffStatus::Identical) => summary.identical += 1,
+                (_, DiffStatus::Differs) => summary.changed += 1,
+            }
+        }
+        summary
+    }
+
+    /// How many paths an apply would create or change
+    pub fn differences(&self) -> usize {
+        self.new_files + self.changed + self.dirs_to_create
+    }
+}
+
+/// Runs the diff subcommand: compares a template against an existing directory.
+///
+/// Fails with [`SkeletorError::TreeDiffers`] when applying the template would change anything.
+pub fn run_diff(matches: &ArgMatches) -> Result<(), SkeletorError> {
+    let working_dir = working_dir_from_matches(matches);
+    let config_path = resolve_path(
+        working_dir.as_deref(),
+        PathBuf::from(matches.get_one::<String>("template").unwrap()),
+    );
+    let dir = resolve_path(
+        working_dir.as_deref(),
+        PathBuf::from(matches.get_one::<String>("dir").unwrap()),
+    );
+    let vars: Vec<String> = matches
+        .get_many::<String>("var")
+        .map(|vals| vals.map(|v| v.to_string()).collect())
+        .unwrap_or_default();
+    let verbose = matches.get_flag("verbose");
+
+    let mut yaml_doc = crate::utils::read_config_file(&config_path)?;
+    resolve_includes(&mut yaml_doc, &config_path)?;
+    let directories = resolve_file_refs(
+        &Value::Mapping(directories_mapping(&yaml_doc)?.clone()),
+        config_dir(&config_path),
+    )?;
+    let variables = collect_variables(&yaml_doc, &vars)?;
+    let env_vars = matches.get_flag("env_vars");
+    let directories = if variables.is_empty() && !env_vars {
+        directories
+    } else {
+        substitute_variables_with_env(&directories, &variables, env_vars)?
+    };
+    let directories = if braces_enabled(&yaml_doc)? {
+        expand_braces(&directories)?
+    } else {
+        directories
+    };
+
+    let entries = diff_tasks(&traverse_structure(&dir, &directories)?);
+    if verbose {
+        for entry in &entries {
+            if let Some(line) = describe_entry(entry) {
+                println!("{}", line);
+            }
+        }
+    }
+
+    let summary = DiffSummary::from_entries(&entries);
+    println!(
+        "+ {} new files, ~ {} changed, = {} identical, {} dir{} to create",
+        summary.new_files,
+        summary.changed,
+        summary.identical,
+        summary.dirs_to_create,
+        if summary.dirs_to_create == 1 { "" } else { "s" }
+    );
+
+    match summary.differences() {
+        0 => Ok(()),
+        count => Err(SkeletorError::TreeDiffers { count }),
+    }
+}
+
+/// Classifies each task against what currently exists at its path.
+///
+/// Files compare byte for byte and symlinks by target; a directory differs only
+/// when something other than a directory is in its place.
+pub fn diff_tasks(tasks: &[Task]) -> Vec<DiffEntry> {
+    tasks
+        .iter()
+        .map(|task| {
+            let path = task.path();
+            let status = match task {
+                _ if fs::symlink_metadata(path).is_err() => DiffStatus::Missing,
+                Task::Dir(_) if path.is_dir() => DiffStatus::Identical,
+                Task::File(_, content) => match fs::read(path) {
+                    Ok(current) if current == content.as_bytes() => DiffStatus::Identical,
+                    _ => DiffStatus::Differs,
+                },
+                Task::Symlink(_, target) => match fs::read_link(path) {
+                    Ok(current) if current == Path::new(target) => DiffStatus::Identical,
+                    _ => DiffStatus::Differs,
+                },
+                Task::Dir(_) => DiffStatus::Differs,
+            };
+            DiffEntry {
+                path: path.to_path_buf(),
+                is_dir: task.is_dir(),
+                status,
+            }
+        })
+        .collect()
+}
+
+/// The `--verbose` line for one entry; directories that already exist are not listed
+fn describe_entry(entry: &DiffEntry) -> Option<String> {
+    let marker = match (entry.is_dir, entry.status) {
+        (true, DiffStatus::Identical) => return None,
+        (_, DiffStatus::Missing) => '+',
+        (_, DiffStatus::Differs) => '~',
+        (false, DiffStatus::Identical) => '=',
+    };
+    let suffix = if entry.is_dir { "/" } else { "" };
+    Some(format!("{} {}{}", marker, entry.path.display(), suffix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_diff_tasks_classifies_each_path() {
+        let fs = TestFileSystem::new();
+        fs.create_file("project/same.txt", "same");
+        fs.create_file("project/edited.txt", "local edit");
+        fs.create_file("project/src", "a file where a directory belongs");
+
+        let structure: Value = serde_yaml::from_str(
+            "same.txt: same\nedited.txt: template\nnew.txt: new\nsrc:\n  lib.rs: ''\ndocs:\n  guide.md: hi\n",
+        )
+        .unwrap();
+        let tasks = traverse_structure(&fs.path("project"), &structure).unwrap();
+        let entries = diff_tasks(&tasks);
+
+        let status_of = |name: &str| {
+            entries
+                .iter()
+                .find(|entry| entry.path == fs.path("project").join(name))
+                .map(|entry| entry.status)
+        };
+        assert_eq!(status_of("same.txt"), Some(DiffStatus::Identical));
+        assert_eq!(status_of("edited.txt"), Some(DiffStatus::Differs));
+        assert_eq!(status_of("new.txt"), Some(DiffStatus::Missing));
+        assert_eq!(status_of("src"), Some(DiffStatus::Differs));
+        assert_eq!(status_of("docs"), Some(DiffStatus::Missing));
+
+        let summary = DiffSummary::from_entries(&entries);
+        assert_eq!(
+            summary,
+            DiffSummary {
+                new_files: 3,
+                changed: 2,
+                identical: 1,
+                dirs_to_create: 1,
+            }
+        );
+        assert_eq!(summary.differences(), 6);
+    }
+
+    #[test]
+    fn test_run_diff_fails_only_on_drift() {
+        let fs = TestFileSystem::new();
+        let template = fs.create_file("template.yml", "directories:\n  src:\n    main.rs: fn main() {}\n");
+        let project = fs.path("project");
+
+        let args = vec![template.to_str().unwrap(), project.to_str().unwrap()];
+        let matches = create_diff_matches(args.clone()).unwrap();
+        assert!(matches!(run_diff(&matches), Err(SkeletorError::TreeDiffers { count: 2 })));
+
+        fs.create_file("project/src/main.rs", "fn main() {}");
+        let matches = create_diff_matches(args).unwrap();
+        assert!(run_diff(&matches).is_ok());
+    }
+}
@@ -24,6 +24,12 @@ pub enum SkeletorError {
     #[error("invalid YAML configuration: {message}\ntip: Validate your YAML syntax using an online YAML validator")]
     InvalidYaml { message: String },
     
+    #[error("invalid {format} configuration: {message}\ntip: Check the file's {format} syntax, or rename it if the extension does not match its format")]
+    InvalidConfig { format: String, message: String },
+    
+    #[error("invalid configuration at '{path}': {reason}\ntip: Fix or remove that entry; `skeletor validate` lists every problem in the file")]
+    InvalidConfigEntry { path: String, reason: String },
+    
     #[error("missing configuration key: '{key}'\ntip: Ensure your YAML file contains the required '{key}' section")]
     MissingConfigKey { key: String },
     
@@ -32,9 +38,53 @@ pub enum SkeletorError {
 
     #[error("invalid path in configuration: '{path}'\ntip: Remove absolute paths and '..' segments from YAML keys")]
     InvalidPath { path: String },
+
+    #[error("{count} problem(s) found in configuration\ntip: Every entry under 'directories' must be a string (file) or a mapping (directory)")]
+    InvalidStructure { count: usize },
+
+    #[error("{count} key(s) do not match the schema\ntip: Add or correct the keys listed above so the template declares what the schema requires")]
+    SchemaViolations { count: usize },
+
+    #[error("{count} file(s) already existed and were skipped\ntip: Use --overwrite to replace them, or drop --fail-on-skip")]
+    FilesSkipped { count: usize },
+
+    #[error("{count} path(s) differ from the template\ntip: Run `skeletor apply` to bring the directory in line; add --overwrite to replace changed files")]
+    TreeDiffers { count: usize },
+
+    #[error("{count} file(s) or directories could not be created\ntip: See the failures listed above; use --fail-fast to stop at the first one")]
+    FilesFailed { count: usize },
+
+    #[error("could not create '{path}': {reason}\ntip: Fix the cause and apply again; without --fail-fast, apply creates everything else and lists each failure")]
+    TaskFailed { path: String, reason: String },
+
+    #[error("{count} warning(s) treated as errors under --strict\ntip: Resolve the warnings above, or drop --strict to let them pass")]
+    StrictWarnings { count: usize },
+
+    #[error("checksum mismatch: recorded {expected}, computed {actual}\ntip: The directories section changed after the snapshot was taken; re-snapshot if the change is intended")]
+    ChecksumMismatch { expected: String, actual: String },
+
+    #[error("post-apply hook failed: '{command}' ({status})\ntip: Fix the command in the template's hooks, or rerun without --run-hooks to skip hooks")]
+    HookFailed { command: String, status: String },
+
+    #[error("file name is not valid UTF-8: {path:?}\ntip: Rename it, or pass --on-invalid-name skip (leave it out) or lossy (replace the invalid bytes)")]
+    InvalidFileName { path: PathBuf },
+
+    #[error("symlink cycle detected: '{path}' leads back to '{target}'\ntip: Remove the loop, or drop --follow-symlinks so links are recorded instead of followed")]
+    SymlinkCycle { path: PathBuf, target: PathBuf },
+
+    #[error("apply stopped at '{path}'; nothing was written\ntip: Answer n to keep a file, or a to overwrite it and every file after it")]
+    Aborted { path: String },
 }
 
 impl SkeletorError {
+    /// Process exit status for this error; skipped files under `--fail-on-skip` use 3
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::FilesSkipped { .. } => 3,
+            _ => 1,
+        }
+    }
+
     /// Creates a contextual IO error based on the operation and path
     pub fn from_io_with_context(error: io::Error, path: PathBuf) -> Self {
         match error.kind() {
@@ -65,6 +115,16 @@ impl SkeletorError {
         Self::InvalidYaml { message: message.into() }
     }
     
+    /// Creates a parse error that names the detected config format (JSON, TOML)
+    pub fn invalid_config(format: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::InvalidConfig { format: format.into(), message: message.into() }
+    }
+    
+    /// Creates an error pointing at a dotted location in the config (e.g. `directories.src.build.rs`)
+    pub fn invalid_config_entry(path: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InvalidConfigEntry { path: path.into(), reason: reason.into() }
+    }
+    
     /// Creates a missing config key error
     pub fn missing_config_key(key: impl Into<String>) -> Self {
         Self::MissingConfigKey { key: key.into() }
@@ -134,6 +194,21 @@ mod tests {
         assert!(error_str.contains("required 'directories' section"));
     }
 
+    #[test]
+    fn test_invalid_config_entry_error() {
+        let error = SkeletorError::invalid_config_entry("directories.src.build.rs", "expected string or mapping, found integer");
+        match &error {
+            SkeletorError::InvalidConfigEntry { path, reason } => {
+                assert_eq!(path, "directories.src.build.rs");
+                assert_eq!(reason, "expected string or mapping, found integer");
+            }
+            _ => panic!("Expected InvalidConfigEntry error"),
+        }
+        let error_str = error.to_string();
+        assert!(error_str.starts_with("invalid configuration at 'directories.src.build.rs': expected string"));
+        assert!(error_str.contains("tip:"));
+    }
+
     #[test]
     fn test_invalid_ignore_pattern_error() {
         let error = SkeletorError::InvalidIgnorePattern { 
@@ -271,4 +346,17 @@ mod tests {
             panic!("Expected YAML parsing to fail");
         }
     }
+
+    #[test]
+    fn test_symlink_cycle_error() {
+        let error = SkeletorError::SymlinkCycle {
+            path: PathBuf::from("a/to_b/to_a"),
+            target: PathBuf::from("/project/a"),
+        };
+        let error_str = error.to_string();
+        assert!(error_str.contains("a/to_b/to_a"));
+        assert!(error_str.contains("/project/a"));
+        assert!(error_str.contains("tip:"));
+        assert_eq!(error.exit_code(), 1);
+    }
 }
@@ -1,15 +1,81 @@
-use crate::config::default_file_path;
+use crate::apply::extract_binary_files_from_yaml;
+use crate::config::{
+    check_strict, config_dir, default_config_from_matches, quiet_from_matches, resolve_includes, resolve_path,
+    resolve_template_path, strict_from_matches, template_dir_from_matches, working_dir_from_matches,
+    SkeletorMetadata,
+};
 use crate::errors::SkeletorError;
+use crate::snapshot::{directories_checksum, render_tree};
+use crate::tasks::{compute_stats, resolve_file_refs};
 use clap::ArgMatches;
 use serde_yaml::Value;
+use std::path::PathBuf;
 
 /// Runs the info subcommand: prints annotation and stats information from a .skeletorrc file.
 pub fn run_info(matches: &ArgMatches) -> Result<(), SkeletorError> {
-    // Use default_file_path so that .skeletorrc is used by default.
-    let input_path = default_file_path(matches.get_one::<String>("config"));
+    // .skeletorrc is used by default, and a bare name may refer to a stored template
+    let config = match matches.get_one::<String>("config") {
+        Some(arg) => resolve_template_path(arg, template_dir_from_matches(matches).as_deref()),
+        None => default_config_from_matches(matches),
+    };
+    let input_path = resolve_path(working_dir_from_matches(matches).as_deref(), config);
 
-    let yaml_docs: Value = crate::utils::read_yaml_file(&input_path)?;
+    let yaml_docs: Value = crate::utils::read_config_file(&input_path)?;
 
+    let quiet = quiet_from_matches(matches);
+    // A file from a newer Skeletor is the one warning `--strict` turns into a failure
+    let newer_version = yaml_docs
+        .get("generated_with")
+        .and_then(Value::as_str)
+        .is_some_and(|version| is_newer_version(version, env!("CARGO_PKG_VERSION")));
+
+    // Resolved into a copy, so --verify still checks the checksum the file records
+    let resolved = if matches.get_flag("resolve_includes") {
+        let mut resolved_docs = yaml_docs.clone();
+        let includes = resolve_includes(&mut resolved_docs, &input_path)?;
+        Some((resolved_docs, includes))
+    } else {
+        None
+    };
+    let shown = resolved.as_ref().map_or(&yaml_docs, |(docs, _)| docs);
+
+    let json = matches.get_flag("json");
+    if !quiet {
+        if json {
+            let mut metadata = serde_json::to_value(SkeletorMetadata::from_value(shown))
+                .map_err(|e| SkeletorError::Config(format!("Failed to serialize metadata: {}", e)))?;
+            if let (Some((docs, includes)), Some(object)) = (&resolved, metadata.as_object_mut()) {
+                let includes: Vec<String> = includes.iter().map(|path| path.display().to_string()).collect();
+                object.insert("includes".to_string(), includes.into());
+                object.insert("top_level".to_string(), top_level_entries(docs).into());
+            }
+            let metadata = serde_json::to_string_pretty(&metadata)
+                .map_err(|e| SkeletorError::Config(format!("Failed to serialize metadata: {}", e)))?;
+            println!("{}", metadata);
+        } else {
+            print_metadata(shown, &input_path);
+            if let Some((docs, includes)) = &resolved {
+                print_includes(docs, includes);
+            }
+        }
+        if matches.get_flag("tree") {
+            print_structure(shown, &input_path);
+        }
+    }
+
+    if matches.get_flag("verify") {
+        let checksum = verify_checksum(&yaml_docs, &input_path)?;
+        // A failed check is reported as an error either way; success adds nothing to the JSON
+        if !quiet && !json {
+            println!("  Checksum verified: {}", checksum);
+        }
+    }
+
+    check_strict(strict_from_matches(matches), usize::from(newer_version))
+}
+
+/// Prints the metadata fields, noting the ones that are missing.
+fn print_metadata(yaml_docs: &Value, input_path: &std::path::Path) {
     println!("Information from {:?}:", input_path);
 
     if let Some(created) = yaml_docs.get("created").and_then(Value::as_str) {
@@ -30,13 +96,48 @@ pub fn run_info(matches: &ArgMatches) -> Result<(), SkeletorError> {
         println!("  No generated comments available.");
     }
 
+    let current = env!("CARGO_PKG_VERSION");
+    match yaml_docs.get("generated_with").and_then(Value::as_str) {
+        Some(version) => {
+            println!("  Generated by Skeletor {}", version);
+            if is_newer_version(version, current) {
+                println!(
+                    "  warning: this file was written by a newer Skeletor than this one ({}); fields it added may be ignored",
+                    current
+                );
+            } else {
+                println!("  Compatible with this Skeletor ({})", current);
+            }
+        }
+        None => println!("  Generated by Skeletor unknown"),
+    }
+
+    match yaml_docs.get("notes") {
+        Some(Value::String(note)) => println!("  Notes: {}", note),
+        Some(Value::Mapping(notes)) => {
+            println!("  Notes:");
+            for (key, value) in notes {
+                if let (Some(key), Some(value)) = (key.as_str(), value.as_str()) {
+                    println!("    {}: {}", key, value);
+                }
+            }
+        }
+        _ => println!("  No notes available."),
+    }
+
     if let Some(stats) = yaml_docs.get("stats").and_then(Value::as_mapping) {
         let files = stats.get("files").and_then(Value::as_u64).unwrap_or(0);
         let directories = stats
             .get("directories")
             .and_then(Value::as_u64)
             .unwrap_or(0);
-        println!("  Stats: {} files, {} directories", files, directories);
+        match stats.get("binary_files").and_then(Value::as_u64) {
+            Some(binaries) if binaries > 0 => println!(
+                "  Stats: {} files ({} binary), {} directories",
+                files, binaries, directories
+            ),
+            _ => println!("  Stats: {} files, {} directories", files, directories),
+        }
     } else {
         println!("  No stats available.");
     }
@@ -47,21 +148,103 @@ pub fn run_info(matches: &ArgMatches) -> Result<(), SkeletorError> {
     } else {
         println!("  No ignore patterns available.");
     }
+}
+
+/// Prints the files `include:` merged in and the top-level entries they produced.
+fn print_includes(yaml_docs: &Value, includes: &[PathBuf]) {
+    if includes.is_empty() {
+        println!("  No includes.");
+    } else {
+        println!("  Includes:");
+        for include in includes {
+            println!("    {}", include.display());
+        }
+    }
+    println!("  Resolved top level: {}", top_level_entries(yaml_docs).join(", "));
+}
+
+/// Names of the entries directly under `directories`, directories marked with a trailing `/`
+fn top_level_entries(yaml_docs: &Value) -> Vec<String> {
+    let Some(directories) = yaml_docs.get("directories").and_then(Value::as_mapping) else {
+        return Vec::new();
+    };
+    directories
+        .iter()
+        .filter_map(|(key, value)| {
+            let name = key.as_str()?;
+            Some(if value.is_mapping() { format!("{}/", name) } else { name.to_string() })
+        })
+        .collect()
+}
+
+/// Recomputes the checksum of the `directories` section and compares it with the recorded one.
+///
+/// Sidecar contents are loaded first, so externalized snapshots verify like inline ones.
+fn verify_checksum(yaml_docs: &Value, input_path: &std::path::Path) -> Result<String, SkeletorError> {
+    let expected = yaml_docs
+        .get("checksum")
+        .and_then(Value::as_str)
+        .ok_or_else(|| SkeletorError::Config("no checksum recorded; re-snapshot with this version of Skeletor to add one".to_string()))?;
+    let directories = yaml_docs
+        .get("directories")
+        .ok_or_else(|| SkeletorError::missing_config_key("directories"))?;
+    let actual = directories_checksum(&resolve_file_refs(directories, config_dir(input_path))?)?;
+
+    if actual != expected {
+        return Err(SkeletorError::ChecksumMismatch {
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+    Ok(actual)
+}
+
+/// Prints the `directories` section as a tree, followed by totals.
+fn print_structure(yaml_docs: &Value, input_path: &std::path::Path) {
+    let Some(directories) = yaml_docs.get("directories") else {
+        println!("  No directories section available.");
+        return;
+    };
+    let root_name = input_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| input_path.display().to_string());
+    let binary_files = extract_binary_files_from_yaml(yaml_docs);
+    let (files, dirs) = compute_stats(directories);
+
+    println!();
+    print!("{}", render_tree(&root_name, directories, &binary_files));
+    println!();
+    println!("{} files, {} directories", files, dirs);
+}
+
+/// Whether `version` is newer than `current`, comparing `major.minor.patch` numerically.
+/// Pre-release or build suffixes are ignored; unparseable versions never count as newer.
+fn is_newer_version(version: &str, current: &str) -> bool {
+    fn parse(version: &str) -> Option<(u64, u64, u64)> {
+        let core = version.trim().trim_start_matches('v');
+        let core = core.split(['-', '+']).next()?;
+        let mut parts = core.split('.');
+        // Missing minor/patch components count as 0
+        let mut next = || parts.next().map_or(Some(0), |part| part.parse::<u64>().ok());
+        Some((next()?, next()?, next()?))
+    }
 
-    Ok(())
+    match (parse(version), parse(current)) {
+        (Some(version), Some(current)) => version > current,
+        _ => false,
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::test_utils::helpers::*;
-    use std::env;
 
     #[test]
     fn test_run_info_defaults_to_local_config() {
         let fs = TestFileSystem::new();
         
-        let _guard = cwd_lock();
         // Create .skeletorrc with metadata
         let config_content = r#"
 created: "2020-01-01T00:00:00Z"
@@ -78,17 +261,13 @@ ignore_patterns:
 "#;
         let _config_path = fs.create_file(".skeletorrc", config_content);
         
-        // Change to temp directory so the test finds .skeletorrc
-        let orig_dir = env::current_dir().unwrap();
-        env::set_current_dir(&fs.root_path).unwrap();
-
-        let args = vec![];
+        // --working-dir points the default .skeletorrc lookup at the temp directory
+        let args = vec!["--working-dir", fs.root_path.to_str().unwrap()];
         if let Some(sub_m) = create_info_matches(args) {
             assert_command_succeeds(|| run_info(&sub_m));
         } else {
             panic!("Info subcommand not found");
         }
-        env::set_current_dir(orig_dir).unwrap();
     }
 
     #[test]
@@ -116,6 +295,154 @@ ignore_patterns:
         }
     }
 
+    #[test]
+    fn test_run_info_with_tree() {
+        let fs = TestFileSystem::new();
+        let config_path = fs.create_file("config.yaml", r#"
+directories:
+  src:
+    main.rs: "fn main() {}"
+  assets:
+    logo.png: ""
+binary_files:
+  - assets/logo.png
+"#);
+
+        let args = vec![config_path.to_str().unwrap(), "--tree"];
+        if let Some(sub_m) = create_info_matches(args) {
+            assert_command_succeeds(|| run_info(&sub_m));
+        } else {
+            panic!("Info subcommand not found");
+        }
+    }
+
+    #[test]
+    fn test_run_info_resolve_includes() {
+        let fs = TestFileSystem::new();
+        fs.create_file("shared/base.yml", "directories:\n  LICENSE: MIT\n  src:\n    lib.rs: base\n");
+        let config_path = fs.create_file(
+            "app.yml",
+            "include: [shared/base.yml]\ndirectories:\n  src:\n    main.rs: app\n  README.md: app\n",
+        );
+
+        let mut yaml_docs = crate::utils::read_config_file(&config_path).unwrap();
+        let includes = resolve_includes(&mut yaml_docs, &config_path).unwrap();
+        assert_eq!(includes, vec![config_path.parent().unwrap().join("shared/base.yml")]);
+        assert_eq!(top_level_entries(&yaml_docs), ["LICENSE", "src/", "README.md"]);
+
+        for extra in [vec!["--tree"], vec!["--json"]] {
+            let mut args = vec![config_path.to_str().unwrap(), "--resolve-includes"];
+            args.extend(extra);
+            let sub_m = create_info_matches(args).expect("Info subcommand not found");
+            assert_command_succeeds(|| run_info(&sub_m));
+        }
+
+        // Circular and missing includes fail instead of printing a partial picture
+        fs.create_file("loop.yml", "include: [loop.yml]\ndirectories: {}\n");
+        fs.create_file("missing.yml", "include: [nowhere.yml]\ndirectories: {}\n");
+        for name in ["loop.yml", "missing.yml"] {
+            let config_path = fs.path(name);
+            let sub_m = create_info_matches(vec![config_path.to_str().unwrap(), "--resolve-includes"])
+                .expect("Info subcommand not found");
+            assert_command_fails(|| run_info(&sub_m));
+            // Without the flag, info only reads the file itself
+            let sub_m = create_info_matches(vec![config_path.to_str().unwrap()]).expect("Info subcommand not found");
+            assert_command_succeeds(|| run_info(&sub_m));
+        }
+    }
+
+    #[test]
+    fn test_run_info_verify_detects_tampering() {
+        let fs = TestFileSystem::new();
+        fs.create_file("project/src/main.rs", "fn main() {}");
+        let output = fs.path("snapshot.yml");
+        let opts = crate::snapshot::SnapshotOptions::default();
+        let mut file = std::fs::File::create(&output).unwrap();
+        crate::snapshot::snapshot_to_writer(&fs.path("project"), &opts, &mut file).unwrap();
+
+        let verify = |path: &std::path::Path| {
+            let sub_m = create_info_matches(vec![path.to_str().unwrap(), "--verify"]).unwrap();
+            run_info(&sub_m)
+        };
+        assert_command_succeeds(|| verify(&output));
+
+        // Timestamps are not covered, contents are
+        let mut snapshot: Value = crate::utils::read_yaml_file(&output).unwrap();
+        snapshot["updated"] = Value::String("1999-01-01T00:00:00Z".to_string());
+        std::fs::write(&output, serde_yaml::to_string(&snapshot).unwrap()).unwrap();
+        assert_command_succeeds(|| verify(&output));
+
+        snapshot["directories"]["src"]["main.rs"] = Value::String("fn main() { evil() }".to_string());
+        std::fs::write(&output, serde_yaml::to_string(&snapshot).unwrap()).unwrap();
+        assert!(matches!(verify(&output), Err(SkeletorError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn test_run_info_with_structured_notes() {
+        let fs = TestFileSystem::new();
+        let config_path = fs.create_file("config.yaml", r#"
+notes:
+  author: "Jane"
+  ticket: "ABC-123"
+  description: "Initial snapshot"
+directories:
+  src:
+    main.rs: "fn main() {}"
+"#);
+
+        let args = vec![config_path.to_str().unwrap()];
+        if let Some(sub_m) = create_info_matches(args) {
+            assert_command_succeeds(|| run_info(&sub_m));
+        } else {
+            panic!("Info subcommand not found");
+        }
+    }
+
+    #[test]
+    fn test_run_info_strict_fails_on_newer_version() {
+        let fs = TestFileSystem::new();
+        let config_path = fs.create_file("config.yaml", r#"
+generated_with: "999.0.0"
+directories:
+  src:
+    main.rs: "fn main() {}"
+"#);
+
+        let sub_m = create_info_matches(vec![config_path.to_str().unwrap()]).expect("Info subcommand not found");
+        assert_command_succeeds(|| run_info(&sub_m));
+
+        let sub_m = create_info_matches(vec![config_path.to_str().unwrap(), "--strict"])
+            .expect("Info subcommand not found");
+        assert!(matches!(run_info(&sub_m), Err(SkeletorError::StrictWarnings { count: 1 })));
+    }
+
+    #[test]
+    fn test_is_newer_version() {
+        assert!(is_newer_version("0.4.0", "0.3.16"));
+        assert!(is_newer_version("1.0", "0.9.9"));
+        assert!(!is_newer_version("0.3.16", "0.3.16"));
+        assert!(!is_newer_version("0.3.2-beta", "0.3.16"));
+        assert!(!is_newer_version("not-a-version", "0.3.16"));
+    }
+
+    #[test]
+    fn test_run_info_with_generated_with() {
+        let fs = TestFileSystem::new();
+        let config_path = fs.create_file("config.yaml", r#"
+generated_with: "99.0.0"
+directories:
+  src:
+    main.rs: "fn main() {}"
+"#);
+
+        let args = vec![config_path.to_str().unwrap()];
+        if let Some(sub_m) = create_info_matches(args) {
+            assert_command_succeeds(|| run_info(&sub_m));
+        } else {
+            panic!("Info subcommand not found");
+        }
+    }
+
     #[test]
     fn test_run_info_with_missing_file() {
         let args = vec!["missing.yaml"];
@@ -28,18 +28,31 @@
 //! let result = skeletor::apply_config(&config, Path::new("./my-project"), false, false)?;
 //!
 //! println!("Created {} files and {} directories", result.files_created, result.dirs_created);
+//!
+//! // Capture an existing folder without writing anything to disk
+//! let opts = skeletor::SnapshotOptions::builder()
+//!     .ignore("*.log")
+//!     .note("Captured from my tool")
+//!     .build();
+//! let (stats, snapshot) = skeletor::take_snapshot(Path::new("./my-project"), &opts)?;
+//! println!("Captured {} files", stats.files_processed);
+//! println!("{}", serde_yaml::to_string(&snapshot)?);
 //! # Ok(())
 //! # }
 //! ```
 
 pub mod apply;
+pub mod cat;
 pub mod config;
+pub mod diff;
 pub mod errors;
 pub mod info;
 pub mod output;
 pub mod snapshot;
 pub mod tasks;
+pub mod templates;
 pub mod utils;
+pub mod validate;
 
 #[cfg(test)]
 pub mod test_utils;
@@ -47,6 +60,11 @@ pub mod test_utils;
 // Re-export key types for library users
 pub use crate::config::{SkeletorConfig, SkeletorMetadata};
 pub use crate::errors::SkeletorError;
+pub use crate::output::{OutputFormat, Reporter, SimpleApplyResult, SimpleSnapshotResult};
+#[cfg(feature = "progress-bar")]
+pub use crate::output::ProgressReporter;
+pub use crate::snapshot::{snapshot_to_writer, take_snapshot, SnapshotOptions, SnapshotOptionsBuilder, SnapshotResult, SortOrder};
+pub use crate::tasks::{BinaryDetection, DetectionStrategy, InvalidNamePolicy, Task};
 
 use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
@@ -59,53 +77,314 @@ pub struct ApplyResult {
     pub dirs_created: usize,
     pub duration: Duration,
     pub tasks_total: usize,
+    /// What would be created, in task order; only populated for dry runs
+    pub planned_tasks: Option<Vec<TaskSummary>>,
+    /// Planned files that would be written: new ones, plus existing ones when overwriting.
+    /// Only counted for dry runs; 0 otherwise
+    pub would_create: usize,
+    /// Planned files that already exist and would be left alone, because `overwrite` is
+    /// off or `.skeletorkeep` keeps them. Only counted for dry runs; 0 otherwise
+    pub would_skip: usize,
+    /// `(path, reason)` for each entry that could not be created; the apply carries on
+    /// past these, so check this before treating the run as a success. Empty for dry runs
+    pub failed_files: Vec<(PathBuf, String)>,
+}
+
+/// Kind of entry a planned task creates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskKind {
+    File,
+    Dir,
+    Symlink,
+}
+
+/// A single planned operation from a dry run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskSummary {
+    pub path: PathBuf,
+    pub kind: TaskKind,
+}
+
+impl From<&tasks::Task> for TaskSummary {
+    fn from(task: &tasks::Task) -> Self {
+        let kind = match task {
+            tasks::Task::File(_, _) => TaskKind::File,
+            tasks::Task::Dir(_) => TaskKind::Dir,
+            tasks::Task::Symlink(_, _) => TaskKind::Symlink,
+        };
+        Self {
+            path: task.path().to_path_buf(),
+            kind,
+        }
+    }
 }
 
-/// Result of taking a directory snapshot
+/// Options for [`apply_config_with`]
 #[derive(Debug, Clone)]
-pub struct SnapshotResult {
-    pub files_processed: usize,
-    pub dirs_processed: usize,
-    pub duration: Duration,
-    pub output_path: PathBuf,
-    pub binary_files_excluded: usize,
+pub struct ApplyOptions {
+    /// Directory the structure is created in
+    pub target_dir: PathBuf,
+    /// Replace files that already exist
+    pub overwrite: bool,
+    /// Plan the tasks without touching the filesystem
+    pub dry_run: bool,
+    /// Create tasks in batches of this size as they are planned, rather than planning
+    /// everything first; bounds memory for very large configs (ignored for dry runs)
+    pub batch_size: Option<usize>,
+    /// Write each file to a temporary file and rename it into place, so an interrupted
+    /// apply never leaves a partially written file (default: true)
+    pub atomic: bool,
 }
 
-/// Basic apply function for library usage
+impl Default for ApplyOptions {
+    fn default() -> Self {
+        Self {
+            target_dir: PathBuf::from("."),
+            overwrite: false,
+            dry_run: false,
+            batch_size: None,
+            atomic: true,
+        }
+    }
+}
+
+/// Basic apply function for library usage.
+///
+/// Fails with [`SkeletorError::Config`] before writing anything when an entry would land
+/// outside `target_dir`, such as under a directory that is a symlink to elsewhere.
 pub fn apply_config(
     config: &SkeletorConfig,
     target_dir: &Path,
     overwrite: bool,
     dry_run: bool,
+) -> Result<ApplyResult, SkeletorError> {
+    let opts = ApplyOptions {
+        target_dir: target_dir.to_path_buf(),
+        overwrite,
+        dry_run,
+        ..ApplyOptions::default()
+    };
+    apply_config_with(config, &opts, |_| Ok(()))
+}
+
+/// Plans the tasks applying `config` under `target` would run, without touching the filesystem.
+///
+/// Variables are substituted as in [`apply_config`]. Tasks come in traversal order, so a
+/// directory's task always precedes the tasks for its contents.
+///
+/// ```
+/// use skeletor::{plan_tasks, SkeletorConfig};
+/// use std::path::Path;
+///
+/// # fn main() -> Result<(), skeletor::SkeletorError> {
+/// let config = SkeletorConfig::from_yaml_str("directories:\n  src:\n    main.rs: 'fn main() {}'\n")?;
+/// let tasks = plan_tasks(&config, Path::new("out"))?;
+/// assert!(tasks[0].is_dir());
+/// assert_eq!(tasks[1].path(), Path::new("out/src/main.rs"));
+/// # Ok(())
+/// # }
+/// ```
+pub fn plan_tasks(config: &SkeletorConfig, target: &Path) -> Result<Vec<Task>, SkeletorError> {
+    let mut directories = config.directories.clone();
+    if !config.variables.is_empty() {
+        directories = tasks::variables::substitute_variables(&directories, &config.variables)?;
+    }
+    tasks::traverse_structure(target, &directories)
+}
+
+/// Like [`apply_config`], but lets `transform` edit a copy of the config's
+/// `directories` before any tasks are planned, e.g. to inject or rename entries.
+///
+/// The transform runs before variable substitution, so `${name}` placeholders it
+/// generates are substituted like those written in the config.
+pub fn apply_config_with(
+    config: &SkeletorConfig,
+    opts: &ApplyOptions,
+    transform: impl FnOnce(&mut serde_yaml::Value) -> Result<(), SkeletorError>,
+) -> Result<ApplyResult, SkeletorError> {
+    apply_config_reporting(config, opts, transform, &output::SilentReporter)
+}
+
+/// Like [`apply_config_with`], but reports to `reporter` as the apply runs, e.g. to
+/// drive a GUI or TUI progress view.
+///
+/// Each created task reaches [`Reporter::progress`] and then [`Reporter::task_success`]
+/// or [`Reporter::task_warning`], and the totals reach [`Reporter::apply_complete`].
+/// A dry run calls [`Reporter::dry_run_preview`] with the planned tasks instead. With
+/// [`ApplyOptions::batch_size`], only `apply_complete` is called.
+///
+/// ```
+/// use skeletor::{apply_config_with_reporter, ApplyOptions, OutputFormat, SkeletorConfig};
+/// use skeletor::output::DefaultReporter;
+///
+/// # fn main() -> Result<(), skeletor::SkeletorError> {
+/// let config = SkeletorConfig::from_yaml_str("directories:\n  src:\n    main.rs: 'fn main() {}'\n")?;
+/// let opts = ApplyOptions { target_dir: "out".into(), dry_run: true, ..ApplyOptions::default() };
+/// let reporter = DefaultReporter::with_format(OutputFormat::Plain);
+/// let result = apply_config_with_reporter(&config, &opts, &reporter)?;
+/// assert_eq!(result.tasks_total, 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn apply_config_with_reporter(
+    config: &SkeletorConfig,
+    opts: &ApplyOptions,
+    reporter: &dyn Reporter,
+) -> Result<ApplyResult, SkeletorError> {
+    apply_config_reporting(config, opts, |_| Ok(()), reporter)
+}
+
+fn apply_config_reporting(
+    config: &SkeletorConfig,
+    opts: &ApplyOptions,
+    transform: impl FnOnce(&mut serde_yaml::Value) -> Result<(), SkeletorError>,
+    reporter: &dyn Reporter,
 ) -> Result<ApplyResult, SkeletorError> {
     let start_time = Instant::now();
-    let tasks = tasks::traverse_structure(target_dir, &config.directories)?;
+    let target_dir = opts.target_dir.as_path();
+    let mut directories = config.directories.clone();
+    transform(&mut directories)?;
+    if !config.variables.is_empty() {
+        directories = tasks::variables::substitute_variables(&directories, &config.variables)?;
+    }
+    if let (Some(batch_size), false) = (opts.batch_size, opts.dry_run) {
+        tasks::ensure_output_root(target_dir)?;
+        let keep = tasks::load_keep_matcher(target_dir)?;
+        let (result, tasks_total) = tasks::create_files_and_directories_batched(
+            target_dir,
+            &directories,
+            batch_size,
+            opts.overwrite,
+            keep.as_ref(),
+            opts.atomic,
+        )?;
+        let duration = start_time.elapsed();
+        let (files_created, dirs_created) = (result.files_created, result.dirs_created);
+        let failed_files = failed_paths(&result);
+        reporter.apply_complete(&result.into_apply_result(duration, tasks_total), false);
+        return Ok(ApplyResult {
+            files_created,
+            dirs_created,
+            duration,
+            tasks_total,
+            planned_tasks: None,
+            would_create: 0,
+            would_skip: 0,
+            failed_files,
+        });
+    }
+    let tasks = tasks::traverse_structure(target_dir, &directories)?;
+    tasks::ensure_within_output(target_dir, &tasks)?;
     
-    if dry_run {
-        // For dry run, just return the task count
+    if opts.dry_run {
+        reporter.dry_run_preview(&tasks);
+        let keep = tasks::load_keep_matcher(target_dir)?;
+        let (would_skip, would_create) = tasks
+            .iter()
+            .filter_map(|task| match task {
+                tasks::Task::File(path, _) => Some(path),
+                _ => None,
+            })
+            .fold((0, 0), |(skip, create), path| {
+                if path.exists() && (!opts.overwrite || tasks::is_kept(keep.as_ref(), path)) {
+                    (skip + 1, create)
+                } else {
+                    (skip, create + 1)
+                }
+            });
         Ok(ApplyResult {
             files_created: 0,
             dirs_created: 0,
             duration: start_time.elapsed(),
             tasks_total: tasks.len(),
+            planned_tasks: Some(tasks.iter().map(TaskSummary::from).collect()),
+            would_create,
+            would_skip,
+            failed_files: Vec::new(),
         })
     } else {
-        let result = tasks::create_files_and_directories(&tasks, overwrite)?;
+        tasks::ensure_output_root(target_dir)?;
+        let keep = tasks::load_keep_matcher(target_dir)?;
+        let options = tasks::FileWriteOptions {
+            overwrite: opts.overwrite,
+            atomic: opts.atomic,
+            keep: keep.as_ref(),
+            ..Default::default()
+        };
+        let result = tasks::create_files_and_directories_with(&tasks, &options, reporter)?;
+        let duration = start_time.elapsed();
+        let (files_created, dirs_created) = (result.files_created, result.dirs_created);
+        let failed_files = failed_paths(&result);
+        reporter.apply_complete(&result.into_apply_result(duration, tasks.len()), false);
         
         Ok(ApplyResult {
-            files_created: result.files_created,
-            dirs_created: result.dirs_created,
-            duration: start_time.elapsed(),
+            files_created,
+            dirs_created,
+            duration,
             tasks_total: tasks.len(),
+            planned_tasks: None,
+            would_create: 0,
+            would_skip: 0,
+            failed_files,
         })
     }
 }
 
-// Note: Full snapshot library API implementation would require refactoring 
-// the snapshot module to separate CLI concerns from core logic.
-// For now, snapshot functionality is available through the CLI interface.
+fn failed_paths(result: &tasks::CreationResult) -> Vec<(PathBuf, String)> {
+    result
+        .failed_files_list
+        .iter()
+        .map(|(path, reason)| (PathBuf::from(path), reason.clone()))
+        .collect()
+}
+
+/// Shared `--format` option for subcommands that report results
+fn format_arg() -> Arg {
+    Arg::new("format")
+        .long("format")
+        .value_name("FORMAT")
+        .value_parser(["pretty", "plain", "json", "github"])
+        .default_value("pretty")
+        .help("Output format for results (default: pretty; json emits one machine-readable object to stdout; github emits GitHub Actions annotations)")
+}
+
+/// Shared `--var` option for subcommands that substitute template variables
+fn var_arg() -> Arg {
+    Arg::new("var")
+        .long("var")
+        .value_name("KEY=VALUE")
+        .help("Set a value for ${KEY} placeholders in names and contents (repeatable; overrides the config's `variables`)")
+        .action(ArgAction::Append)
+}
+
+fn no_ascend_arg() -> Arg {
+    Arg::new("no_ascend")
+        .long("no-ascend")
+        .help("Without a config argument, only read .skeletorrc from the current (or --working-dir) directory instead of the nearest one in a parent directory (default: search upward)")
+        .action(ArgAction::SetTrue)
+}
+
+fn env_vars_arg() -> Arg {
+    Arg::new("env_vars")
+        .long("env-vars")
+        .help("Fill ${NAME} placeholders left undefined by `variables` and --var from environment variables; their values end up in the generated files (default: off)")
+        .action(ArgAction::SetTrue)
+}
+
+/// Like [`apply_config`], but resolves a relative `target_dir` against `working_dir`
+/// instead of the process's current directory, which is never changed.
+pub fn apply_config_in(
+    config: &SkeletorConfig,
+    working_dir: &Path,
+    target_dir: &Path,
+    overwrite: bool,
+    dry_run: bool,
+) -> Result<ApplyResult, SkeletorError> {
+    let target_dir = config::resolve_path(Some(working_dir), target_dir.to_path_buf());
+    apply_config(config, &target_dir, overwrite, dry_run)
+}
 
-/// Build the CLI interface with three subcommands: `apply`, `snapshot` and `info`
+/// Build the CLI interface with subcommands: `apply`, `snapshot`, `info`, `cat`, `diff`, `templates` and `validate`
 /// This function is used by both the main CLI and by tests to ensure consistency
 pub fn build_cli() -> Command {
     Command::new("Skeletor")
@@ -113,21 +392,59 @@ pub fn build_cli() -> Command {
         .author("Jason Joseph Nathan")
         .about("A blazing-fast Rust scaffolding tool with snapshot capabilities.\n\nSkeletor helps you create project templates and scaffold new projects from YAML configurations.\nYou can capture existing folder structures as templates and apply them to create new projects.\n\nCommon workflow:\n  1. skeletor snapshot my-project -o template.yml  # Capture existing project\n  2. skeletor apply template.yml                   # Apply template elsewhere")
         .subcommand_required(true)
+        .arg(
+            Arg::new("working_dir")
+                .long("working-dir")
+                .value_name("DIR")
+                .global(true)
+                .help("Resolve relative config, source and output paths against DIR instead of the current directory (default: current directory)"),
+        )
+        .arg(
+            Arg::new("template_dir")
+                .long("template-dir")
+                .value_name("DIR")
+                .global(true)
+                .help("Look up bare template names such as `rust` as DIR/rust.yml (default: $SKELETOR_TEMPLATE_DIR, else ~/.config/skeletor/templates)"),
+        )
+        .arg(
+            Arg::new("quiet")
+                .short('q')
+                .long("quiet")
+                .global(true)
+                .help("Suppress all output except errors and requested data (YAML on stdout, cat contents); exit codes are unchanged (default: off)")
+                .action(ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .global(true)
+                .help("Treat warnings as errors: exit with status 1 if any were reported (default: off)")
+                .action(ArgAction::SetTrue),
+        )
         .subcommand(
             Command::new("apply")
-                .about("Creates files and directories based on a YAML configuration\n\nEXAMPLES:\n  skeletor apply                           # Use .skeletorrc config in current dir\n  skeletor apply my-template.yml           # Use custom config in current dir\n  skeletor apply -o ../new-project         # Apply to different directory\n  skeletor apply --dry-run                 # Preview changes (summary)\n  skeletor apply --dry-run --verbose       # Preview changes (full listing)")
+                .about("Creates files and directories based on a YAML configuration\n\nEXAMPLES:\n  skeletor apply                           # Use .skeletorrc config in current dir\n  skeletor apply my-template.yml           # Use custom config in current dir\n  skeletor apply base.yml overlay.yml      # Overlay one template on another\n  skeletor apply -o ../new-project         # Apply to different directory\n  skeletor apply --dry-run                 # Preview changes (summary)\n  skeletor apply --dry-run --verbose       # Preview changes (full listing)")
                 .arg(
                     Arg::new("config")
                         .value_name("CONFIG_FILE")
-                        .help("YAML configuration file (default: .skeletorrc)")
+                        .help("YAML configuration file; give several to deep-merge their directories left to right, later files winning (default: .skeletorrc)")
+                        .num_args(1..)
+                        .action(ArgAction::Append)
                         .index(1),
                 )
+                .arg(
+                    Arg::new("stdin")
+                        .long("stdin")
+                        .help("Read the YAML configuration from standard input instead of a file (default: off; relative `target:` and `!file_ref` paths resolve against the working directory)")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("config"),
+                )
                 .arg(
                     Arg::new("output")
                         .short('o')
                         .long("output")
                         .value_name("DIR")
-                        .help("Output directory where files will be created (default: current directory)"),
+                        .help("Output directory where files will be created (default: the config's `target`, else current directory)"),
                 )
                 .arg(
                     Arg::new("overwrite")
@@ -142,17 +459,141 @@ pub fn build_cli() -> Command {
                         .help("Preview changes without writing files (default: off; summary by default)")
                         .action(ArgAction::SetTrue),
                 )
+                .arg(
+                    Arg::new("cache")
+                        .long("cache")
+                        .value_name("DIR")
+                        .help("Cache dry-run fingerprints in DIR and skip unchanged repeat previews (default: off)\n  • Invalidated when the config changes or any targeted path in the output directory changes")
+                        .requires("dry_run"),
+                )
                 .arg(
                     Arg::new("verbose")
                         .short('v')
                         .long("verbose")
                         .help("Show full operation listing (default: off; affects dry-run and apply output)")
                         .action(ArgAction::SetTrue),
+                )
+                .arg(format_arg())
+                .arg(
+                    Arg::new("fail_fast")
+                        .long("fail-fast")
+                        .help("Stop at the first file or directory that cannot be created (default: off; create the rest and list every failure)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("clean")
+                        .long("clean")
+                        .help("Delete files in the output directory that the template does not define; requires --overwrite (default: off)")
+                        .requires("overwrite")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("run_hooks")
+                        .long("run-hooks")
+                        .help("Run the template's `hooks.post_apply` commands after a successful apply (default: off, hooks are only listed)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("skip_hidden")
+                        .long("skip-hidden")
+                        .help("Skip dotfiles and dot-directories (and everything beneath them) (default: off)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(var_arg())
+                .arg(env_vars_arg())
+                .arg(no_ascend_arg())
+                .arg(
+                    Arg::new("update")
+                        .long("update")
+                        .help("Rewrite existing files only when their content differs from the template (default: off)\n  • Identical files are left untouched and reported as unchanged")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("parallel")
+                        .long("parallel")
+                        .help("Write files concurrently; useful for templates with thousands of files (default: off)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("interactive")
+                        .short('p')
+                        .long("interactive")
+                        .help("Ask before replacing each existing file whose contents differ: y(es), n(o), a(ll remaining) or q(uit before writing anything) (default: off; without a terminal every answer is no)")
+                        .conflicts_with("on_local_edit")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("backup")
+                        .long("backup")
+                        .value_name("SUFFIX")
+                        .num_args(0..=1)
+                        .require_equals(true)
+                        .default_missing_value(".bak")
+                        .help("Copy each existing file to its name plus SUFFIX before replacing it (default: off; `--backup` alone uses .bak)")
+                        .conflicts_with("clean"),
+                )
+                .arg(
+                    Arg::new("on_local_edit")
+                        .long("on-local-edit")
+                        .value_name("MODE")
+                        .value_parser(["skip", "overwrite", "prompt"])
+                        .help("Track applied files in .skeletor/applied.json: files untouched since the last apply are upgraded to the new template, and MODE decides files edited locally (default: off)"),
+                )
+                .arg(
+                    Arg::new("root_name")
+                        .long("root-name")
+                        .value_name("NAME")
+                        .help("Create the template's single top-level directory under NAME instead (default: the name in the template)\n  • Fails when the template has more than one top-level entry"),
+                )
+                .arg(
+                    Arg::new("templating")
+                        .long("templating")
+                        .help("Render file contents with Tera: {{ var }}, {% if %}, {% for %}, filters (default: off; needs the `templating` cargo feature)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("context_file")
+                        .long("context-file")
+                        .value_name("FILE")
+                        .help("JSON object of extra template data for --templating; --var values and config variables override its keys")
+                        .requires("templating"),
+                )
+                .arg(
+                    Arg::new("allow_escape")
+                        .long("allow-escape")
+                        .help("Accept entries that land outside the output directory: keys or a config target: with '..' or absolute paths, and paths under symlinks leading elsewhere (default: off; such entries are rejected)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("line_endings")
+                        .long("line-endings")
+                        .value_name("STYLE")
+                        .value_parser(["lf", "crlf", "preserve"])
+                        .default_value("preserve")
+                        .help("Convert every written file's line endings to lf or crlf; files containing NUL bytes are left as-is (default: preserve)"),
+                )
+                .arg(
+                    Arg::new("preserve_mtime")
+                        .long("preserve-mtime")
+                        .help("Set each written file's modification time to the one recorded by snapshot --preserve-mtime (default: off)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("fail_on_skip")
+                        .long("fail-on-skip")
+                        .help("Exit with status 3 if any existing files were skipped (default: off; ignored with --overwrite)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("verify_stats")
+                        .long("verify-stats")
+                        .help("Warn if the recorded stats or the created files/directories diverge from the entries the template defines (default: off)")
+                        .action(ArgAction::SetTrue),
                 ),
         )
         .subcommand(
             Command::new("snapshot")
-                .about("Creates a .skeletorrc snapshot from an existing folder\n\nEXAMPLES:\n  skeletor snapshot my-project               # Write .skeletorrc\n  skeletor snapshot my-project -o config.yml # Save to file\n  skeletor snapshot my-project --stdout      # Print YAML to stdout\n  skeletor snapshot src/ -i \"*.log\" -i target/ # Ignore build artifacts\n  skeletor snapshot --dry-run my-project     # Preview snapshot (summary)\n  skeletor snapshot --dry-run --verbose my-project # Preview with details\n\nIMPORTANT: Quote glob patterns to prevent shell expansion:\n  ✓ skeletor snapshot -i \"*.log\" -i \"src/**/*.tmp\" .\n  ✗ skeletor snapshot -i *.log -i src/**/*.tmp .  # Shell expands patterns")
+                .about("Creates a .skeletorrc snapshot from an existing folder\n\nEXAMPLES:\n  skeletor snapshot my-project               # Write .skeletorrc\n  skeletor snapshot my-project -o config.yml # Save to file\n  skeletor snapshot my-project --stdout      # Print YAML to stdout\n  skeletor snapshot src/ -i \"*.log\" -i target/ # Ignore build artifacts\n  skeletor snapshot --dry-run my-project     # Preview snapshot (summary)\n  skeletor snapshot --dry-run --verbose my-project # Preview with details\n  skeletor snapshot my-project --as-tree     # Print a tree listing for docs\n\nIMPORTANT: Quote glob patterns to prevent shell expansion:\n  ✓ skeletor snapshot -i \"*.log\" -i \"src/**/*.tmp\" .\n  ✗ skeletor snapshot -i *.log -i src/**/*.tmp .  # Shell expands patterns")
                 .arg(
                     Arg::new("source")
                         .value_name("FOLDER")
@@ -164,7 +605,7 @@ pub fn build_cli() -> Command {
                         .short('o')
                         .long("output")
                         .value_name("FILE")
-                        .help("Save snapshot YAML to a file (default: .skeletorrc)"),
+                        .help("Save snapshot YAML to a file; `-` prints it to stdout (default: .skeletorrc)"),
                 )
                 .arg(
                     Arg::new("stdout")
@@ -179,6 +620,13 @@ pub fn build_cli() -> Command {
                         .help("Exclude file contents (default: include contents; binary files still detected)")
                         .action(ArgAction::SetTrue),
                 )
+                .arg(
+                    Arg::new("contents_for")
+                        .long("contents-for")
+                        .value_name("GLOB")
+                        .help("Capture contents only for files whose relative path or name matches GLOB, recording the rest empty (repeatable; default: all files)")
+                        .action(ArgAction::Append),
+                )
                 .arg(
                     Arg::new("ignore")
                         .short('i')
@@ -194,6 +642,24 @@ pub fn build_cli() -> Command {
                         .help("Read ignore patterns from a file (default: none; use multiple times)")
                         .action(ArgAction::Append),
                 )
+                .arg(
+                    Arg::new("use_gitignore")
+                        .long("use-gitignore")
+                        .help("Honor .gitignore files in the source folder and its subfolders, as git does (default: off)\n  • Explicit -i/--ignore-file patterns are applied on top")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("no_default_ignore")
+                        .long("no-default-ignore")
+                        .help("Do not read .skeletorignore from the source folder (default: read it when present)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("ignore_case")
+                        .long("ignore-case")
+                        .help("Match ignore patterns regardless of case, e.g. -i \"*.PNG\" also skips photo.png (default: off)\n  • Applies to -i, --ignore-file and --use-gitignore patterns alike")
+                        .action(ArgAction::SetTrue),
+                )
                 .arg(
                     Arg::new("verbose")
                         .short('v')
@@ -212,18 +678,264 @@ pub fn build_cli() -> Command {
                     Arg::new("note")
                         .short('n')
                         .long("note")
-                        .value_name("NOTE")
-                        .help("Attach a user-defined note to the snapshot (default: none)"),
+                        .value_name("NOTE|KEY=VALUE")
+                        .help("Attach a note to the snapshot (default: none; can be used multiple times)\n  • KEY=VALUE adds a structured entry, e.g. author=Jane, ticket=ABC-123\n  • Freeform text is stored as notes.description")
+                        .action(ArgAction::Append),
+                )
+                .arg(format_arg())
+                .arg(
+                    Arg::new("binary_detection")
+                        .long("binary-detection")
+                        .value_name("MODE")
+                        .value_parser(["utf8", "null-byte"])
+                        .default_value("utf8")
+                        .help("How to spot binary files: `utf8` reads each file in full and checks it is UTF-8; `null-byte` treats a NUL in the first 8 KiB as binary without reading the rest (default: utf8)"),
+                )
+                .arg(
+                    Arg::new("binary_ext")
+                        .long("binary-ext")
+                        .value_name("EXT")
+                        .help("Treat files with these extensions as binary without reading them, e.g. png,mp4 (repeatable or comma-separated)")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("text_ext")
+                        .long("text-ext")
+                        .value_name("EXT")
+                        .help("Always read files with these extensions in full, skipping the NUL-byte check (repeatable or comma-separated)")
+                        .action(ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("binary_sniff_limit")
+                        .long("binary-sniff-limit")
+                        .value_name("BYTES")
+                        .value_parser(clap::value_parser!(u64))
+                        .help("Binary detection only: classify files larger than BYTES as binary without reading them. Their contents are omitted and they are listed as binary (default: no limit; files over --max-content-size never reach this check)"),
+                )
+                .arg(
+                    Arg::new("max_content_size")
+                        .long("max-content-size")
+                        .value_name("BYTES")
+                        .value_parser(clap::value_parser!(u64))
+                        .default_value("5242880")
+                        .help("Content cap for every file: record files larger than BYTES empty without reading them and list them as oversized, not binary (default: 5242880, i.e. 5 MiB)"),
+                )
+                .arg(
+                    Arg::new("externalize_threshold")
+                        .long("externalize-threshold")
+                        .value_name("BYTES")
+                        .value_parser(clap::value_parser!(u64))
+                        .help("Write file contents larger than BYTES to sidecar files under .skeletor_assets/ next to the output and reference them with `!file_ref` (default: off)")
+                        .conflicts_with_all(["stdout", "as_tree"]),
+                )
+                .arg(
+                    Arg::new("dedupe")
+                        .long("dedupe")
+                        .visible_alias("dedupe-contents")
+                        .help("Store identical file contents once under `anchors:` and reference copies with YAML aliases (default: off; applies unchanged)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("preserve_mtime")
+                        .long("preserve-mtime")
+                        .help("Record each file's modification time, writing files as !file { content, mtime } (default: off)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("sorted")
+                        .long("sorted")
+                        .value_name("ORDER")
+                        .num_args(0..=1)
+                        .require_equals(true)
+                        .default_missing_value("name")
+                        .value_parser(["name", "dirs-first"])
+                        .help("Sort each directory's entries for reproducible, diff-friendly snapshots: `name` intermixes files and directories by name, `--sorted=dirs-first` lists directories first (default: filesystem order)"),
+                )
+                .arg(
+                    Arg::new("streaming")
+                        .long("streaming")
+                        .help("Write the YAML while walking the source instead of building it in memory first, for very large trees; the source is walked twice (default: off)")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all(["dedupe", "externalize_threshold", "as_tree"]),
+                )
+                .arg(
+                    Arg::new("append")
+                        .long("append")
+                        .help("Update the output snapshot in place: re-read only files modified since its `updated` time and keep the recorded contents of the rest (default: off; a missing output gets a full snapshot)")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all(["stdout", "streaming", "as_tree"]),
+                )
+                .arg(
+                    Arg::new("count_only")
+                        .long("count-only")
+                        .help("Only count the files, directories and binary files a snapshot would record, without building or writing it (default: off)")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with_all([
+                            "output", "stdout", "dry_run", "as_tree", "streaming", "append", "dedupe",
+                            "externalize_threshold",
+                        ]),
+                )
+                .arg(
+                    Arg::new("follow_symlinks")
+                        .long("follow-symlinks")
+                        .help("Read through symlinks: recurse into linked directories and capture linked files' contents (default: off)")
+                        .overrides_with("preserve_symlinks")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("preserve_symlinks")
+                        .long("preserve-symlinks")
+                        .help("Record every symlink as `name: !symlink target` so apply recreates it (default: on; overrides --follow-symlinks)")
+                        .overrides_with("follow_symlinks")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("on_invalid_name")
+                        .long("on-invalid-name")
+                        .value_name("POLICY")
+                        .value_parser(["skip", "error", "lossy"])
+                        .default_value("error")
+                        .help("What to do with file names that are not valid UTF-8: fail, skip them with a warning, or replace invalid bytes with U+FFFD (default: error)"),
+                )
+                .arg(
+                    Arg::new("max_depth")
+                        .long("max-depth")
+                        .visible_alias("depth")
+                        .value_name("N")
+                        .value_parser(clap::value_parser!(usize))
+                        .help("Record directories more than N levels below the source as empty, without reading them (0 keeps just the top level; default: no limit)"),
+                )
+                .arg(
+                    Arg::new("as_tree")
+                        .long("as-tree")
+                        .help("Print an indented tree (├──/└──) instead of YAML (default: off; stdout unless -o is given)")
+                        .action(ArgAction::SetTrue)
+                        .conflicts_with("dry_run"),
+                )
+                .arg(
+                    Arg::new("inherit_config")
+                        .long("inherit-config")
+                        .help("Seed ignore patterns, notes and name from a .skeletorrc in the source folder (default: off)\n  • CLI --note keys override inherited note keys\n  • CLI ignore patterns are added to the inherited ones")
+                        .action(ArgAction::SetTrue),
                 ),
         )
         .subcommand(
             Command::new("info")
-                .about("Displays metadata from a .skeletorrc file\n\nEXAMPLES:\n  skeletor info                             # Show info for .skeletorrc\n  skeletor info my-template.yml             # Show info for custom file")
+                .about("Displays metadata from a .skeletorrc file\n\nEXAMPLES:\n  skeletor info                             # Show info for .skeletorrc\n  skeletor info my-template.yml             # Show info for custom file\n  skeletor info --tree                      # Also show the template's structure\n  skeletor info --verify                    # Check the snapshot's checksum
+  skeletor info --resolve-includes app.yml  # List the includes and the merged top level
+  skeletor info --json template.yml         # Metadata as JSON, for scripts")
                 .arg(
                     Arg::new("config")
                         .value_name("CONFIG_FILE")
                         .help("YAML configuration file to inspect (default: .skeletorrc)")
                         .index(1),
+                )
+                .arg(no_ascend_arg())
+                .arg(
+                    Arg::new("tree")
+                        .long("tree")
+                        .visible_alias("show-structure")
+                        .help("Also render the directories section as a tree, binary entries tagged [binary] (default: off)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("verify")
+                        .long("verify")
+                        .help("Recompute the checksum of the directories section and fail if it does not match (default: off)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("resolve_includes")
+                        .long("resolve-includes")
+                        .help("Merge in the config's include: files, then list them and the resulting top-level entries; --tree shows the merged tree (default: off)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("json")
+                        .long("json")
+                        .help("Print the metadata as one JSON object, missing fields as null (default: off)")
+                        .conflicts_with("tree")
+                        .action(ArgAction::SetTrue),
                 ),
         )
+        .subcommand(
+            Command::new("cat")
+                .about("Prints one file's content from a YAML configuration without applying it
+
+EXAMPLES:
+  skeletor cat template.yml src/main.rs               # Show embedded content
+  skeletor cat template.yml src/lib.rs --var name=app # Substitute ${name} first")
+                .arg(
+                    Arg::new("config")
+                        .value_name("CONFIG_FILE")
+                        .help("YAML configuration file to read")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("path")
+                        .value_name("PATH")
+                        .help("Path of the file inside the config, e.g. src/main.rs")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(var_arg())
+                .arg(env_vars_arg()),
+        )
+        .subcommand(
+            Command::new("diff")
+                .about("Compares a template against an existing directory without changing anything
+
+Exits with status 1 when applying the template would create or change anything.
+
+EXAMPLES:
+  skeletor diff template.yml .             # Summarize what an apply would change
+  skeletor diff template.yml app -v        # List every file's status")
+                .arg(
+                    Arg::new("template")
+                        .value_name("TEMPLATE")
+                        .help("Configuration file to compare")
+                        .required(true)
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("dir")
+                        .value_name("DIR")
+                        .help("Directory to compare it against")
+                        .required(true)
+                        .index(2),
+                )
+                .arg(
+                    Arg::new("verbose")
+                        .short('v')
+                        .long("verbose")
+                        .help("List each path as new (+), changed (~) or identical (=) (default: off)")
+                        .action(ArgAction::SetTrue),
+                )
+                .arg(var_arg())
+                .arg(env_vars_arg()),
+        )
+        .subcommand(
+            Command::new("templates")
+                .about("Works with the template directory that bare names like `skeletor apply rust` resolve in\n\nEXAMPLES:\n  skeletor templates list                   # Names of the available templates")
+                .subcommand_required(true)
+                .subcommand(Command::new("list").about("Lists the templates in the template directory")),
+        )
+        .subcommand(
+            Command::new("validate")
+                .about("Checks a configuration's structure without applying it\n\nEXAMPLES:\n  skeletor validate                         # Check .skeletorrc\n  skeletor validate my-template.yml         # Check a custom file\n  skeletor validate --schema schema.yml t.yml # Require the keys schema.yml lists")
+                .arg(
+                    Arg::new("config")
+                        .value_name("CONFIG_FILE")
+                        .help("Configuration file to check (default: .skeletorrc)")
+                        .index(1),
+                )
+                .arg(
+                    Arg::new("schema")
+                        .long("schema")
+                        .value_name("SCHEMA_FILE")
+                        .help("Also check the top-level keys the schema's `required:` lists, and their types (default: none)"),
+                )
+                .arg(no_ascend_arg()),
+        )
 }
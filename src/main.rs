@@ -1,11 +1,15 @@
 mod apply;
+mod cat;
 mod config;
+mod diff;
 mod errors;
 mod info;
 mod output;
 mod snapshot;
 mod tasks;
+mod templates;
 mod utils;
+mod validate;
 
 #[cfg(test)]
 mod test_utils;
@@ -14,22 +18,16 @@ mod test_utils;
 pub use skeletor::build_cli;
 
 use crate::apply::run_apply;
+use crate::cat::run_cat;
+use crate::diff::run_diff;
 use crate::info::run_info;
 use crate::snapshot::run_snapshot;
+use crate::templates::run_templates;
+use crate::validate::run_validate;
 use crate::errors::SkeletorError;
-use termcolor::{StandardStream, ColorChoice, Color, ColorSpec, WriteColor};
-use std::io::Write;
+use crate::output::{DefaultReporter, OutputFormat, Reporter};
 
-/// Displays a formatted error message to stderr
-fn print_error(message: &str) {
-    let mut stderr = StandardStream::stderr(ColorChoice::Auto);
-    let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true));
-    let _ = write!(stderr, "error: ");
-    let _ = stderr.reset();
-    eprintln!("{}", message);
-}
-
-/// Build the CLI interface with three subcommands: `apply`, `snapshot` and `info`
+/// Build the CLI interface with subcommands: `apply`, `snapshot`, `info`, `cat`, `diff`, `templates` and `validate`
 fn parse_arguments() -> clap::ArgMatches {
     skeletor::build_cli().get_matches()
 }
@@ -40,18 +38,31 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let matches = parse_arguments();
 
     if let Err(e) = run_command(&matches) {
-        print_error(&e.to_string());
-        std::process::exit(1);
+        DefaultReporter::with_format(format_from_matches(&matches)).error(&e.to_string());
+        std::process::exit(e.exit_code());
     }
 
     Ok(())
 }
 
+/// The `--format` of the invoked subcommand, for reporting the error it failed with
+fn format_from_matches(matches: &clap::ArgMatches) -> OutputFormat {
+    matches
+        .subcommand()
+        .and_then(|(_, sub_m)| sub_m.try_get_one::<String>("format").ok().flatten())
+        .and_then(|format| format.parse().ok())
+        .unwrap_or(OutputFormat::Pretty)
+}
+
 fn run_command(matches: &clap::ArgMatches) -> Result<(), SkeletorError> {
     match matches.subcommand() {
         Some(("apply", sub_m)) => run_apply(sub_m)?,
         Some(("snapshot", sub_m)) => run_snapshot(sub_m)?,
         Some(("info", sub_m)) => run_info(sub_m)?,
+        Some(("cat", sub_m)) => run_cat(sub_m)?,
+        Some(("diff", sub_m)) => run_diff(sub_m)?,
+        Some(("templates", sub_m)) => run_templates(sub_m)?,
+        Some(("validate", sub_m)) => run_validate(sub_m)?,
         _ => unreachable!("A subcommand is required"),
     }
     Ok(())
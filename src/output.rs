@@ -1,9 +1,15 @@
-use std::path::PathBuf;
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use termcolor::{StandardStream, ColorChoice, Color, ColorSpec, WriteColor};
 use std::io::Write;
 use crate::tasks::Task;
 
+#[cfg(feature = "progress-bar")]
+mod progress;
+#[cfg(feature = "progress-bar")]
+pub use self::progress::ProgressReporter;
+
 /// Simple result types for output module (without external dependencies)
 #[derive(Debug, Clone)]
 pub struct SimpleApplyResult {
@@ -15,6 +21,15 @@ pub struct SimpleApplyResult {
     pub skipped_files_list: Vec<String>,
     pub files_overwritten: usize,
     pub overwritten_files_list: Vec<String>,
+    pub hidden_skipped_list: Vec<String>,
+    pub files_preserved: usize,
+    pub preserved_files_list: Vec<String>,
+    pub files_unchanged: usize,
+    pub unchanged_files_list: Vec<String>,
+    pub failed_files_list: Vec<(String, String)>,
+    pub symlinks_created: usize,
+    /// Binary files the snapshot recorded without contents, so they were created empty
+    pub binary_files_list: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +40,24 @@ pub struct SimpleSnapshotResult {
     pub output_path: PathBuf,
     pub binary_files_excluded: usize,
     pub binary_files_list: Vec<String>,
+    /// Files over the content size limit, recorded empty without being read
+    pub oversized_files_list: Vec<String>,
+    /// Files and directories that could not be read and were left out
+    pub unreadable_list: Vec<String>,
+    /// Each ignore pattern with the number of entries it left out
+    pub ignored_by_pattern: Vec<(String, usize)>,
+    /// Size of the written snapshot file
+    pub bytes_written: u64,
+}
+
+/// Totals reported by `snapshot --count-only`
+#[derive(Debug, Clone)]
+pub struct SnapshotCounts {
+    pub source: PathBuf,
+    pub files: usize,
+    pub dirs: usize,
+    pub binary_files: usize,
+    pub duration: Duration,
 }
 
 impl SimpleApplyResult {
@@ -48,10 +81,19 @@ impl SimpleApplyResult {
             skipped_files_list,
             files_overwritten,
             overwritten_files_list,
+            hidden_skipped_list: Vec::new(),
+            files_preserved: 0,
+            preserved_files_list: Vec::new(),
+            files_unchanged: 0,
+            unchanged_files_list: Vec::new(),
+            failed_files_list: Vec::new(),
+            symlinks_created: 0,
+            binary_files_list: Vec::new(),
         }
     }
 
-    #[cfg(test)]
+    /// A result with only the created counts set, e.g. for a custom [`Reporter`] to test against
+    #[allow(dead_code)]
     pub fn new(files_created: usize, dirs_created: usize, duration: Duration, tasks_total: usize) -> Self {
         Self {
             files_created,
@@ -62,18 +104,110 @@ impl SimpleApplyResult {
             skipped_files_list: Vec::new(),
             files_overwritten: 0,
             overwritten_files_list: Vec::new(),
+            hidden_skipped_list: Vec::new(),
+            files_preserved: 0,
+            preserved_files_list: Vec::new(),
+            files_unchanged: 0,
+            unchanged_files_list: Vec::new(),
+            failed_files_list: Vec::new(),
+            symlinks_created: 0,
+            binary_files_list: Vec::new(),
         }
     }
 }
 
 /// Output formatting options
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(dead_code)]
 pub enum OutputFormat {
     /// Plain text output
     Plain,
     /// Colored output with emoji and formatting
     Pretty,
+    /// Machine-readable JSON (one object per result on stdout; warnings go to stderr)
+    Json,
+    /// GitHub Actions workflow commands (`::notice::`, `::warning::`, `::error::`)
+    Github,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "plain" => Ok(Self::Plain),
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            "github" => Ok(Self::Github),
+            other => Err(format!("unknown output format '{}'", other)),
+        }
+    }
+}
+
+/// Formats a byte count with binary units, e.g. `512 B` or `4.2 MiB`
+fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Renders a GitHub Actions workflow command, e.g. `::warning file=a.txt::Skipped`
+pub fn github_command(level: &str, file: Option<&str>, message: &str) -> String {
+    let message = message
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    match file {
+        Some(file) => {
+            let file = file
+                .replace('%', "%25")
+                .replace('\r', "%0D")
+                .replace('\n', "%0A")
+                .replace(':', "%3A")
+                .replace(',', "%2C");
+            format!("::{} file={}::{}", level, file, message)
+        }
+        None => format!("::{}::{}", level, message),
+    }
+}
+
+fn task_to_json(task: &Task) -> serde_json::Value {
+    match task {
+        Task::Dir(path) => serde_json::json!({ "type": "dir", "path": path.display().to_string() }),
+        Task::File(path, _) => serde_json::json!({ "type": "file", "path": path.display().to_string() }),
+        Task::Symlink(path, target) => serde_json::json!({ "type": "symlink", "path": path.display().to_string(), "target": target }),
+    }
+}
+
+/// `Ignored 42 entries (3 by *.log, 39 by target/)`, naming only the patterns that matched
+fn ignored_summary(ignored_by_pattern: &[(String, usize)]) -> Option<String> {
+    let total: usize = ignored_by_pattern.iter().map(|(_, count)| count).sum();
+    if total == 0 {
+        return None;
+    }
+    let by_pattern: Vec<String> = ignored_by_pattern
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(pattern, count)| format!("{} by {}", count, pattern))
+        .collect();
+    Some(format!(
+        "Ignored {} {} ({})",
+        total,
+        if total == 1 { "entry" } else { "entries" },
+        by_pattern.join(", ")
+    ))
+}
+
+fn print_json(value: &serde_json::Value) {
+    println!("{}", value);
 }
 
 /// Trait for reporting progress and results during operations
@@ -96,6 +230,11 @@ pub trait Reporter {
     
     /// Report a general tip
     fn tip(&self, message: &str);
+
+    /// Report the error that ended the command; by default it goes to stderr as is
+    fn error(&self, message: &str) {
+        eprintln!("error: {}", message);
+    }
     
     /// Preview tasks in dry-run mode
     fn dry_run_preview(&self, tasks: &[Task]);
@@ -119,6 +258,8 @@ pub trait Reporter {
 /// Default reporter with colored output
 pub struct DefaultReporter {
     format: OutputFormat,
+    warnings: Cell<usize>,
+    quiet: bool,
 }
 
 impl DefaultReporter {
@@ -129,7 +270,118 @@ impl DefaultReporter {
     
     /// Create a reporter with specific output format
     pub fn with_format(format: OutputFormat) -> Self {
-        Self { format }
+        Self {
+            format,
+            warnings: Cell::new(0),
+            quiet: false,
+        }
+    }
+
+    /// Suppress all output when `quiet` is set (`--quiet`); warnings are still counted
+    pub fn quiet(mut self, quiet: bool) -> Self {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Reports a dry run skipped because the `--cache` entry at `cache` is still fresh
+    pub fn dry_run_unchanged(&self, cache: &Path) {
+        if self.quiet {
+            return;
+        }
+        if self.format == OutputFormat::Json {
+            println!(
+                "{}",
+                serde_json::json!({ "dry_run": true, "unchanged": true, "cache": cache.display().to_string() })
+            );
+            return;
+        }
+        println!("Dry run: no changes since last check (cache: {})", cache.display());
+    }
+
+    /// Lists the template's post-apply hooks in a dry run, and whether `run` (`--run-hooks`)
+    /// means they would run (on stderr for JSON output)
+    pub fn hooks_preview(&self, commands: &[String], run: bool) {
+        if self.quiet || commands.is_empty() {
+            return;
+        }
+        if self.format == OutputFormat::Json {
+            for command in commands {
+                eprintln!("hook: {}", command);
+            }
+            return;
+        }
+        println!();
+        let title = if run {
+            "Post-apply hooks that would run:"
+        } else {
+            "Post-apply hooks (run only with --run-hooks):"
+        };
+        self.print_string_list(title, commands, true, commands.len(), None);
+    }
+
+    /// Lists the files `apply --clean` removes, or would remove in a dry run (on stderr for JSON output)
+    pub fn clean_preview(&self, paths: &[String], dry_run: bool) {
+        if self.quiet || paths.is_empty() {
+            return;
+        }
+        if self.format == OutputFormat::Json {
+            for path in paths {
+                eprintln!("remove: {}", path);
+            }
+            return;
+        }
+        let title = if dry_run {
+            "Files not in the template that would be removed:"
+        } else {
+            "Removing files not in the template:"
+        };
+        println!();
+        self.print_string_list(title, paths, true, paths.len(), None);
+    }
+
+    /// Prints the totals of `snapshot --count-only`
+    pub fn snapshot_counts(&self, counts: &SnapshotCounts) {
+        if self.quiet {
+            return;
+        }
+        match self.format {
+            OutputFormat::Pretty => {
+                self.write_colored_inline("Counted ", Some(Color::Green));
+                println!(
+                    "{:?}: {} files, {} directories, {} binary files",
+                    counts.source, counts.files, counts.dirs, counts.binary_files
+                );
+            }
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "source": counts.source.display().to_string(),
+                "files": counts.files,
+                "directories": counts.dirs,
+                "binary_files": counts.binary_files,
+                "duration_ms": counts.duration.as_micros() as f64 / 1000.0,
+            })),
+            OutputFormat::Github => println!(
+                "{}",
+                github_command(
+                    "notice",
+                    None,
+                    &format!(
+                        "{}: {} files, {} directories, {} binary files",
+                        counts.source.display(), counts.files, counts.dirs, counts.binary_files
+                    )
+                )
+            ),
+            _ => {
+                println!("Files: {}", counts.files);
+                println!("Directories: {}", counts.dirs);
+                println!("Binary files: {}", counts.binary_files);
+                println!("Duration: {:.2}ms", counts.duration.as_micros() as f64 / 1000.0);
+            }
+        }
+    }
+
+    /// Number of `warning` and `task_warning` calls so far, for `--strict`
+    pub fn warning_count(&self) -> usize {
+        self.warnings.get()
     }
     
     fn write_colored_inline(&self, text: &str, color: Option<Color>) {
@@ -143,16 +395,23 @@ impl DefaultReporter {
 
     fn summarize_tasks(tasks: &[Task]) -> (usize, usize) {
         tasks.iter().fold((0, 0), |(files, dirs), task| match task {
-            Task::File(_, _) => (files + 1, dirs),
+            Task::File(_, _) | Task::Symlink(_, _) => (files + 1, dirs),
             Task::Dir(_) => (files, dirs + 1),
         })
     }
 
+    /// Full listing for verbose previews; files are annotated with their content size
     fn print_task_list(&self, tasks: &[Task]) {
         for (i, task) in tasks.iter().enumerate() {
             match task {
-                Task::File(path, _) => println!("  {}. 📄 {}", i + 1, path.display()),
+                Task::File(path, content) => println!(
+                    "  {}. 📄 {} ({})",
+                    i + 1,
+                    path.display(),
+                    format_size(content.len())
+                ),
                 Task::Dir(path) => println!("  {}. 📁 {}", i + 1, path.display()),
+                Task::Symlink(path, target) => println!("  {}. 🔗 {} -> {}", i + 1, path.display(), target),
             }
         }
     }
@@ -165,6 +424,7 @@ impl DefaultReporter {
             match task {
                 Task::File(path, _) => println!("  {}. 📄 {}", i + 1, path.display()),
                 Task::Dir(path) => println!("  {}. 📁 {}", i + 1, path.display()),
+                Task::Symlink(path, target) => println!("  {}. 🔗 {} -> {}", i + 1, path.display(), target),
             }
         }
         if tasks.len() > limit {
@@ -203,26 +463,37 @@ impl DefaultReporter {
 
 impl Reporter for DefaultReporter {
     fn operation_start(&self, operation: &str, details: &str) {
+        if self.quiet {
+            return;
+        }
         match self.format {
             OutputFormat::Pretty => {
                 self.write_colored_inline("start: ", Some(Color::Blue));
                 println!("{}: {}", operation, details);
             },
+            OutputFormat::Json => {}
             _ => println!("start: {}: {}", operation, details),
         }
     }
     
     fn progress(&self, current: usize, total: usize, message: &str) {
+        if self.quiet {
+            return;
+        }
         match self.format {
             OutputFormat::Pretty => {
                 self.write_colored_inline("progress: ", Some(Color::Yellow));
                 println!("{}/{} - {}", current, total, message);
             },
+            OutputFormat::Json => {}
             _ => println!("progress: {}/{} - {}", current, total, message),
         }
     }
     
     fn task_success(&self, task: &Task) {
+        if self.quiet {
+            return;
+        }
         match self.format {
             OutputFormat::Pretty => {
                 match task {
@@ -236,60 +507,96 @@ impl Reporter for DefaultReporter {
                         self.write_colored_inline("File: ", Some(Color::Green));
                         println!("{}", path.display());
                     },
+                    Task::Symlink(path, target) => {
+                        print!("🔗 ");
+                        self.write_colored_inline("Link: ", Some(Color::Cyan));
+                        println!("{} -> {}", path.display(), target);
+                    },
                 }
             },
-            _ => {
-                match task {
-                    Task::Dir(path) => println!("✓ {}", path.display()),
-                    Task::File(path, _) => println!("✓ {}", path.display()),
-                }
-            }
+            OutputFormat::Json => {}
+            _ => println!("✓ {}", task.path().display()),
         }
     }
     
     fn task_warning(&self, task: &Task, error: &str) {
+        self.warnings.set(self.warnings.get() + 1);
+        if self.quiet {
+            return;
+        }
         match self.format {
             OutputFormat::Pretty => {
                 self.write_colored_inline("warning: ", Some(Color::Yellow));
-                match task {
-                    Task::Dir(path) => println!("{}: {}", path.display(), error),
-                    Task::File(path, _) => println!("{}: {}", path.display(), error),
-                }
+                println!("{}: {}", task.path().display(), error);
             },
-            _ => {
-                match task {
-                    Task::Dir(path) => println!("warning: {}: {}", path.display(), error),
-                    Task::File(path, _) => println!("warning: {}: {}", path.display(), error),
-                }
-            }
+            OutputFormat::Json => eprintln!("warning: {}: {}", task.path().display(), error),
+            OutputFormat::Github => println!(
+                "{}",
+                github_command("warning", Some(&task.path().display().to_string()), error)
+            ),
+            _ => println!("warning: {}: {}", task.path().display(), error),
         }
     }
     
     fn warning(&self, message: &str) {
+        self.warnings.set(self.warnings.get() + 1);
+        if self.quiet {
+            return;
+        }
         match self.format {
             OutputFormat::Pretty => {
                 self.write_colored_inline("warning: ", Some(Color::Yellow));
                 println!("{}", message);
             },
+            OutputFormat::Json => eprintln!("warning: {}", message),
+            OutputFormat::Github => println!("{}", github_command("warning", None, message)),
             _ => println!("warning: {}", message),
         }
     }
     
     fn tip(&self, message: &str) {
+        if self.quiet {
+            return;
+        }
         match self.format {
             OutputFormat::Pretty => {
                 self.write_colored_inline("tip: ", Some(Color::Yellow));
                 println!("{}", message);
             },
+            OutputFormat::Json => eprintln!("tip: {}", message),
             _ => println!("tip: {}", message),
         }
     }
+
+    /// Always on stderr, even when quiet; GitHub Actions also gets an `::error::` annotation
+    fn error(&self, message: &str) {
+        match self.format {
+            OutputFormat::Pretty => {
+                let mut stderr = StandardStream::stderr(ColorChoice::Auto);
+                let _ = stderr.set_color(ColorSpec::new().set_fg(Some(Color::Red)).set_bold(true));
+                let _ = write!(stderr, "error: ");
+                let _ = stderr.reset();
+                eprintln!("{}", message);
+            }
+            OutputFormat::Github => {
+                println!("{}", github_command("error", None, message));
+                eprintln!("error: {}", message);
+            }
+            _ => eprintln!("error: {}", message),
+        }
+    }
     
     fn dry_run_preview(&self, tasks: &[Task]) {
+        if self.quiet {
+            return;
+        }
         self.dry_run_preview_verbose(tasks, false);
     }
     
     fn dry_run_preview_verbose(&self, tasks: &[Task], verbose: bool) {
+        if self.quiet {
+            return;
+        }
         match self.format {
             OutputFormat::Pretty => {
                 println!("Dry run enabled. Summary of planned operations:");
@@ -314,19 +621,34 @@ impl Reporter for DefaultReporter {
                 
                 println!("\nDry run complete. No changes were made.");
             },
+            OutputFormat::Json => {
+                let tasks: Vec<_> = tasks.iter().map(task_to_json).collect();
+                print_json(&serde_json::json!({ "dry_run": true, "tasks": tasks }));
+            }
             _ => {
                 println!("Dry run preview ({} tasks):", tasks.len());
                 for task in tasks {
-                    match task {
-                        Task::Dir(path) => println!("  {}", path.display()),
-                        Task::File(path, _) => println!("  {}", path.display()),
-                    }
+                    println!("  {}", task.path().display());
                 }
             }
         }
     }
     
     fn dry_run_preview_comprehensive(&self, tasks: &[Task], verbose: bool, binary_files: &[String], ignore_patterns: &[String], verb: &str) {
+        if self.quiet {
+            return;
+        }
+        if self.format == OutputFormat::Json {
+            let tasks: Vec<_> = tasks.iter().map(task_to_json).collect();
+            print_json(&serde_json::json!({
+                "dry_run": true,
+                "tasks": tasks,
+                "binary_files": binary_files,
+                "ignore_patterns": ignore_patterns,
+            }));
+            return;
+        }
+
         // Header
         println!("Dry run enabled.");
         println!();
@@ -379,6 +701,12 @@ impl Reporter for DefaultReporter {
     }
     
     fn verbose_operation_preview(&self, tasks: &[Task]) {
+        if self.quiet {
+            return;
+        }
+        if self.format == OutputFormat::Json {
+            return;
+        }
         println!("Operations to be executed:");
         for (i, task) in tasks.iter().enumerate() {
             match task {
@@ -388,12 +716,18 @@ impl Reporter for DefaultReporter {
                 Task::Dir(path) => {
                     println!("  {}. 📁 {}", i + 1, path.display());
                 }
+                Task::Symlink(path, target) => {
+                    println!("  {}. 🔗 {} -> {}", i + 1, path.display(), target);
+                }
             }
         }
         println!();
     }
     
     fn apply_complete(&self, result: &SimpleApplyResult, verbose: bool) {
+        if self.quiet {
+            return;
+        }
         match self.format {
             OutputFormat::Pretty => {
                 // Show skipped files with helpful tip
@@ -411,6 +745,70 @@ impl Reporter for DefaultReporter {
                     println!("Use --overwrite to update existing files");
                 }
                 
+                // Show hidden entries filtered by --skip-hidden
+                if !result.hidden_skipped_list.is_empty() {
+                    println!();
+                    self.print_string_list(
+                        "Hidden entries skipped (--skip-hidden):",
+                        &result.hidden_skipped_list,
+                        verbose,
+                        3,
+                        Some("Use --verbose to see all skipped hidden entries"),
+                    );
+                }
+                
+                // Show files protected by .skeletorkeep or kept for their local edits
+                if result.files_preserved > 0 {
+                    println!();
+                    self.print_string_list(
+                        "Files preserved (.skeletorkeep or local edits):",
+                        &result.preserved_files_list,
+                        verbose,
+                        3,
+                        Some("Use --verbose to see all preserved files"),
+                    );
+                }
+                
+                // Show files left untouched by --update
+                if result.files_unchanged > 0 {
+                    println!();
+                    self.print_string_list(
+                        "Files unchanged (--update):",
+                        &result.unchanged_files_list,
+                        verbose,
+                        3,
+                        Some("Use --verbose to see all unchanged files"),
+                    );
+                }
+                
+                // Show entries that could not be written
+                if !result.failed_files_list.is_empty() {
+                    println!();
+                    let failures: Vec<String> = result
+                        .failed_files_list
+                        .iter()
+                        .map(|(path, reason)| format!("{}: {}", path, reason))
+                        .collect();
+                    self.print_string_list("Failed to create:", &failures, true, 3, None);
+                }
+                
+                // Show binary files whose contents the snapshot could not capture
+                if !result.binary_files_list.is_empty() {
+                    println!();
+                    self.write_colored_inline("warning: ", Some(Color::Yellow));
+                    println!(
+                        "{} binary files from the original snapshot could not be recreated and were created empty",
+                        result.binary_files_list.len()
+                    );
+                    self.print_string_list(
+                        "Binary files without contents:",
+                        &result.binary_files_list,
+                        verbose,
+                        3,
+                        Some("Use --verbose to see all binary files"),
+                    );
+                }
+                
                 // Show overwritten files
                 if result.files_overwritten > 0 {
                     println!();
@@ -425,23 +823,96 @@ impl Reporter for DefaultReporter {
                 
                 println!("------------------------------------------");
                 let mut stdout = StandardStream::stdout(ColorChoice::Auto);
-                print!("✅ Successfully generated {} files and {} directories in ", 
+                print!("✅ Successfully generated {} files and {} directories", 
                        result.files_created, result.dirs_created);
+                if result.symlinks_created > 0 {
+                    print!(" ({} symlinks)", result.symlinks_created);
+                }
+                print!(" in ");
                 let _ = stdout.set_color(ColorSpec::new().set_fg(Some(Color::Cyan)).set_bold(true));
                 let _ = write!(stdout, "{:.2}ms", result.duration.as_micros() as f64 / 1000.0);
                 let _ = stdout.reset();
                 println!();
             },
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "files_created": result.files_created,
+                "dirs_created": result.dirs_created,
+                "duration_ms": result.duration.as_micros() as f64 / 1000.0,
+                "tasks_total": result.tasks_total,
+                "files_skipped": result.files_skipped,
+                "skipped_files_list": result.skipped_files_list,
+                "files_overwritten": result.files_overwritten,
+                "overwritten_files_list": result.overwritten_files_list,
+                "hidden_skipped_list": result.hidden_skipped_list,
+                "files_preserved": result.files_preserved,
+                "preserved_files_list": result.preserved_files_list,
+                "files_unchanged": result.files_unchanged,
+                "unchanged_files_list": result.unchanged_files_list,
+                "symlinks_created": result.symlinks_created,
+                "binary_files_list": result.binary_files_list,
+                "failed_files_list": result.failed_files_list.iter()
+                    .map(|(path, reason)| serde_json::json!({ "path": path, "error": reason }))
+                    .collect::<Vec<_>>(),
+            })),
+            OutputFormat::Github => {
+                for path in &result.skipped_files_list {
+                    println!("{}", github_command("warning", Some(path), "Skipped: file already exists (use --overwrite to replace it)"));
+                }
+                for (path, reason) in &result.failed_files_list {
+                    println!("{}", github_command("error", Some(path), &format!("Failed to create: {}", reason)));
+                }
+                for path in &result.binary_files_list {
+                    println!("{}", github_command("warning", Some(path), "Binary file created empty: the snapshot did not capture its contents"));
+                }
+                println!(
+                    "{}",
+                    github_command(
+                        "notice",
+                        None,
+                        &format!(
+                            "Created {} files and {} directories ({} skipped, {} overwritten)",
+                            result.files_created, result.dirs_created, result.files_skipped, result.files_overwritten
+                        )
+                    )
+                );
+            }
             _ => {
                 println!("Success!");
                 println!("Directories created: {}", result.dirs_created);
                 println!("Files created: {}", result.files_created);
+                if result.symlinks_created > 0 {
+                    println!("Symlinks created: {}", result.symlinks_created);
+                }
                 if result.files_skipped > 0 {
                     println!("Files skipped: {}", result.files_skipped);
+                    for path in &result.skipped_files_list {
+                        println!("  skipped: {}", path);
+                    }
+                }
+                if !result.hidden_skipped_list.is_empty() {
+                    println!("Hidden entries skipped: {}", result.hidden_skipped_list.len());
+                }
+                if result.files_preserved > 0 {
+                    println!("Files preserved: {}", result.files_preserved);
+                }
+                if result.files_unchanged > 0 {
+                    println!("Files unchanged: {}", result.files_unchanged);
                 }
                 if result.files_overwritten > 0 {
                     println!("Files overwritten: {}", result.files_overwritten);
                 }
+                if !result.failed_files_list.is_empty() {
+                    println!("Failed: {}", result.failed_files_list.len());
+                    for (path, reason) in &result.failed_files_list {
+                        println!("  failed: {}: {}", path, reason);
+                    }
+                }
+                if !result.binary_files_list.is_empty() {
+                    println!("Binary files not recreated: {}", result.binary_files_list.len());
+                    for path in &result.binary_files_list {
+                        println!("  binary: {}", path);
+                    }
+                }
                 println!("Duration: {:.2}ms", result.duration.as_micros() as f64 / 1000.0);
                 println!("Total operations: {}", result.tasks_total);
             }
@@ -449,10 +920,13 @@ impl Reporter for DefaultReporter {
     }
     
     fn snapshot_complete(&self, result: &SimpleSnapshotResult) {
+        if self.quiet {
+            return;
+        }
         match self.format {
             OutputFormat::Pretty => {
                 self.write_colored_inline("Snapshot written to ", Some(Color::Green));
-                println!("{:?}", result.output_path);
+                println!("{:?} ({} bytes)", result.output_path, result.bytes_written);
                 
                 // Show binary files excluded information if any
                 self.print_string_list(
@@ -462,16 +936,72 @@ impl Reporter for DefaultReporter {
                     3,
                     None,
                 );
+                self.print_string_list(
+                    "Oversized files recorded empty:",
+                    &result.oversized_files_list,
+                    true,
+                    3,
+                    None,
+                );
+                self.print_string_list(
+                    "Unreadable entries left out:",
+                    &result.unreadable_list,
+                    true,
+                    3,
+                    None,
+                );
+                if let Some(summary) = ignored_summary(&result.ignored_by_pattern) {
+                    println!("{}", summary);
+                }
             },
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "files_processed": result.files_processed,
+                "dirs_processed": result.dirs_processed,
+                "duration_ms": result.duration.as_micros() as f64 / 1000.0,
+                "output_path": result.output_path.display().to_string(),
+                "bytes_written": result.bytes_written,
+                "binary_files_excluded": result.binary_files_excluded,
+                "binary_files_list": result.binary_files_list,
+                "oversized_files_list": result.oversized_files_list,
+                "unreadable_list": result.unreadable_list,
+                "ignored_by_pattern": result
+                    .ignored_by_pattern
+                    .iter()
+                    .map(|(pattern, count)| serde_json::json!({ "pattern": pattern, "count": count }))
+                    .collect::<Vec<_>>(),
+            })),
+            OutputFormat::Github => println!(
+                "{}",
+                github_command(
+                    "notice",
+                    None,
+                    &format!(
+                        "Snapshot written to {} ({} bytes, {} files, {} directories)",
+                        result.output_path.display(), result.bytes_written, result.files_processed, result.dirs_processed
+                    )
+                )
+            ),
             _ => {
                 println!("Snapshot complete!");
                 println!("Files processed: {}", result.files_processed);
                 println!("Directories processed: {}", result.dirs_processed);
                 println!("Duration: {:.2}ms", result.duration.as_micros() as f64 / 1000.0);
-                println!("Output: {}", result.output_path.display());
+                println!("Output: {} ({} bytes)", result.output_path.display(), result.bytes_written);
                 if result.binary_files_excluded > 0 {
                     println!("Binary files excluded: {}", result.binary_files_excluded);
                 }
+                if !result.oversized_files_list.is_empty() {
+                    println!("Oversized files recorded empty: {}", result.oversized_files_list.len());
+                }
+                if !result.unreadable_list.is_empty() {
+                    println!("Unreadable entries left out: {}", result.unreadable_list.len());
+                    for path in &result.unreadable_list {
+                        println!("  unreadable: {}", path);
+                    }
+                }
+                if let Some(summary) = ignored_summary(&result.ignored_by_pattern) {
+                    println!("{}", summary);
+                }
             }
         }
     }
@@ -488,6 +1018,7 @@ impl Reporter for SilentReporter {
     fn task_warning(&self, _task: &Task, _error: &str) {}
     fn warning(&self, _message: &str) {}
     fn tip(&self, _message: &str) {}
+    fn error(&self, _message: &str) {}
     fn dry_run_preview(&self, _tasks: &[Task]) {}
     fn dry_run_preview_verbose(&self, _tasks: &[Task], _verbose: bool) {}
     fn dry_run_preview_comprehensive(&self, _tasks: &[Task], _verbose: bool, _binary_files: &[String], _ignore_patterns: &[String], _verb: &str) {}
@@ -535,6 +1066,20 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_quiet_reporter_still_counts_warnings() {
+        let reporter = DefaultReporter::new().quiet(true);
+        reporter.warning("unreadable file");
+        reporter.task_warning(&Task::File("a.txt".into(), String::new()), "already exists");
+        reporter.operation_start("apply", "Creating 1 tasks");
+        assert_eq!(reporter.warning_count(), 2);
+
+        // Errors are not warnings, and still print when quiet
+        let plain = DefaultReporter::with_format(OutputFormat::Plain).quiet(true);
+        plain.error("config not found");
+        assert_eq!(plain.warning_count(), 0);
+    }
+
     #[test]
     fn test_silent_reporter_methods() {
         let reporter = SilentReporter;
@@ -545,6 +1090,7 @@ mod tests {
         reporter.progress(1, 10, "message");
         reporter.task_success(&task);
         reporter.task_warning(&task, "warning");
+        reporter.error("error");
         reporter.dry_run_preview(&[task]);
         
         let apply_result = SimpleApplyResult::new(1, 1, Duration::from_millis(50), 2);
@@ -557,6 +1103,10 @@ mod tests {
             output_path: PathBuf::from("test.yml"),
             binary_files_excluded: 0,
             binary_files_list: vec![],
+            oversized_files_list: vec![],
+            unreadable_list: vec![],
+            ignored_by_pattern: vec![],
+            bytes_written: 0,
         };
         reporter.snapshot_complete(&snapshot_result);
     }
@@ -583,6 +1133,10 @@ mod tests {
             output_path: PathBuf::from("snapshot.yml"),
             binary_files_excluded: 1,
             binary_files_list: vec!["image.png".to_string()],
+            oversized_files_list: vec![],
+            unreadable_list: vec![],
+            ignored_by_pattern: vec![],
+            bytes_written: 0,
         };
         reporter.snapshot_complete(&snapshot_result);
     }
@@ -602,10 +1156,64 @@ mod tests {
             output_path: PathBuf::from("plain.yml"),
             binary_files_excluded: 2,
             binary_files_list: vec!["image.png".to_string(), "video.mp4".to_string()],
+            oversized_files_list: vec![],
+            unreadable_list: vec![],
+            ignored_by_pattern: vec![],
+            bytes_written: 0,
         };
         reporter.snapshot_complete(&snapshot_result);
     }
 
+    #[test]
+    fn test_ignored_summary_names_only_matching_patterns() {
+        let counts = |list: &[(&str, usize)]| -> Vec<(String, usize)> {
+            list.iter().map(|(pattern, count)| (pattern.to_string(), *count)).collect()
+        };
+        assert_eq!(
+            ignored_summary(&counts(&[("*.log", 3), ("*.lgo", 0), ("target/", 39)])).as_deref(),
+            Some("Ignored 42 entries (3 by *.log, 39 by target/)")
+        );
+        assert_eq!(ignored_summary(&counts(&[("dist/", 1)])).as_deref(), Some("Ignored 1 entry (1 by dist/)"));
+        assert_eq!(ignored_summary(&counts(&[("*.lgo", 0)])), None);
+    }
+
+    #[test]
+    fn test_output_format_from_str() {
+        assert_eq!("json".parse::<OutputFormat>(), Ok(OutputFormat::Json));
+        assert_eq!("plain".parse::<OutputFormat>(), Ok(OutputFormat::Plain));
+        assert_eq!("pretty".parse::<OutputFormat>(), Ok(OutputFormat::Pretty));
+        assert!("xml".parse::<OutputFormat>().is_err());
+    }
+
+    #[test]
+    fn test_json_format_reporter() {
+        let reporter = DefaultReporter::with_format(OutputFormat::Json);
+        let tasks = vec![
+            Task::Dir("src".into()),
+            Task::File("src/main.rs".into(), "fn main() {}".to_string()),
+        ];
+
+        let json = task_to_json(&tasks[1]);
+        assert_eq!(json["type"], "file");
+        assert_eq!(json["path"], "src/main.rs");
+
+        // Test that JSON format doesn't panic
+        reporter.dry_run_preview_comprehensive(&tasks, false, &["logo.png".to_string()], &[], "applied");
+        reporter.apply_complete(&SimpleApplyResult::new(1, 1, Duration::from_millis(5), 2), false);
+        reporter.snapshot_complete(&SimpleSnapshotResult {
+            files_processed: 1,
+            dirs_processed: 1,
+            duration: Duration::from_millis(5),
+            output_path: PathBuf::from("snapshot.yml"),
+            binary_files_excluded: 0,
+            binary_files_list: vec![],
+            oversized_files_list: vec![],
+            unreadable_list: vec![],
+            ignored_by_pattern: vec![],
+            bytes_written: 0,
+        });
+    }
+
     #[test]
     fn test_output_format_debug() {
         let format = OutputFormat::Pretty;
@@ -626,6 +1234,10 @@ mod tests {
             output_path: PathBuf::from("test.yml"),
             binary_files_excluded: 0,
             binary_files_list: vec![],
+            oversized_files_list: vec![],
+            unreadable_list: vec![],
+            ignored_by_pattern: vec![],
+            bytes_written: 0,
         };
         let debug_str = format!("{:?}", snapshot_result);
         assert!(debug_str.contains("files_processed"));
@@ -644,6 +1256,10 @@ mod tests {
             output_path: PathBuf::from("test.yml"),
             binary_files_excluded: 0,
             binary_files_list: vec![],
+            oversized_files_list: vec![],
+            unreadable_list: vec![],
+            ignored_by_pattern: vec![],
+            bytes_written: 0,
         };
         let cloned = snapshot_result.clone();
         assert_eq!(cloned.files_processed, snapshot_result.files_processed);
@@ -796,6 +1412,10 @@ mod tests {
                 "video.mp4".to_string(),
                 "data.bin".to_string(),
             ],
+            oversized_files_list: vec![],
+            unreadable_list: vec![],
+            ignored_by_pattern: vec![],
+            bytes_written: 0,
         };
         
         reporter.snapshot_complete(&snapshot_result);
@@ -812,6 +1432,10 @@ mod tests {
             output_path: PathBuf::from("clean_snapshot.yml"),
             binary_files_excluded: 0,
             binary_files_list: vec![],
+            oversized_files_list: vec![],
+            unreadable_list: vec![],
+            ignored_by_pattern: vec![],
+            bytes_written: 0,
         };
         
         reporter.snapshot_complete(&snapshot_result);
@@ -828,11 +1452,37 @@ mod tests {
             output_path: PathBuf::from("plain_snapshot.yml"),
             binary_files_excluded: 2,
             binary_files_list: vec!["file1.bin".to_string(), "file2.exe".to_string()],
+            oversized_files_list: vec![],
+            unreadable_list: vec![],
+            ignored_by_pattern: vec![],
+            bytes_written: 0,
         };
         
         reporter.snapshot_complete(&snapshot_result);
     }
 
+    #[test]
+    fn test_github_command_escaping() {
+        assert_eq!(github_command("notice", None, "done"), "::notice::done");
+        assert_eq!(
+            github_command("warning", Some("a,b:c.txt"), "50%\nskipped"),
+            "::warning file=a%2Cb%3Ac.txt::50%25%0Askipped"
+        );
+    }
+
+    #[test]
+    fn test_apply_complete_github_format() {
+        let reporter = DefaultReporter::with_format(OutputFormat::Github);
+        let mut result = SimpleApplyResult::with_skipped_and_overwritten(
+            1, 1, Duration::from_millis(5), 3,
+            1, vec!["exists.txt".to_string()],
+            0, vec![]
+        );
+        result.failed_files_list = vec![("locked.txt".to_string(), "permission denied".to_string())];
+
+        reporter.apply_complete(&result, false);
+    }
+
     #[test]
     fn test_with_skipped_and_overwritten_constructor() {
         let result = SimpleApplyResult::with_skipped_and_overwritten(
@@ -862,4 +1512,12 @@ mod tests {
         reporter.operation_start("test", "test operation");
         reporter.progress(50, 100, "test.txt");
     }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(4_404_019), "4.2 MiB");
+    }
 }
@@ -0,0 +1,108 @@
+use super::{DefaultReporter, Reporter, SimpleApplyResult, SimpleSnapshotResult};
+use crate::tasks::Task;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+/// Renders task progress as a single updating bar with files/sec and ETA.
+///
+/// Per-task lines are left out, as on a non-verbose apply; everything else is
+/// printed by a [`DefaultReporter`] above the bar. The bar is cleared when dropped.
+pub struct ProgressReporter {
+    bar: ProgressBar,
+    inner: DefaultReporter,
+}
+
+impl ProgressReporter {
+    /// A bar on stdout, which draws nothing unless stdout is a terminal
+    pub fn new() -> Self {
+        let bar = ProgressBar::with_draw_target(Some(0), ProgressDrawTarget::stdout());
+        bar.set_style(
+            ProgressStyle::with_template("{bar:40.cyan/blue} {pos}/{len} ({per_sec}, ETA {eta}) {wide_msg}")
+                .expect("valid progress template")
+                .progress_chars("=> "),
+        );
+        Self {
+            bar,
+            inner: DefaultReporter::new(),
+        }
+    }
+}
+
+impl Default for ProgressReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for ProgressReporter {
+    fn drop(&mut self) {
+        self.bar.finish_and_clear();
+    }
+}
+
+impl Reporter for ProgressReporter {
+    fn operation_start(&self, operation: &str, details: &str) {
+        self.bar.suspend(|| self.inner.operation_start(operation, details));
+    }
+
+    fn progress(&self, current: usize, total: usize, message: &str) {
+        self.bar.set_length(total as u64);
+        self.bar.set_position(current as u64);
+        self.bar.set_message(message.to_string());
+    }
+
+    fn task_success(&self, _task: &Task) {}
+
+    fn task_warning(&self, _task: &Task, _error: &str) {}
+
+    fn warning(&self, message: &str) {
+        self.bar.suspend(|| self.inner.warning(message));
+    }
+
+    fn tip(&self, message: &str) {
+        self.bar.suspend(|| self.inner.tip(message));
+    }
+
+    fn error(&self, message: &str) {
+        self.bar.suspend(|| self.inner.error(message));
+    }
+
+    fn dry_run_preview(&self, tasks: &[Task]) {
+        self.inner.dry_run_preview(tasks);
+    }
+
+    fn dry_run_preview_verbose(&self, tasks: &[Task], verbose: bool) {
+        self.inner.dry_run_preview_verbose(tasks, verbose);
+    }
+
+    fn dry_run_preview_comprehensive(&self, tasks: &[Task], verbose: bool, binary_files: &[String], ignore_patterns: &[String], verb: &str) {
+        self.inner.dry_run_preview_comprehensive(tasks, verbose, binary_files, ignore_patterns, verb);
+    }
+
+    fn verbose_operation_preview(&self, tasks: &[Task]) {
+        self.inner.verbose_operation_preview(tasks);
+    }
+
+    fn apply_complete(&self, result: &SimpleApplyResult, verbose: bool) {
+        self.bar.finish_and_clear();
+        self.inner.apply_complete(result, verbose);
+    }
+
+    fn snapshot_complete(&self, result: &SimpleSnapshotResult) {
+        self.bar.finish_and_clear();
+        self.inner.snapshot_complete(result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_moves_the_bar() {
+        let reporter = ProgressReporter::new();
+        reporter.progress(3, 10, "src/main.rs");
+        assert_eq!(reporter.bar.length(), Some(10));
+        assert_eq!(reporter.bar.position(), 3);
+        assert_eq!(reporter.bar.message(), "src/main.rs");
+    }
+}
@@ -1,49 +1,448 @@
+mod append;
+mod checksum;
+mod count;
+mod dedupe;
+mod externalize;
 mod ignore;
-
-use crate::config::{default_file_path, read_config};
+mod inherit;
+mod notes;
+mod sort;
+mod stream;
+mod tree;
+
+use crate::config::{
+    check_strict, default_file_path, quiet_from_matches, resolve_path,
+    strict_from_matches, working_dir_from_matches,
+};
 use crate::errors::SkeletorError;
-use crate::output::{DefaultReporter, SimpleSnapshotResult, Reporter};
-use crate::tasks::{compute_stats, traverse_directory, Task};
+use crate::output::{DefaultReporter, OutputFormat, SilentReporter, SimpleSnapshotResult, SnapshotCounts, Reporter};
+use crate::tasks::{
+    compute_stats, file_content, symlink_target, BinaryDetection,
+    DetectionStrategy, DirectoryWalker, InvalidNamePolicy, PreviousSnapshot, Task, TraverseOptions,
+    WalkEvent,
+};
+use crate::tasks::mtime::record_mtimes;
+use crate::utils::{parse_config_string, ConfigFormat};
 use time::OffsetDateTime;
 use time::format_description::well_known::Rfc3339;
 use clap::ArgMatches;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ::ignore::gitignore::Gitignore;
 use log::info;
 use serde_yaml::{Mapping, Value};
-#[cfg(test)]
+use std::collections::HashMap;
 use std::fs;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::time::{Duration, Instant};
+use self::append::load_previous_snapshot;
+use self::checksum::sorted_checksum;
+use self::count::{count_tree, TreeCounts};
+pub use self::checksum::directories_checksum;
+use self::dedupe::to_deduped_yaml;
+use self::externalize::externalize_contents;
 use self::ignore::{collect_ignore_spec, IgnoreSpec};
+use self::inherit::{load_inherited_config, InheritedConfig};
+use self::notes::{merge_notes, notes_from_value, parse_note_args};
+use self::sort::sort_entries;
+pub use self::sort::SortOrder;
+use self::stream::{DirectoryStream, StreamedSnapshot};
+pub use self::tree::render_tree;
+
+/// `--output` value that sends the snapshot to stdout
+const STDOUT_PATH: &str = "-";
+
+/// Files larger than this are recorded empty unless the limit is changed (5 MiB)
+pub const DEFAULT_MAX_CONTENT_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Options for taking a snapshot programmatically
+#[derive(Debug, Clone)]
+pub struct SnapshotOptions {
+    /// Capture file contents (binary files are always recorded empty)
+    pub include_contents: bool,
+    /// Globs selecting the only files whose contents are captured, matched against the path
+    /// relative to the source or the file name; other files are recorded empty
+    pub contents_for: Vec<String>,
+    /// Gitignore-style patterns; entries naming an existing file are read as ignore files
+    pub ignore_patterns: Vec<String>,
+    /// Ignore files that must exist, as with `--ignore-file`
+    pub ignore_files: Vec<PathBuf>,
+    /// Honor `.gitignore` files found under the source, layered per directory as git does
+    pub use_gitignore: bool,
+    /// Skip the `.skeletorignore` file otherwise read from the source root
+    pub no_default_ignore: bool,
+    /// Match ignore patterns regardless of case, as on case-insensitive filesystems
+    pub ignore_case: bool,
+    /// Notes in `--note` syntax: `key=value` pairs or freeform text
+    pub notes: Vec<String>,
+    /// Seed ignore patterns and notes from a `.skeletorrc` in the source
+    pub inherit_config: bool,
+    /// Descend into symlinked directories instead of recording them as links
+    pub follow_symlinks: bool,
+    /// Record directories this many levels below the source as empty mappings, unread
+    pub max_depth: Option<usize>,
+    /// Existing snapshot whose `created` timestamp and notes are carried over
+    pub output_path: Option<PathBuf>,
+    /// Base for the relative source, output and ignore file paths (default: the process CWD)
+    pub working_dir: Option<PathBuf>,
+    /// When serializing, store repeated file contents once under `anchors:` and alias each copy
+    pub dedupe: bool,
+    /// Which files are recorded as binary (empty) rather than read as text
+    pub binary_detection: BinaryDetection,
+    /// Files larger than this many bytes are recorded empty without being read and
+    /// listed as oversized; `None` reads files of any size
+    pub max_content_size: Option<u64>,
+    /// Record each file's modification time, as a `!file { content, mtime }` node
+    pub preserve_mtime: bool,
+    /// What to do with entries whose names are not valid UTF-8 (default: fail)
+    pub on_invalid_name: InvalidNamePolicy,
+    /// Order each directory's entries this way, so unchanged sources give identical
+    /// snapshots; `None` keeps the filesystem's order
+    pub sort: Option<SortOrder>,
+    /// When serializing, write the YAML while walking the source instead of building the
+    /// whole tree first; the source is walked twice and `dedupe` is not supported
+    pub streaming: bool,
+    /// Update the snapshot at `output_path`: files unmodified since its `updated` time keep
+    /// their recorded contents instead of being read again. Without one, a full snapshot is taken
+    pub append: bool,
+    /// Fail on a directory that cannot be read for lack of permission instead of leaving it
+    /// out and listing it in [`SnapshotResult::unreadable_list`]
+    pub fail_on_unreadable: bool,
+}
+
+impl Default for SnapshotOptions {
+    fn default() -> Self {
+        Self {
+            include_contents: true,
+            contents_for: Vec::new(),
+            ignore_patterns: Vec::new(),
+            ignore_files: Vec::new(),
+            use_gitignore: false,
+            no_default_ignore: false,
+            ignore_case: false,
+            notes: Vec::new(),
+            inherit_config: false,
+            follow_symlinks: false,
+            max_depth: None,
+            output_path: None,
+            working_dir: None,
+            dedupe: false,
+            binary_detection: BinaryDetection::default(),
+            max_content_size: Some(DEFAULT_MAX_CONTENT_SIZE),
+            preserve_mtime: false,
+            on_invalid_name: InvalidNamePolicy::Error,
+            sort: None,
+            streaming: false,
+            append: false,
+            fail_on_unreadable: false,
+        }
+    }
+}
+
+impl SnapshotOptions {
+    /// Starts a builder from the defaults
+    pub fn builder() -> SnapshotOptionsBuilder {
+        SnapshotOptionsBuilder::default()
+    }
+}
+
+/// Builder for [`SnapshotOptions`], for configuring snapshots without clap.
+///
+/// ```
+/// use skeletor::SnapshotOptions;
+///
+/// let opts = SnapshotOptions::builder()
+///     .include_contents(true)
+///     .ignore("*.log")
+///     .note("Captured nightly")
+///     .build();
+/// assert_eq!(opts.ignore_patterns, vec!["*.log".to_string()]);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotOptionsBuilder {
+    options: SnapshotOptions,
+}
+
+impl SnapshotOptionsBuilder {
+    pub fn include_contents(mut self, include: bool) -> Self {
+        self.options.include_contents = include;
+        self
+    }
+
+    /// Adds a glob of files whose contents are captured; once any is set, other files are recorded empty
+    pub fn contents_for(mut self, glob: impl Into<String>) -> Self {
+        self.options.contents_for.push(glob.into());
+        self
+    }
+
+    /// Adds a gitignore-style pattern (or the path of an ignore file)
+    pub fn ignore(mut self, pattern: impl Into<String>) -> Self {
+        self.options.ignore_patterns.push(pattern.into());
+        self
+    }
+
+    /// Adds an ignore file that must exist
+    pub fn ignore_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.options.ignore_files.push(path.into());
+        self
+    }
+
+    pub fn use_gitignore(mut self, enabled: bool) -> Self {
+        self.options.use_gitignore = enabled;
+        self
+    }
+
+    pub fn no_default_ignore(mut self, enabled: bool) -> Self {
+        self.options.no_default_ignore = enabled;
+        self
+    }
+
+    pub fn ignore_case(mut self, enabled: bool) -> Self {
+        self.options.ignore_case = enabled;
+        self
+    }
+
+    /// Adds a note in `--note` syntax
+    pub fn note(mut self, note: impl Into<String>) -> Self {
+        self.options.notes.push(note.into());
+        self
+    }
+
+    pub fn inherit_config(mut self, enabled: bool) -> Self {
+        self.options.inherit_config = enabled;
+        self
+    }
+
+    pub fn follow_symlinks(mut self, enabled: bool) -> Self {
+        self.options.follow_symlinks = enabled;
+        self
+    }
+
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.options.max_depth = Some(depth);
+        self
+    }
+
+    pub fn output_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.options.output_path = Some(path.into());
+        self
+    }
+
+    pub fn working_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.options.working_dir = Some(path.into());
+        self
+    }
+
+    pub fn dedupe(mut self, enabled: bool) -> Self {
+        self.options.dedupe = enabled;
+        self
+    }
+
+    pub fn binary_detection(mut self, detection: BinaryDetection) -> Self {
+        self.options.binary_detection = detection;
+        self
+    }
+
+    /// Sets the content size limit; `None` reads files of any size
+    pub fn max_content_size(mut self, limit: Option<u64>) -> Self {
+        self.options.max_content_size = limit;
+        self
+    }
+
+    pub fn preserve_mtime(mut self, enabled: bool) -> Self {
+        self.options.preserve_mtime = enabled;
+        self
+    }
+
+    pub fn on_invalid_name(mut self, policy: InvalidNamePolicy) -> Self {
+        self.options.on_invalid_name = policy;
+        self
+    }
+
+    pub fn sorted(mut self, order: SortOrder) -> Self {
+        self.options.sort = Some(order);
+        self
+    }
+
+    pub fn streaming(mut self, enabled: bool) -> Self {
+        self.options.streaming = enabled;
+        self
+    }
+
+    pub fn append(mut self, enabled: bool) -> Self {
+        self.options.append = enabled;
+        self
+    }
+
+    pub fn fail_on_unreadable(mut self, enabled: bool) -> Self {
+        self.options.fail_on_unreadable = enabled;
+        self
+    }
+
+    pub fn build(self) -> SnapshotOptions {
+        self.options
+    }
+}
+
+/// Result of taking a directory snapshot
+#[derive(Debug, Clone)]
+pub struct SnapshotResult {
+    pub files_processed: usize,
+    pub dirs_processed: usize,
+    pub duration: Duration,
+    pub output_path: Option<PathBuf>,
+    pub binary_files_excluded: usize,
+    pub binary_files_list: Vec<String>,
+    /// Files over the content size limit, recorded empty without being read
+    pub oversized_files_list: Vec<String>,
+    /// Files and directories that could not be read and were left out
+    pub unreadable_list: Vec<String>,
+    pub ignore_patterns: Vec<String>,
+    /// Each ignore pattern with the number of entries it left out, negations aside
+    pub ignored_by_pattern: Vec<(String, usize)>,
+    /// `output_path` relative to the source, when it lies inside it and was left out
+    pub excluded_output: Option<String>,
+    /// Entries left out under [`InvalidNamePolicy::Skip`] because their names are not valid UTF-8
+    #[allow(dead_code)]
+    pub invalid_names_skipped: Vec<PathBuf>,
+    /// Size of the snapshot file written to `output_path`; `None` when no snapshot file was written
+    pub bytes_written: Option<u64>,
+}
 
 /// Configuration for snapshot command extracted from CLI arguments
 struct SnapshotConfig {
     pub source_path: PathBuf,
     pub output_path: PathBuf,
-    pub include_contents: bool,
+    pub options: SnapshotOptions,
     pub dry_run: bool,
     pub verbose: bool,
-    pub user_note: Option<String>,
     pub output_to_stdout: bool,
+    pub as_tree: bool,
+    pub explicit_output: bool,
+    pub format: OutputFormat,
+    /// Contents larger than this many bytes are moved to sidecar files next to the output
+    pub externalize_threshold: Option<u64>,
+    pub strict: bool,
+    pub quiet: bool,
+    /// Report the totals only, without building or writing a snapshot
+    pub count_only: bool,
 }
 
 impl SnapshotConfig {
     fn from_matches(matches: &ArgMatches) -> Self {
+        let working_dir = working_dir_from_matches(matches);
+        let output_path = resolve_path(
+            working_dir.as_deref(),
+            default_file_path(matches.get_one::<String>("output")),
+        );
+        // `-o -` is the conventional spelling of stdout
+        let output_to_stdout = matches.get_flag("stdout")
+            || matches.get_one::<String>("output").is_some_and(|path| path == STDOUT_PATH);
+        let mut builder = SnapshotOptions::builder()
+            // Tree rendering only needs structure, so contents are never read
+            .include_contents(!matches.get_flag("exclude_contents") && !matches.get_flag("as_tree"))
+            .use_gitignore(matches.get_flag("use_gitignore"))
+            .no_default_ignore(matches.get_flag("no_default_ignore"))
+            .ignore_case(matches.get_flag("ignore_case"))
+            .inherit_config(matches.get_flag("inherit_config"))
+            .follow_symlinks(matches.get_flag("follow_symlinks"))
+            .dedupe(matches.get_flag("dedupe"))
+            .preserve_mtime(matches.get_flag("preserve_mtime"))
+            .streaming(matches.get_flag("streaming"))
+            .append(matches.get_flag("append"))
+            // Under --strict an unreadable directory fails the snapshot at once
+            .fail_on_unreadable(strict_from_matches(matches))
+            .max_content_size(matches.get_one::<u64>("max_content_size").copied())
+            .on_invalid_name(
+                matches
+                    .get_one::<String>("on_invalid_name")
+                    .and_then(|policy| InvalidNamePolicy::parse(policy))
+                    .unwrap_or(InvalidNamePolicy::Error),
+            )
+            .binary_detection(BinaryDetection {
+                strategy: matches
+                    .get_one::<String>("binary_detection")
+                    .and_then(|name| DetectionStrategy::parse(name))
+                    .unwrap_or(DetectionStrategy::Utf8),
+                binary_extensions: extensions_from_matches(matches, "binary_ext"),
+                text_extensions: extensions_from_matches(matches, "text_ext"),
+                max_size: matches.get_one::<u64>("binary_sniff_limit").copied(),
+            });
+        // Tree rendering reads no contents at all
+        if !matches.get_flag("as_tree") {
+            for glob in matches.get_many::<String>("contents_for").into_iter().flatten() {
+                builder = builder.contents_for(glob);
+            }
+        }
+        for pattern in matches.get_many::<String>("ignore").into_iter().flatten() {
+            builder = builder.ignore(pattern);
+        }
+        for path in matches.get_many::<String>("ignore_file").into_iter().flatten() {
+            builder = builder.ignore_file(path);
+        }
+        for note in matches.get_many::<String>("note").into_iter().flatten() {
+            builder = builder.note(note);
+        }
+        if let Some(depth) = matches.get_one::<usize>("max_depth") {
+            builder = builder.max_depth(*depth);
+        }
+        if let Some(order) = matches.get_one::<String>("sorted").and_then(|order| SortOrder::parse(order)) {
+            builder = builder.sorted(order);
+        }
+        // Nothing is written when only counting, so there is no output to leave out
+        if !output_to_stdout && !matches.get_flag("count_only") {
+            builder = builder.output_path(output_path.clone());
+        }
+        if let Some(dir) = &working_dir {
+            builder = builder.working_dir(dir);
+        }
+        let options = builder.build();
+
         Self {
-            source_path: PathBuf::from(matches.get_one::<String>("source").unwrap()),
-            output_path: default_file_path(matches.get_one::<String>("output")),
-            include_contents: !matches.get_flag("exclude_contents"),
+            source_path: resolve_path(
+                working_dir.as_deref(),
+                PathBuf::from(matches.get_one::<String>("source").unwrap()),
+            ),
+            output_path,
+            options,
             dry_run: matches.get_flag("dry_run"),
             verbose: matches.get_flag("verbose"),
-            user_note: matches.get_one::<String>("note").map(|s| s.to_string()),
-            output_to_stdout: matches.get_flag("stdout"),
+            output_to_stdout,
+            as_tree: matches.get_flag("as_tree"),
+            format: matches
+                .get_one::<String>("format")
+                .and_then(|name| name.parse().ok())
+                .unwrap_or(OutputFormat::Pretty),
+            explicit_output: matches.get_one::<String>("output").is_some() && !output_to_stdout,
+            externalize_threshold: matches.get_one::<u64>("externalize_threshold").copied(),
+            strict: strict_from_matches(matches),
+            quiet: quiet_from_matches(matches),
+            count_only: matches.get_flag("count_only"),
         }
     }
 }
 
+/// Reads a repeatable, comma-separated extension option, dropping any leading dots.
+fn extensions_from_matches(matches: &ArgMatches, id: &str) -> Vec<String> {
+    matches
+        .get_many::<String>(id)
+        .into_iter()
+        .flatten()
+        .flat_map(|value| value.split(','))
+        .map(|ext| ext.trim().trim_start_matches('.').to_string())
+        .filter(|ext| !ext.is_empty())
+        .collect()
+}
+
 /// Handles verbose information collection and display
-fn prepare_verbose_info(ignore_patterns: &[String], verbose: bool) -> Vec<String> {
+fn prepare_verbose_info(ignore_patterns: &[String], excluded_output: Option<&str>, verbose: bool) -> Vec<String> {
     let mut verbose_info = Vec::new();
     if verbose {
+        if let Some(output) = excluded_output {
+            verbose_info.push(format!("Excluded the snapshot's own output: {}", output));
+        }
         verbose_info.push(format!("Loaded ignore patterns: {:?}", ignore_patterns));
         if !ignore_patterns.is_empty() {
             for pattern in ignore_patterns {
@@ -57,107 +456,620 @@ fn prepare_verbose_info(ignore_patterns: &[String], verbose: bool) -> Vec<String
     verbose_info
 }
 
-struct SnapshotPlan {
-    dir_snapshot: Value,
-    binary_files: Vec<String>,
-    ignore_patterns: Vec<String>,
-    verbose_info: Vec<String>,
-    files_count: usize,
-    dirs_count: usize,
-    snapshot: Value,
-}
-
 /// Runs the snapshot subcommand: Generates a structured snapshot and writes it to disk.
 pub fn run_snapshot(matches: &ArgMatches) -> Result<(), SkeletorError> {
     let config = SnapshotConfig::from_matches(matches);
+    let reporter = DefaultReporter::with_format(config.format).quiet(config.quiet);
+    if config.count_only {
+        let result = count_snapshot(&config.source_path, &config.options, &reporter)?;
+        warn_unmatched_patterns(&config, &result, &reporter);
+        reporter.snapshot_counts(&SnapshotCounts {
+            source: config.source_path,
+            files: result.files_processed,
+            dirs: result.dirs_processed,
+            binary_files: result.binary_files_excluded,
+            duration: result.duration,
+        });
+        return check_strict(config.strict, reporter.warning_count());
+    }
+    let result = execute_snapshot(&config, &reporter)?;
+    warn_unmatched_patterns(&config, &result, &reporter);
+
+    if let Some(bytes_written) = result.bytes_written {
+        reporter.snapshot_complete(&SimpleSnapshotResult {
+            files_processed: result.files_processed,
+            dirs_processed: result.dirs_processed,
+            duration: result.duration,
+            output_path: result.output_path.unwrap_or(config.output_path),
+            binary_files_excluded: result.binary_files_excluded,
+            binary_files_list: result.binary_files_list,
+            oversized_files_list: result.oversized_files_list,
+            unreadable_list: result.unreadable_list,
+            ignored_by_pattern: result.ignored_by_pattern,
+            bytes_written,
+        });
+    }
+
+    check_strict(config.strict, reporter.warning_count())
+}
+
+/// Under `--verbose`, warns about `--ignore` patterns that left nothing out, which are often
+/// typos. Patterns read from ignore files are not checked, as most of a `.gitignore` may
+/// not apply to the tree.
+fn warn_unmatched_patterns(config: &SnapshotConfig, result: &SnapshotResult, reporter: &dyn Reporter) {
+    if !config.verbose {
+        return;
+    }
+    for (pattern, _) in result.ignored_by_pattern.iter().filter(|(_, count)| *count == 0) {
+        if config.options.ignore_patterns.iter().any(|value| value.trim() == pattern) {
+            reporter.warning(&format!("Ignore pattern '{}' did not match anything", pattern));
+        }
+    }
+}
+
+/// Takes the snapshot described by `config` and sends it where the CLI asked (file,
+/// stdout, tree or dry-run preview), returning the run's statistics. Only the final
+/// summary is left to the caller; warnings go to `reporter` as they happen.
+fn execute_snapshot(config: &SnapshotConfig, reporter: &DefaultReporter) -> Result<SnapshotResult, SkeletorError> {
+    if config.output_to_stdout && config.externalize_threshold.is_some() {
+        return Err(SkeletorError::Config(
+            "--externalize-threshold needs an output file; it cannot be used with stdout".to_string(),
+        ));
+    }
     
     info!("Taking snapshot of folder: {:?}", config.source_path);
-    let start_time = Instant::now();
 
-    let reporter = DefaultReporter::new();
-    let plan = build_snapshot_plan(matches, &config, &reporter)?;
+    if config.options.streaming && !config.dry_run {
+        return execute_streaming_snapshot(config, reporter);
+    }
+
+    let (mut result, snapshot) =
+        take_snapshot_with_reporter(&config.source_path, &config.options, reporter)?;
+    let mut verbose_info = if config.quiet {
+        Vec::new()
+    } else {
+        prepare_verbose_info(&result.ignore_patterns, result.excluded_output.as_deref(), config.verbose)
+    };
+    let dir_snapshot = snapshot.get("directories").cloned().unwrap_or(Value::Null);
 
-    let duration = start_time.elapsed();
+    // Keep stdout machine-readable in JSON mode
+    if config.format == OutputFormat::Json {
+        for info in verbose_info.drain(..) {
+            eprintln!("{}", info);
+        }
+    }
     
-    if config.dry_run {
-        print_snapshot_dry_run_context(&config);
+    if config.as_tree {
+        write_snapshot_tree(config, &dir_snapshot, reporter)?;
+    } else if config.dry_run {
+        if config.format != OutputFormat::Json && !config.quiet {
+            print_snapshot_dry_run_context(config);
+        }
         display_snapshot_dry_run_comprehensive(
-            &plan.dir_snapshot,
+            reporter,
+            &dir_snapshot,
             config.verbose,
-            &plan.binary_files,
-            &plan.ignore_patterns,
+            &result.binary_files_list,
+            &result.ignore_patterns,
         )?;
     } else if config.output_to_stdout {
-        write_snapshot_to_stdout(plan.snapshot, plan.verbose_info)?;
+        write_snapshot_to_stdout(snapshot, config.options.dedupe, verbose_info)?;
     } else {
-        write_snapshot_with_reporter(plan.snapshot, &config.output_path, plan.verbose_info)?;
-        
-        let snapshot_result = SimpleSnapshotResult {
-            files_processed: plan.files_count,
-            dirs_processed: plan.dirs_count,
-            duration,
-            output_path: config.output_path,
-            binary_files_excluded: plan.binary_files.len(),
-            binary_files_list: plan.binary_files,
+        let mut snapshot = snapshot;
+        if let Some(threshold) = config.externalize_threshold {
+            let count = externalize_contents(
+                &mut snapshot["directories"],
+                threshold,
+                crate::config::config_dir(&config.output_path),
+            )?;
+            info!("Externalized {} file contents larger than {} bytes", count, threshold);
+        }
+        result.bytes_written = Some(write_snapshot_with_reporter(
+            snapshot,
+            &config.output_path,
+            config.options.dedupe,
+            verbose_info,
+        )?);
+    }
+
+    Ok(result)
+}
+
+/// `--streaming`: writes the snapshot to the output file or stdout while walking the source
+fn execute_streaming_snapshot(config: &SnapshotConfig, reporter: &DefaultReporter) -> Result<SnapshotResult, SkeletorError> {
+    let result = if config.output_to_stdout {
+        stream_snapshot(&config.source_path, &config.options, reporter, || Ok(std::io::stdout().lock()))?.0
+    } else {
+        let output_path = &config.output_path;
+        let create = || {
+            fs::File::create(output_path)
+                .map(BufWriter::new)
+                .map_err(|e| SkeletorError::from_io_with_context(e, output_path.to_path_buf()))
+        };
+        let mut result = if crate::utils::is_gzip_path(output_path) {
+            let open = || create().map(|writer| GzEncoder::new(writer, Compression::default()));
+            let (result, encoder) = stream_snapshot(&config.source_path, &config.options, reporter, open)?;
+            encoder.finish()?.flush()?;
+            result
+        } else {
+            stream_snapshot(&config.source_path, &config.options, reporter, create)?.0
         };
-        reporter.snapshot_complete(&snapshot_result);
+        result.bytes_written = Some(
+            fs::metadata(output_path)
+                .map_err(|e| SkeletorError::from_io_with_context(e, output_path.to_path_buf()))?
+                .len(),
+        );
+        result
+    };
+
+    if !config.quiet {
+        for info in prepare_verbose_info(&result.ignore_patterns, result.excluded_output.as_deref(), config.verbose) {
+            // Keep stdout for the snapshot itself or, in JSON mode, for machine-readable output
+            if config.output_to_stdout || config.format == OutputFormat::Json {
+                eprintln!("{}", info);
+            } else {
+                println!("{}", info);
+            }
+        }
     }
-    
+
+    Ok(result)
+}
+
+/// Takes a snapshot of `source` and streams it as YAML into `writer`.
+///
+/// The writer is flushed once the whole document has been written, but never
+/// closed, so it can be a socket, an in-memory buffer or a compressor the
+/// caller finishes afterwards. With [`SnapshotOptions::streaming`], the YAML is
+/// written during the walk rather than after it. On error, part of the document may already have
+/// been written; serialization failures are reported as
+/// [`SkeletorError::Config`] and a failed flush as [`SkeletorError::Io`].
+#[allow(dead_code)]
+pub fn snapshot_to_writer(
+    source: &Path,
+    opts: &SnapshotOptions,
+    writer: &mut impl Write,
+) -> Result<SnapshotResult, SkeletorError> {
+    if opts.streaming {
+        return stream_snapshot(source, opts, &SilentReporter, || Ok(writer)).map(|(result, _)| result);
+    }
+    let (result, snapshot) = take_snapshot(source, opts)?;
+    write_snapshot_yaml(&snapshot, opts.dedupe, writer)?;
+    Ok(result)
+}
+
+/// Serializes a snapshot document into `writer` and flushes it
+fn write_snapshot_yaml(snapshot: &Value, dedupe: bool, writer: &mut impl Write) -> Result<(), SkeletorError> {
+    if dedupe {
+        writer.write_all(to_deduped_yaml(snapshot)?.as_bytes())?;
+    } else {
+        serde_yaml::to_writer(&mut *writer, snapshot).map_err(|e| SkeletorError::Config(e.to_string()))?;
+    }
+    writer.flush()?;
     Ok(())
 }
 
-fn build_snapshot_plan(
-    matches: &ArgMatches,
-    config: &SnapshotConfig,
-    reporter: &DefaultReporter,
-) -> Result<SnapshotPlan, SkeletorError> {
-    let ignore_values = matches
-        .get_many::<String>("ignore")
-        .map(|vals| vals.map(|v| v.to_string()));
-    let ignore_files = matches
-        .get_many::<String>("ignore_file")
-        .map(|vals| vals.map(|v| v.to_string()));
-
-    let IgnoreSpec {
-        matcher,
-        patterns: ignore_patterns,
-    } = collect_ignore_spec(&config.source_path, ignore_values, ignore_files, reporter)?;
-    let verbose_info = prepare_verbose_info(&ignore_patterns, config.verbose);
-
-    let (dir_snapshot, binary_files) = traverse_directory(
-        &config.source_path,
-        &config.source_path,
-        config.include_contents,
-        matcher.as_ref(),
-        false,
-    )?;
+/// Takes a snapshot of `source` without writing anything to disk.
+///
+/// Returns the run statistics together with the full snapshot document
+/// (metadata, `stats` and `directories`), ready to serialize or inspect.
+#[allow(dead_code)]
+pub fn take_snapshot(
+    source: &Path,
+    opts: &SnapshotOptions,
+) -> Result<(SnapshotResult, Value), SkeletorError> {
+    take_snapshot_with_reporter(source, opts, &SilentReporter)
+}
+
+fn build_contents_globs(patterns: &[String]) -> Result<Option<GlobSet>, SkeletorError> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| {
+            SkeletorError::Config(format!("Invalid --contents-for pattern '{}': {}", pattern, e))
+        })?;
+        builder.add(glob);
+    }
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| SkeletorError::Config(format!("Failed to compile --contents-for patterns: {}", e)))
+}
+
+/// Same as [`take_snapshot`], routing warnings (e.g. skipped ignore lines) to `reporter`.
+pub fn take_snapshot_with_reporter(
+    source: &Path,
+    opts: &SnapshotOptions,
+    reporter: &dyn Reporter,
+) -> Result<(SnapshotResult, Value), SkeletorError> {
+    let start_time = Instant::now();
+    let setup = SnapshotSetup::prepare(source, opts, reporter)?;
+    let source = setup.source.as_path();
+
+    let mut log = WalkLog::default();
+    let (mut dir_snapshot, binary_files) = setup
+        .walker(opts, Some(&mut |event: WalkEvent| log.record(event, source)))
+        .walk(source, 0)?;
+    log.report(reporter);
+    if opts.preserve_mtime {
+        record_mtimes(&mut dir_snapshot, source);
+    }
+    if let Some(order) = opts.sort {
+        sort_entries(&mut dir_snapshot, order);
+    }
     let (files_count, dirs_count) = compute_stats(&dir_snapshot);
 
     let snapshot = build_snapshot(
-        if config.output_to_stdout {
-            None
-        } else {
-            Some(&config.output_path)
-        },
-        &config.source_path,
-        config.user_note.clone(),
-        dir_snapshot.clone(),
+        setup.output_path.as_deref(),
+        source,
+        setup.notes(opts),
+        setup.inherited.as_ref(),
+        dir_snapshot,
         binary_files.clone(),
         files_count,
         dirs_count,
     )?;
 
-    Ok(SnapshotPlan {
-        dir_snapshot,
-        binary_files,
-        ignore_patterns,
-        verbose_info,
-        files_count,
-        dirs_count,
-        snapshot,
+    let result = SnapshotResult {
+        files_processed: files_count,
+        dirs_processed: dirs_count,
+        duration: start_time.elapsed(),
+        output_path: setup.output_path,
+        binary_files_excluded: binary_files.len(),
+        binary_files_list: binary_files,
+        unreadable_list: log.unreadable_list(source),
+        ignored_by_pattern: log.ignored_by_pattern(&setup.ignore_patterns),
+        oversized_files_list: log.oversized_files,
+        ignore_patterns: setup.ignore_patterns,
+        excluded_output: setup.excluded_output,
+        invalid_names_skipped: log.invalid_names_skipped,
+        bytes_written: None,
+    };
+
+    Ok((result, snapshot))
+}
+
+/// Takes a snapshot of `source` and writes it as YAML while walking, so the tree is never
+/// held in memory. The writer is opened with `open` only once the metadata is known.
+///
+/// The source is walked twice: once for the stats and checksum, which the document lists
+/// before `directories`, and once to write it. Changes to the source in between make the
+/// checksum disagree with the written tree. Returns the writer, flushed, so the caller
+/// can finish it.
+fn stream_snapshot<W: Write>(
+    source: &Path,
+    opts: &SnapshotOptions,
+    reporter: &dyn Reporter,
+    open: impl FnOnce() -> Result<W, SkeletorError>,
+) -> Result<(SnapshotResult, W), SkeletorError> {
+    if opts.dedupe {
+        return Err(SkeletorError::Config(
+            "dedupe needs the whole snapshot in memory; it cannot be used with streaming".to_string(),
+        ));
+    }
+    if opts.append {
+        return Err(SkeletorError::Config(
+            "append merges into the snapshot being replaced; it cannot be used with streaming".to_string(),
+        ));
+    }
+    let start_time = Instant::now();
+    let setup = SnapshotSetup::prepare(source, opts, reporter)?;
+    let source = setup.source.as_path();
+    let mut log = WalkLog::default();
+    let mut record = |event: WalkEvent| log.record(event, source);
+    let counting = setup.directory_stream(opts, None, Some(&mut record));
+    let checksum = sorted_checksum(&counting).map_err(|e| counting.walk_error_or(e))?;
+    let summary = counting.into_summary();
+    log.report(reporter);
+
+    let mut header = snapshot_header(
+        setup.output_path.as_deref(),
+        source,
+        setup.notes(opts),
+        setup.inherited.as_ref(),
+        &summary.binary_files,
+        summary.files,
+        summary.dirs,
+    )?;
+    header.insert(Value::String("checksum".to_string()), Value::String(checksum));
+
+    let mut writer = open()?;
+    let directories = setup.directory_stream(opts, opts.sort, None);
+    serde_yaml::to_writer(&mut writer, &StreamedSnapshot { header: &header, directories: &directories })
+        .map_err(|e| directories.walk_error_or(SkeletorError::Config(e.to_string())))?;
+    writer.flush()?;
+
+    let result = SnapshotResult {
+        files_processed: summary.files,
+        dirs_processed: summary.dirs,
+        duration: start_time.elapsed(),
+        output_path: setup.output_path,
+        binary_files_excluded: summary.binary_files.len(),
+        binary_files_list: summary.binary_files,
+        unreadable_list: log.unreadable_list(source),
+        ignored_by_pattern: log.ignored_by_pattern(&setup.ignore_patterns),
+        oversized_files_list: log.oversized_files,
+        ignore_patterns: setup.ignore_patterns,
+        excluded_output: setup.excluded_output,
+        invalid_names_skipped: log.invalid_names_skipped,
+        bytes_written: None,
+    };
+
+    Ok((result, writer))
+}
+
+/// Counts the files, directories and binary files a snapshot of `source` would record,
+/// walking it with the same filters but never building the tree or keeping contents.
+///
+/// File contents are still read to detect binary files, unless `opts` captures none.
+/// The result has no output path and no bytes written.
+pub fn count_snapshot(
+    source: &Path,
+    opts: &SnapshotOptions,
+    reporter: &dyn Reporter,
+) -> Result<SnapshotResult, SkeletorError> {
+    let start_time = Instant::now();
+    let setup = SnapshotSetup::prepare(source, opts, reporter)?;
+    let source = setup.source.as_path();
+
+    let mut log = WalkLog::default();
+    let mut counts = TreeCounts::default();
+    count_tree(
+        &mut setup.walker(opts, Some(&mut |event: WalkEvent| log.record(event, source))),
+        source,
+        0,
+        &mut counts,
+    )?;
+    log.report(reporter);
+    counts.binary_files.sort();
+
+    Ok(SnapshotResult {
+        files_processed: counts.files,
+        dirs_processed: counts.dirs,
+        duration: start_time.elapsed(),
+        output_path: None,
+        binary_files_excluded: counts.binary_files.len(),
+        binary_files_list: counts.binary_files,
+        unreadable_list: log.unreadable_list(source),
+        ignored_by_pattern: log.ignored_by_pattern(&setup.ignore_patterns),
+        oversized_files_list: log.oversized_files,
+        ignore_patterns: setup.ignore_patterns,
+        excluded_output: setup.excluded_output,
+        invalid_names_skipped: log.invalid_names_skipped,
+        bytes_written: None,
     })
 }
 
+/// Everything a snapshot needs before walking: resolved paths, the inherited
+/// `.skeletorrc`, the ignore matcher and the `contents_for` globs
+struct SnapshotSetup {
+    source: PathBuf,
+    output_path: Option<PathBuf>,
+    inherited: Option<InheritedConfig>,
+    matcher: Option<Gitignore>,
+    ignore_patterns: Vec<String>,
+    contents_for: Option<GlobSet>,
+    /// The snapshot `append` updates
+    previous: Option<PreviousSnapshot>,
+    /// The output file relative to the source, when it would otherwise be captured
+    excluded_output: Option<String>,
+}
+
+impl SnapshotSetup {
+    fn prepare(source: &Path, opts: &SnapshotOptions, reporter: &dyn Reporter) -> Result<Self, SkeletorError> {
+        let working_dir = opts.working_dir.as_deref();
+        let source = resolve_path(working_dir, source.to_path_buf());
+        let output_path = opts
+            .output_path
+            .clone()
+            .map(|path| resolve_path(working_dir, path));
+
+        let inherited = if opts.inherit_config {
+            let inherited = load_inherited_config(&source)?;
+            if inherited.is_none() {
+                reporter.warning(&format!(
+                    "No .skeletorrc found in {} to inherit from",
+                    source.display()
+                ));
+            }
+            inherited
+        } else {
+            None
+        };
+
+        // Inherited patterns come first; caller patterns are added on top of them
+        let ignore_values: Vec<String> = inherited
+            .iter()
+            .flat_map(|defaults| defaults.ignore_patterns.iter().cloned())
+            .chain(opts.ignore_patterns.iter().map(|value| match working_dir {
+                // A value naming a file is read as an ignore file, relative to the working dir
+                Some(base) if base.join(value).is_file() => base.join(value).to_string_lossy().into_owned(),
+                _ => value.clone(),
+            }))
+            .collect();
+        let ignore_files = opts
+            .ignore_files
+            .iter()
+            .map(|path| resolve_path(working_dir, path.clone()).to_string_lossy().into_owned());
+
+        let IgnoreSpec {
+            matcher,
+            patterns: ignore_patterns,
+        } = collect_ignore_spec(
+            &source,
+            Some(ignore_values.into_iter()),
+            Some(ignore_files),
+            opts.use_gitignore,
+            !opts.no_default_ignore,
+            opts.ignore_case,
+            reporter,
+        )?;
+
+        let contents_for = build_contents_globs(&opts.contents_for)?;
+        let previous = match &output_path {
+            Some(path) if opts.append => load_previous_snapshot(path)?,
+            _ => None,
+        };
+        let excluded_output = output_path.as_deref().and_then(|path| relative_within(&source, path));
+
+        Ok(Self {
+            source,
+            output_path,
+            inherited,
+            matcher,
+            ignore_patterns,
+            contents_for,
+            previous,
+            excluded_output,
+        })
+    }
+
+    /// A walker over the source with the options' filters and content rules
+    fn walker<'a, 'p>(
+        &'a self,
+        opts: &'a SnapshotOptions,
+        progress: Option<&'p mut dyn FnMut(WalkEvent)>,
+    ) -> DirectoryWalker<'a, 'p> {
+        let options = TraverseOptions {
+            include_contents: opts.include_contents,
+            contents_for: self.contents_for.as_ref(),
+            binary: opts.binary_detection.clone(),
+            max_content_size: opts.max_content_size,
+            ignore: self.matcher.as_ref(),
+            verbose: false,
+            follow_symlinks: opts.follow_symlinks,
+            max_depth: opts.max_depth,
+            on_invalid_name: opts.on_invalid_name,
+        };
+        DirectoryWalker::new(&self.source, options, progress)
+        .reusing(self.previous.as_ref())
+        .skipping_unreadable_dirs(!opts.fail_on_unreadable)
+        .excluding(self.excluded_output.as_deref())
+    }
+
+    /// A streamed walk of the source, configured as [`take_snapshot_with_reporter`] walks it;
+    /// entries come in checksum order unless `sort` overrides it
+    fn directory_stream<'a, 'p>(
+        &'a self,
+        opts: &'a SnapshotOptions,
+        sort: Option<SortOrder>,
+        progress: Option<&'p mut dyn FnMut(WalkEvent)>,
+    ) -> DirectoryStream<'a, 'p> {
+        DirectoryStream::new(
+            self.walker(opts, progress),
+            &self.source,
+            opts.preserve_mtime,
+            sort,
+        )
+    }
+
+    /// Inherited notes with the caller's `--note` values on top
+    fn notes(&self, opts: &SnapshotOptions) -> Mapping {
+        merge_notes(
+            self.inherited
+                .as_ref()
+                .map(|defaults| defaults.notes.clone())
+                .unwrap_or_default(),
+            parse_note_args(&opts.notes),
+        )
+    }
+}
+
+/// `path` relative to `source`, `/`-separated, when it lies inside it. Both are
+/// canonicalized, so `.` and symlinked spellings of the same place compare equal;
+/// `path` itself need not exist yet.
+fn relative_within(source: &Path, path: &Path) -> Option<String> {
+    let source = fs::canonicalize(source).ok()?;
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let path = fs::canonicalize(parent).ok()?.join(path.file_name()?);
+    let relative = path.strip_prefix(&source).ok()?;
+    Some(relative.to_string_lossy().replace('\\', "/"))
+}
+
+/// What the walk reported through its [`WalkEvent`]s
+#[derive(Default)]
+struct WalkLog {
+    entries_seen: usize,
+    /// Entries left out, by the ignore pattern that matched them
+    ignored: HashMap<String, usize>,
+    unreadable: Vec<(PathBuf, String)>,
+    invalid_names_skipped: Vec<PathBuf>,
+    oversized_files: Vec<String>,
+}
+
+impl WalkLog {
+    fn record(&mut self, event: WalkEvent, source: &Path) {
+        match event {
+            WalkEvent::Visited(path) => {
+                self.entries_seen += 1;
+                // Log progress every 1000 entries to keep long walks observable
+                if self.entries_seen % 1000 == 0 {
+                    info!("Scanned {} entries (at {:?})...", self.entries_seen, path);
+                }
+            }
+            WalkEvent::Unreadable(path, error) => self.unreadable.push((path.to_path_buf(), error.to_string())),
+            WalkEvent::InvalidName(path) => self.invalid_names_skipped.push(path.to_path_buf()),
+            WalkEvent::Ignored(path, pattern) => {
+                info!("Ignored {:?} (matched by {})", path, pattern);
+                *self.ignored.entry(pattern.to_string()).or_default() += 1;
+            }
+            WalkEvent::Oversized(path) => self.oversized_files.push(
+                path.strip_prefix(source)
+                    .unwrap_or(path)
+                    .to_string_lossy()
+                    .replace('\\', "/"),
+            ),
+        }
+    }
+
+    /// The unreadable entries' paths relative to `source`, sorted
+    fn unreadable_list(&self, source: &Path) -> Vec<String> {
+        let mut paths: Vec<String> = self
+            .unreadable
+            .iter()
+            .map(|(path, _)| path.strip_prefix(source).unwrap_or(path).to_string_lossy().replace('\\', "/"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// How many entries each of `patterns` left out, in their order; negations, which
+    /// only re-include, are not listed
+    fn ignored_by_pattern(&self, patterns: &[String]) -> Vec<(String, usize)> {
+        let mut counts: Vec<(String, usize)> = Vec::new();
+        for pattern in patterns.iter().filter(|pattern| !pattern.starts_with('!')) {
+            if counts.iter().all(|(listed, _)| listed != pattern) {
+                counts.push((pattern.clone(), self.ignored.get(pattern).copied().unwrap_or(0)));
+            }
+        }
+        counts
+    }
+
+    /// Sorts the oversized files and sends the walk's warnings to `reporter`
+    fn report(&mut self, reporter: &dyn Reporter) {
+        self.oversized_files.sort();
+        for (path, error) in &self.unreadable {
+            reporter.warning(&format!(
+                "Could not read {} ({}); it was left out of the snapshot",
+                path.display(),
+                error
+            ));
+        }
+        for path in &self.invalid_names_skipped {
+            reporter.warning(&format!(
+                "Skipped {}: its name is not valid UTF-8",
+                path.display()
+            ));
+        }
+    }
+}
+
 fn print_snapshot_dry_run_context(config: &SnapshotConfig) {
     let output_target = if config.output_to_stdout {
         "stdout".to_string()
@@ -167,36 +1079,71 @@ fn print_snapshot_dry_run_context(config: &SnapshotConfig) {
     println!("Output target: {}", output_target);
     println!(
         "Include contents: {}",
-        if config.include_contents { "yes" } else { "no" }
+        if config.options.include_contents { "yes" } else { "no" }
     );
     println!();
 }
 
 /// Builds a structured snapshot with metadata.
+#[allow(clippy::too_many_arguments)]
 fn build_snapshot(
     output_path: Option<&Path>,
     source_path: &Path,
-    user_note: Option<String>,
+    notes: Mapping,
+    inherited: Option<&InheritedConfig>,
     dir_snapshot: Value,
     binary_files: Vec<String>,
     files_count: usize,
     dirs_count: usize,
 ) -> Result<Value, SkeletorError> {
+    let mut top_map = snapshot_header(
+        output_path,
+        source_path,
+        notes,
+        inherited,
+        &binary_files,
+        files_count,
+        dirs_count,
+    )?;
+    top_map.insert(
+        Value::String("checksum".to_string()),
+        Value::String(directories_checksum(&dir_snapshot)?),
+    );
+    top_map.insert(Value::String("directories".to_string()), dir_snapshot);
+
+    Ok(Value::Mapping(top_map))
+}
+
+/// The snapshot's metadata and `stats`, everything that precedes `checksum` and `directories`
+fn snapshot_header(
+    output_path: Option<&Path>,
+    source_path: &Path,
+    notes: Mapping,
+    inherited: Option<&InheritedConfig>,
+    binary_files: &[String],
+    files_count: usize,
+    dirs_count: usize,
+) -> Result<Mapping, SkeletorError> {
     let now = OffsetDateTime::now_utc()
         .format(&Rfc3339)
         .map_err(|e| SkeletorError::Config(e.to_string()))?;
     let mut created = now.clone();
+    let mut existing_notes = Mapping::new();
 
-    // Preserve "created" timestamp if output file exists
+    // Preserve "created" timestamp and structured notes if output file exists
     if let Some(path) = output_path {
         if path.exists() {
-            if let Ok(existing_config) = read_config(path) {
-                if let Some(Value::String(c)) = existing_config.get("created") {
-                    created = c.clone();
+            if let Some(existing_created) = existing_created(path) {
+                created = existing_created;
+            }
+            if let Ok(existing_doc) = crate::utils::read_yaml_file(path) {
+                if let Some(existing) = existing_doc.get("notes") {
+                    existing_notes = notes_from_value(existing);
                 }
             }
         }
     }
+    let notes = merge_notes(existing_notes, notes);
 
     let updated = now;
 
@@ -217,9 +1164,38 @@ fn build_snapshot(
         Value::String("generated_comments".to_string()),
         Value::String(auto_info),
     );
+    top_map.insert(
+        Value::String("generated_with".to_string()),
+        Value::String(env!("CARGO_PKG_VERSION").to_string()),
+    );
+    // Listed for apply, which warns that these come out empty
+    if !binary_files.is_empty() {
+        top_map.insert(
+            Value::String("binary_files".to_string()),
+            Value::Sequence(binary_files.iter().map(|f| Value::String(f.clone())).collect()),
+        );
+    }
+
+    if !notes.is_empty() {
+        top_map.insert(Value::String("notes".to_string()), Value::Mapping(notes));
+    }
 
-    if let Some(note) = user_note {
-        top_map.insert(Value::String("notes".to_string()), Value::String(note));
+    if let Some(defaults) = inherited {
+        if let Some(name) = &defaults.name {
+            top_map.insert(Value::String("name".to_string()), Value::String(name.clone()));
+        }
+        if !defaults.ignore_patterns.is_empty() {
+            top_map.insert(
+                Value::String("ignore_patterns".to_string()),
+                Value::Sequence(
+                    defaults
+                        .ignore_patterns
+                        .iter()
+                        .map(|p| Value::String(p.clone()))
+                        .collect(),
+                ),
+            );
+        }
     }
 
     let mut stats_map = Mapping::new();
@@ -231,14 +1207,44 @@ fn build_snapshot(
         Value::String("directories".to_string()),
         Value::Number(dirs_count.into()),
     );
+    // Binary files are kept in the tree as empty entries, so `files` already
+    // includes them; `binary_files` says how many of those had contents omitted
+    stats_map.insert(
+        Value::String("binary_files".to_string()),
+        Value::Number(binary_files.len().into()),
+    );
 
     top_map.insert(
         Value::String("stats".to_string()),
         Value::Mapping(stats_map),
     );
-    top_map.insert(Value::String("directories".to_string()), dir_snapshot);
 
-    Ok(Value::Mapping(top_map))
+    Ok(top_map)
+}
+
+/// The `created` timestamp of an existing snapshot, whatever its format.
+///
+/// The file is parsed as the format its extension names, then as the others; when
+/// none of them accepts it, the first `created` line holding an RFC 3339 timestamp
+/// is used, so a malformed or half-edited snapshot still keeps its creation date.
+fn existing_created(path: &Path) -> Option<String> {
+    let content = crate::utils::read_file_to_string(path).ok()?;
+    let parsed = [ConfigFormat::from_path(path), ConfigFormat::Yaml, ConfigFormat::Json, ConfigFormat::Toml]
+        .into_iter()
+        .find_map(|format| parse_config_string(&content, format).ok());
+    match parsed.as_ref().and_then(|doc| doc.get("created")) {
+        Some(Value::String(created)) => Some(created.clone()),
+        _ => content.lines().find_map(created_from_line),
+    }
+}
+
+/// Reads `created: <time>` (YAML), `"created": "<time>",` (JSON) or `created = <time>` (TOML)
+fn created_from_line(line: &str) -> Option<String> {
+    let rest = line.trim().trim_start_matches('"').strip_prefix("created")?;
+    let value = rest.trim_start_matches('"').trim_start().strip_prefix([':', '='])?;
+    let value = value.trim().trim_end_matches(',').trim_matches(|c| c == '"' || c == '\'');
+    OffsetDateTime::parse(value, &Rfc3339).ok()?;
+    Some(value.to_string())
 }
 
 /// Displays snapshot dry run output using professional formatting
@@ -273,14 +1279,16 @@ fn snapshot_to_operations(dir_snapshot: &Value, base_path: &str) -> Vec<Task> {
                     format!("{}/{}", base_path, name)
                 };
                 
-                if value.as_mapping().is_some() {
+                if let Value::Mapping(_) = value {
                     // This is a directory
                     operations.push(Task::Dir(path.clone().into()));
                     // Recursively process subdirectories and files
                     operations.extend(snapshot_to_operations(value, &path));
-                } else if let Some(_content) = value.as_str() {
-                    // This is a file
-                    operations.push(Task::File(path.into(), "".to_string()));
+                } else if let Some(content) = file_content(value) {
+                    // This is a file; keep its content so verbose previews can show sizes
+                    operations.push(Task::File(path.into(), content.to_string()));
+                } else if let Some(target) = symlink_target(value) {
+                    operations.push(Task::Symlink(path.into(), target.to_string()));
                 }
             }
         }
@@ -291,6 +1299,7 @@ fn snapshot_to_operations(dir_snapshot: &Value, base_path: &str) -> Vec<Task> {
 
 /// Displays comprehensive snapshot dry run using Reporter system for consistency
 fn display_snapshot_dry_run_comprehensive(
+    reporter: &DefaultReporter,
     dir_snapshot: &Value, 
     verbose: bool, 
     binary_files: &[String], 
@@ -300,17 +1309,27 @@ fn display_snapshot_dry_run_comprehensive(
     let operations = snapshot_to_operations(dir_snapshot, "");
     
     // Use the Reporter system for consistent formatting
-    let reporter = DefaultReporter::new();
     reporter.dry_run_preview_comprehensive(&operations, verbose, binary_files, ignore_patterns, "captured");
     
     Ok(())
 }
 
-/// Writes snapshot to disk - output handled by Reporter system
-fn write_snapshot_with_reporter(snapshot: Value, output_path: &Path, verbose_info: Vec<String>) -> Result<(), SkeletorError> {
-    let out_str = serde_yaml::to_string(&snapshot).map_err(|e| SkeletorError::Config(e.to_string()))?;
-    
-    crate::utils::write_string_to_file(output_path, &out_str)?;
+/// Writes snapshot to disk, gzip-compressed when the path ends in `.gz` - output handled by Reporter system.
+/// Returns the size of the written file.
+fn write_snapshot_with_reporter(snapshot: Value, output_path: &Path, dedupe: bool, verbose_info: Vec<String>) -> Result<u64, SkeletorError> {
+    let file = fs::File::create(output_path)
+        .map_err(|e| SkeletorError::from_io_with_context(e, output_path.to_path_buf()))?;
+    let mut writer = BufWriter::new(file);
+    if crate::utils::is_gzip_path(output_path) {
+        let mut encoder = GzEncoder::new(writer, Compression::default());
+        write_snapshot_yaml(&snapshot, dedupe, &mut encoder)?;
+        encoder.finish()?.flush()?;
+    } else {
+        write_snapshot_yaml(&snapshot, dedupe, &mut writer)?;
+    }
+    let bytes_written = fs::metadata(output_path)
+        .map_err(|e| SkeletorError::from_io_with_context(e, output_path.to_path_buf()))?
+        .len();
     
     // Verbose information display (if needed)
     if !verbose_info.is_empty() {
@@ -319,12 +1338,37 @@ fn write_snapshot_with_reporter(snapshot: Value, output_path: &Path, verbose_inf
         }
     }
     
+    Ok(bytes_written)
+}
+
+/// Writes a `tree`-style rendering to stdout, or to the output file when `-o` was given
+fn write_snapshot_tree(
+    config: &SnapshotConfig,
+    dir_snapshot: &Value,
+    reporter: &DefaultReporter,
+) -> Result<(), SkeletorError> {
+    let root_name = config
+        .source_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| config.source_path.display().to_string());
+    let tree = render_tree(&root_name, dir_snapshot, &[]);
+
+    if config.explicit_output {
+        crate::utils::write_string_to_file(&config.output_path, &tree)?;
+        reporter.operation_start(
+            "snapshot",
+            &format!("Tree written to {}", config.output_path.display()),
+        );
+    } else {
+        print!("{}", tree);
+    }
+
     Ok(())
 }
 
-fn write_snapshot_to_stdout(snapshot: Value, verbose_info: Vec<String>) -> Result<(), SkeletorError> {
-    let out_str = serde_yaml::to_string(&snapshot).map_err(|e| SkeletorError::Config(e.to_string()))?;
-    println!("{}", out_str);
+fn write_snapshot_to_stdout(snapshot: Value, dedupe: bool, verbose_info: Vec<String>) -> Result<(), SkeletorError> {
+    write_snapshot_yaml(&snapshot, dedupe, &mut std::io::stdout().lock())?;
 
     if !verbose_info.is_empty() {
         for info in verbose_info {
@@ -341,6 +1385,7 @@ mod tests {
     use std::path::Path;
 
     use super::*;
+    use crate::tasks::traverse_directory;
     use crate::test_utils::helpers::*;
     use clap::ArgMatches;
 
@@ -471,6 +1516,33 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_snapshot_leaves_out_its_own_output_inside_the_source() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/index.js", "console.log('Hello');");
+        let output_file = fs.create_file("snapshots/tree.yml", "stale: true\n");
+
+        // Run twice: the second run must not capture the first run's output either
+        for _ in 0..2 {
+            let args = vec![fs.root_path.to_str().unwrap(), "--output", output_file.to_str().unwrap()];
+            let sub_m = create_snapshot_matches(args).expect("Snapshot subcommand not found");
+            assert!(run_snapshot(&sub_m).is_ok());
+        }
+
+        let snapshot: Value = serde_yaml::from_str(&fs::read_to_string(&output_file).unwrap()).unwrap();
+        assert_eq!(snapshot["directories"]["snapshots"], Value::Mapping(Mapping::new()));
+        assert!(snapshot["directories"]["src"].get("index.js").is_some());
+
+        // Through the library API with a relative source, the output is found all the same
+        let opts = SnapshotOptions::builder()
+            .working_dir(&fs.root_path)
+            .output_path("snapshots/tree.yml")
+            .build();
+        let (result, snapshot) = take_snapshot(Path::new("."), &opts).unwrap();
+        assert_eq!(result.excluded_output.as_deref(), Some("snapshots/tree.yml"));
+        assert!(snapshot["directories"]["snapshots"].get("tree.yml").is_none());
+    }
+
     #[test]
     fn test_run_snapshot_with_stdout_flag() {
         let fs = TestFileSystem::new();
@@ -491,6 +1563,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_output_dash_means_stdout() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/index.js", "console.log('Hello');");
+        let source = fs.root_path.to_str().unwrap();
+
+        let sub_m = create_snapshot_matches(vec![source, "-o", "-"]).unwrap();
+        let config = SnapshotConfig::from_matches(&sub_m);
+        assert!(config.output_to_stdout);
+        assert!(!config.explicit_output);
+        // No existing file is consulted for the `created` timestamp
+        assert!(config.options.output_path.is_none());
+
+        let sub_m = create_snapshot_matches(vec![source, "-o", "-", "--externalize-threshold", "10"]).unwrap();
+        assert!(matches!(run_snapshot(&sub_m), Err(SkeletorError::Config(_))));
+    }
+
     #[test]
     fn test_run_snapshot_with_ignore_patterns() {
         let fs = TestFileSystem::new();
@@ -535,6 +1624,404 @@ mod tests {
             panic!("Snapshot subcommand not found");
         }
     }
+
+    #[test]
+    fn test_execute_snapshot_returns_statistics() {
+        let fs = TestFileSystem::new();
+        fs.create_file("project/src/index.js", "console.log('Hello');");
+        fs.create_file("project/docs/guide.md", "# Guide");
+        fs.create_binary_file("project/src/binary.bin", &[0, 159, 146, 150]);
+        let output = fs.path("snapshot.yml");
+        let reporter = DefaultReporter::new().quiet(true);
+
+        let sub_m = create_snapshot_matches(vec![fs.path("project").to_str().unwrap(), "-o", output.to_str().unwrap()]).unwrap();
+        let result = execute_snapshot(&SnapshotConfig::from_matches(&sub_m), &reporter).unwrap();
+        assert_eq!(result.files_processed, 3);
+        assert_eq!(result.dirs_processed, 2);
+        assert_eq!(result.binary_files_excluded, 1);
+        assert_eq!(result.binary_files_list, vec!["src/binary.bin".to_string()]);
+        assert_eq!(result.bytes_written, Some(std::fs::metadata(&output).unwrap().len()));
+
+        // A dry run reports the same statistics without writing anything
+        std::fs::remove_file(&output).unwrap();
+        let sub_m = create_snapshot_matches(vec![fs.path("project").to_str().unwrap(), "-o", output.to_str().unwrap(), "--dry-run"]).unwrap();
+        let result = execute_snapshot(&SnapshotConfig::from_matches(&sub_m), &reporter).unwrap();
+        assert_eq!((result.files_processed, result.dirs_processed, result.binary_files_excluded), (3, 2, 1));
+        assert_eq!(result.bytes_written, None);
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_run_snapshot_with_binary_detection_options() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        fs.create_file("project/src/index.js", "console.log('Hello');");
+        fs.create_file("project/assets/logo.png", "png-ish text");
+        fs.create_binary_file("project/assets/blob.dat", &[b'a', 0, b'b']);
+        fs.create_file("project/big.log", &"line\n".repeat(1000));
+        let output_file = fs.path("out.yml");
+
+        let args = vec![
+            source.to_str().unwrap(),
+            "--output",
+            output_file.to_str().unwrap(),
+            "--binary-detection",
+            "null-byte",
+            "--binary-ext",
+            ".png,mp4",
+            "--binary-sniff-limit",
+            "1024",
+        ];
+        let sub_m = create_snapshot_matches(args).expect("Snapshot subcommand not found");
+        assert!(run_snapshot(&sub_m).is_ok());
+
+        let snapshot: Value = serde_yaml::from_str(&fs::read_to_string(&output_file).unwrap()).unwrap();
+        assert_eq!(snapshot["stats"]["binary_files"].as_u64(), Some(3));
+        for binary in ["assets/blob.dat", "assets/logo.png", "big.log"] {
+            assert!(snapshot["generated_comments"].as_str().unwrap().contains(binary));
+        }
+        assert_eq!(snapshot["directories"]["assets"]["logo.png"].as_str(), Some(""));
+        assert_eq!(snapshot["directories"]["big.log"].as_str(), Some(""));
+        assert_eq!(snapshot["directories"]["src"]["index.js"].as_str(), Some("console.log('Hello');"));
+    }
+
+    #[test]
+    fn test_snapshot_stats_include_binary_files() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/index.js", "console.log('Hello');");
+        fs.create_file("src/util.js", "");
+        fs.create_binary_file("src/binary.bin", &[0, 159, 146, 150]);
+
+        let (result, snapshot) = take_snapshot(&fs.root_path, &SnapshotOptions::default()).unwrap();
+
+        assert_eq!(result.binary_files_excluded, 1);
+        assert_eq!(snapshot["stats"]["files"].as_u64(), Some(3));
+        assert_eq!(snapshot["stats"]["binary_files"].as_u64(), Some(1));
+        assert_eq!(snapshot["stats"]["directories"].as_u64(), Some(1));
+        // Listed at the top level too, for apply to warn about
+        assert_eq!(crate::apply::extract_binary_files_from_yaml(&snapshot), vec!["src/binary.bin".to_string()]);
+    }
+
+    #[test]
+    fn test_snapshot_negated_ignore_pattern_re_includes_a_file() {
+        let fs = TestFileSystem::new();
+        fs.create_file("build/keep.txt", "kept");
+        fs.create_file("build/out.o", "object");
+        fs.create_file("build/cache/index", "cache");
+        fs.create_file("src/main.rs", "fn main() {}");
+
+        let snapshot = |patterns: &[&str]| {
+            let mut builder = SnapshotOptions::builder();
+            for pattern in patterns {
+                builder = builder.ignore(*pattern);
+            }
+            take_snapshot(&fs.root_path, &builder.build()).unwrap().1
+        };
+
+        let snapshot_with_reinclude = snapshot(&["build/*", "!build/keep.txt"]);
+        let build = snapshot_with_reinclude["directories"]["build"].as_mapping().unwrap();
+        assert_eq!(build.len(), 1);
+        assert_eq!(build.get("keep.txt").and_then(Value::as_str), Some("kept"));
+        assert!(snapshot_with_reinclude["directories"]["src"]["main.rs"].is_string());
+
+        // As in git, a file cannot be re-included once its directory is ignored
+        let snapshot_without = snapshot(&["build/", "!build/keep.txt"]);
+        assert!(snapshot_without["directories"].get("build").is_none());
+    }
+
+    #[test]
+    fn test_snapshot_counts_entries_left_out_by_each_ignore_pattern() {
+        let fs = TestFileSystem::new();
+        fs.create_file("debug.log", "");
+        fs.create_file("src/trace.log", "");
+        fs.create_file("src/keep.log", "");
+        fs.create_file("target/debug/app", "");
+        fs.create_file("src/main.rs", "fn main() {}");
+
+        let opts = SnapshotOptions::builder()
+            .ignore("*.log")
+            .ignore("target/")
+            .ignore("*.lgo")
+            .ignore("!keep.log")
+            .build();
+        let (result, _) = take_snapshot(&fs.root_path, &opts).unwrap();
+
+        // `target/` counts once, for the directory; the negation only re-includes
+        let expected = [("*.log", 2), ("target/", 1), ("*.lgo", 0)];
+        assert_eq!(
+            result.ignored_by_pattern,
+            expected.map(|(pattern, count)| (pattern.to_string(), count))
+        );
+        assert_eq!(count_snapshot(&fs.root_path, &opts, &SilentReporter).unwrap().ignored_by_pattern, result.ignored_by_pattern);
+    }
+
+    #[test]
+    fn test_count_snapshot_matches_the_snapshot_stats() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/index.js", "console.log('Hello');");
+        fs.create_file("src/util/empty.js", "");
+        fs.create_binary_file("assets/logo.bin", &[0, 159, 146, 150]);
+        fs.create_file("target/debug/app", "ignored");
+        let opts = SnapshotOptions::builder().ignore("target/").build();
+
+        let (snapshot_result, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        let counted = count_snapshot(&fs.root_path, &opts, &SilentReporter).unwrap();
+
+        assert_eq!(counted.files_processed, 3);
+        assert_eq!(counted.files_processed, snapshot_result.files_processed);
+        assert_eq!(counted.dirs_processed, snapshot["stats"]["directories"].as_u64().unwrap() as usize);
+        assert_eq!(counted.binary_files_list, vec!["assets/logo.bin".to_string()]);
+
+        // Without contents nothing is read, so nothing is binary
+        let opts = SnapshotOptions::builder().include_contents(false).build();
+        assert_eq!(count_snapshot(&fs.root_path, &opts, &SilentReporter).unwrap().binary_files_excluded, 0);
+    }
+
+    #[test]
+    fn test_run_snapshot_count_only_writes_nothing() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/index.js", "console.log('Hello');");
+
+        let sub_m = create_snapshot_matches(vec![fs.root_path.to_str().unwrap(), "--count-only", "--format", "json"])
+            .expect("Snapshot subcommand not found");
+        assert!(run_snapshot(&sub_m).is_ok());
+        assert!(!fs.path(".skeletorrc").exists());
+
+        let conflicting = crate::build_cli().try_get_matches_from([
+            "skeletor",
+            "snapshot",
+            fs.root_path.to_str().unwrap(),
+            "--count-only",
+            "--stdout",
+        ]);
+        assert!(conflicting.is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_snapshot_skips_unreadable_directories_unless_failing_on_them() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let fs = TestFileSystem::new();
+        fs.create_file("src/main.rs", "fn main() {}");
+        fs.create_file("secret/key.pem", "private");
+        let secret = fs.path("secret");
+        std::fs::set_permissions(&secret, std::fs::Permissions::from_mode(0o000)).unwrap();
+        // Permissions do not stop root, so there is nothing to test when running as root
+        if std::fs::read_dir(&secret).is_ok() {
+            std::fs::set_permissions(&secret, std::fs::Permissions::from_mode(0o755)).unwrap();
+            return;
+        }
+
+        let skipped = take_snapshot(&fs.root_path, &SnapshotOptions::default());
+        let failed = take_snapshot(&fs.root_path, &SnapshotOptions::builder().fail_on_unreadable(true).build());
+        std::fs::set_permissions(&secret, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let (result, snapshot) = skipped.unwrap();
+        assert_eq!(result.unreadable_list, vec!["secret".to_string()]);
+        assert!(snapshot["directories"].get("secret").is_none());
+        assert_eq!(snapshot["directories"]["src"]["main.rs"].as_str(), Some("fn main() {}"));
+        assert!(matches!(failed, Err(SkeletorError::PermissionDenied { .. })), "{:?}", failed.err());
+    }
+
+    #[test]
+    fn test_snapshot_records_oversized_files_empty() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/small.txt", "under the limit");
+        fs.create_file("dist/bundle.js", &"x".repeat(64));
+        fs.create_binary_file("dist/blob.bin", &[0; 64]);
+
+        let opts = SnapshotOptions::builder().max_content_size(Some(32)).build();
+        let (result, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        assert_eq!(result.oversized_files_list, vec!["dist/blob.bin".to_string(), "dist/bundle.js".to_string()]);
+        assert!(result.binary_files_list.is_empty());
+        assert_eq!(snapshot["directories"]["dist"]["bundle.js"].as_str(), Some(""));
+        assert_eq!(snapshot["directories"]["src"]["small.txt"].as_str(), Some("under the limit"));
+        assert_eq!(snapshot["stats"]["files"].as_u64(), Some(3));
+
+        let (result, snapshot) = take_snapshot(&fs.root_path, &SnapshotOptions::default()).unwrap();
+        assert!(result.oversized_files_list.is_empty());
+        assert_eq!(snapshot["directories"]["dist"]["bundle.js"].as_str(), Some("x".repeat(64).as_str()));
+    }
+
+    #[test]
+    fn test_append_reuses_contents_of_files_unchanged_since_updated() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/main.rs", "fn main() {}");
+        fs.create_file("README.md", "current readme");
+        fs.create_binary_file("logo.png", &[0, 159, 146, 150]);
+        let output = fs.path("snapshot.yml");
+        let previous = |updated: &str| {
+            format!(
+                "created: 2024-01-01T00:00:00Z\nupdated: {}\nbinary_files: [logo.png]\ndirectories:\n  README.md: recorded readme\n  logo.png: ''\n  gone.txt: deleted since\n",
+                updated
+            )
+        };
+        let opts = SnapshotOptions::builder().ignore("snapshot.yml").output_path(&output).append(true).build();
+
+        // Everything is older than `updated`: recorded contents are kept, deleted files dropped
+        fs::write(&output, previous("2999-01-01T00:00:00Z")).unwrap();
+        let (result, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        assert_eq!(snapshot["directories"]["README.md"].as_str(), Some("recorded readme"));
+        assert_eq!(snapshot["directories"]["src"]["main.rs"].as_str(), Some("fn main() {}"));
+        assert!(snapshot["directories"].get("gone.txt").is_none());
+        assert_eq!(result.binary_files_list, vec!["logo.png".to_string()]);
+        assert_eq!(snapshot["created"].as_str(), Some("2024-01-01T00:00:00Z"));
+        assert_eq!(snapshot["stats"]["files"].as_u64(), Some(3));
+
+        // Everything changed since `updated`: files are read again
+        fs::write(&output, previous("2000-01-01T00:00:00Z")).unwrap();
+        let (_, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        assert_eq!(snapshot["directories"]["README.md"].as_str(), Some("current readme"));
+
+        fs::write(&output, "directories: {}\n").unwrap();
+        assert!(matches!(take_snapshot(&fs.root_path, &opts), Err(SkeletorError::Config(_))));
+    }
+
+    #[test]
+    fn test_streaming_snapshot_matches_in_memory_snapshot() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/main.rs", "fn main() {}");
+        fs.create_file("src/lib.rs", "pub mod a;");
+        fs.create_file("README.md", "# Title");
+        fs.create_binary_file("assets/logo.png", &[0x89, 0x50, 0x4e, 0x47, 0xff]);
+        fs.create_file("target/debug/out", "ignored");
+
+        let opts = SnapshotOptions::builder().ignore("target/").build();
+        let (result, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+
+        let mut buffer = Vec::new();
+        let streaming = SnapshotOptions::builder().ignore("target/").streaming(true).build();
+        let streamed_result = snapshot_to_writer(&fs.root_path, &streaming, &mut buffer).unwrap();
+        let streamed: Value = serde_yaml::from_slice(&buffer).unwrap();
+
+        assert_eq!(streamed["directories"], snapshot["directories"]);
+        assert_eq!(streamed["stats"], snapshot["stats"]);
+        assert_eq!(streamed["checksum"], snapshot["checksum"]);
+        assert_eq!(
+            streamed["checksum"].as_str().map(str::to_string),
+            Some(directories_checksum(&streamed["directories"]).unwrap())
+        );
+        assert_eq!(streamed_result.files_processed, result.files_processed);
+        assert_eq!(streamed_result.dirs_processed, result.dirs_processed);
+        assert_eq!(streamed_result.binary_files_list, vec!["assets/logo.png".to_string()]);
+
+        let deduped = SnapshotOptions::builder().streaming(true).dedupe(true).build();
+        assert!(snapshot_to_writer(&fs.root_path, &deduped, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_sorted_snapshot_orders_entries_in_memory_and_streamed() {
+        let fs = TestFileSystem::new();
+        fs.create_file("zeta.txt", "z");
+        fs.create_file("src/main.rs", "fn main() {}");
+        fs.create_file("README.md", "hi");
+        fs.create_file("assets/logo.svg", "<svg/>");
+
+        let opts = SnapshotOptions::builder().sorted(SortOrder::DirsFirst).build();
+        let (_, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        let keys: Vec<_> = snapshot["directories"].as_mapping().unwrap().keys().cloned().collect();
+        assert_eq!(keys, ["assets", "src", "README.md", "zeta.txt"].map(|key| Value::String(key.to_string())));
+
+        let mut buffer = Vec::new();
+        let streaming = SnapshotOptions::builder().sorted(SortOrder::DirsFirst).streaming(true).build();
+        snapshot_to_writer(&fs.root_path, &streaming, &mut buffer).unwrap();
+        let streamed: Value = serde_yaml::from_slice(&buffer).unwrap();
+        let streamed_keys: Vec<_> = streamed["directories"].as_mapping().unwrap().keys().cloned().collect();
+        assert_eq!(streamed_keys, keys);
+        assert_eq!(streamed["checksum"], snapshot["checksum"]);
+    }
+
+    #[test]
+    fn test_snapshot_preserve_mtime_changes_shape_only_when_enabled() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/index.js", "console.log('Hello');");
+
+        let (_, plain) = take_snapshot(&fs.root_path, &SnapshotOptions::default()).unwrap();
+        assert_eq!(plain["directories"]["src"]["index.js"].as_str(), Some("console.log('Hello');"));
+
+        let opts = SnapshotOptions::builder().preserve_mtime(true).build();
+        let (_, timed) = take_snapshot(&fs.root_path, &opts).unwrap();
+        let entry = &timed["directories"]["src"]["index.js"];
+        assert_eq!(crate::tasks::file_content(entry), Some("console.log('Hello');"));
+        assert!(crate::tasks::file_mtime(entry).is_some());
+        assert_eq!(timed["stats"]["files"].as_u64(), Some(1));
+    }
+
+    #[test]
+    fn test_snapshot_contents_for_reads_only_matching_files() {
+        let fs = TestFileSystem::new();
+        fs.create_file("Cargo.toml", "[package]");
+        fs.create_file("crates/core/Cargo.toml", "[package] core");
+        fs.create_file("docs/guide.md", "# Guide");
+        fs.create_file("src/main.rs", "fn main() {}");
+        fs.create_binary_file("docs/logo.md", &[0xFF, 0xFE, 0x00]);
+
+        let opts = SnapshotOptions::builder()
+            .contents_for("Cargo.toml")
+            .contents_for("*.md")
+            .build();
+        let (result, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        let directories = &snapshot["directories"];
+        assert_eq!(directories["Cargo.toml"].as_str(), Some("[package]"));
+        assert_eq!(directories["crates"]["core"]["Cargo.toml"].as_str(), Some("[package] core"));
+        assert_eq!(directories["docs"]["guide.md"].as_str(), Some("# Guide"));
+        assert_eq!(directories["src"]["main.rs"].as_str(), Some(""));
+        assert_eq!(directories["docs"]["logo.md"].as_str(), Some(""));
+        assert_eq!(result.binary_files_list, vec!["docs/logo.md".to_string()]);
+
+        let opts = SnapshotOptions::builder().contents_for("[").build();
+        assert!(matches!(take_snapshot(&fs.root_path, &opts), Err(SkeletorError::Config(_))));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_snapshot_invalid_names_policy() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let fs = TestFileSystem::new();
+        fs.create_file("src/main.rs", "fn main() {}");
+        let invalid = fs.root_path.join("src").join(std::ffi::OsStr::from_bytes(b"bad\xff.txt"));
+        std::fs::write(&invalid, "latin-1 name").unwrap();
+
+        // The default refuses to corrupt the name
+        match take_snapshot(&fs.root_path, &SnapshotOptions::default()) {
+            Err(SkeletorError::InvalidFileName { path }) => assert_eq!(path, invalid),
+            other => panic!("Expected InvalidFileName error, got {:?}", other),
+        }
+
+        let opts = SnapshotOptions::builder().on_invalid_name(InvalidNamePolicy::Skip).build();
+        let (result, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        assert_eq!(result.invalid_names_skipped, vec![invalid.clone()]);
+        assert_eq!(result.files_processed, 1);
+        assert_eq!(snapshot["directories"]["src"].as_mapping().unwrap().len(), 1);
+
+        let opts = SnapshotOptions::builder().on_invalid_name(InvalidNamePolicy::Lossy).build();
+        let (_, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        assert_eq!(snapshot["directories"]["src"]["bad\u{FFFD}.txt"].as_str(), Some("latin-1 name"));
+    }
+
+    #[test]
+    fn test_snapshot_max_depth_keeps_cut_directories_empty() {
+        let fs = TestFileSystem::new();
+        fs.create_file("README.md", "# demo");
+        fs.create_file("src/main.rs", "fn main() {}");
+        fs.create_file("src/nested/deep/mod.rs", "// deep");
+
+        let opts = SnapshotOptions::builder().max_depth(1).build();
+        let (_, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        let directories = &snapshot["directories"];
+        assert_eq!(directories["src"]["main.rs"].as_str(), Some("fn main() {}"));
+        assert_eq!(directories["src"]["nested"], Value::Mapping(serde_yaml::Mapping::new()));
+        assert_eq!(snapshot["stats"]["files"].as_u64(), Some(2));
+        assert_eq!(snapshot["stats"]["directories"].as_u64(), Some(2));
+
+        let opts = SnapshotOptions::builder().max_depth(0).build();
+        let (_, snapshot) = take_snapshot(&fs.root_path, &opts).unwrap();
+        assert_eq!(snapshot["directories"]["src"], Value::Mapping(serde_yaml::Mapping::new()));
+        assert_eq!(snapshot["directories"]["README.md"].as_str(), Some("# demo"));
+    }
+
     #[test]
     fn test_run_snapshot_with_notes() {
         let fs = TestFileSystem::new();
@@ -566,6 +2053,351 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_run_snapshot_dedupe_round_trips_through_apply() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        let output_file = fs.path("deduped.yml");
+        let license = "MIT License\n\nPermission is hereby granted, free of charge.\n";
+        for i in 0..5 {
+            fs.create_file(format!("project/pkg{}/LICENSE", i), license);
+        }
+        fs.create_file("project/README.md", "# Demo project\n");
+
+        let args = vec![
+            source.to_str().unwrap(),
+            "--output",
+            output_file.to_str().unwrap(),
+            "--dedupe",
+        ];
+        let sub_m = create_snapshot_matches(args).expect("Snapshot subcommand not found");
+        assert!(run_snapshot(&sub_m).is_ok());
+
+        let yaml = fs::read_to_string(&output_file).unwrap();
+        assert_eq!(yaml.matches("Permission is hereby granted").count(), 1);
+
+        let target = fs.path("restored");
+        let args = vec![output_file.to_str().unwrap(), "-o", target.to_str().unwrap()];
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        for i in 0..5 {
+            assert_file_content(target.join(format!("pkg{}/LICENSE", i)), license);
+        }
+        assert_file_content(target.join("README.md"), "# Demo project\n");
+    }
+
+    #[test]
+    fn test_run_snapshot_brace_names_round_trip_through_apply() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        let output_file = fs.path("braces.yml");
+        fs.create_file("project/src/{{cookiecutter.slug}}/a.txt", "slug\n");
+        fs.create_file("project/{a,b}.txt", "list\n");
+        fs.create_file("project/file_{1..2}.txt", "range\n");
+
+        let args = vec![source.to_str().unwrap(), "--output", output_file.to_str().unwrap()];
+        let sub_m = create_snapshot_matches(args).expect("Snapshot subcommand not found");
+        assert!(run_snapshot(&sub_m).is_ok());
+
+        let target = fs.path("restored");
+        let args = vec![output_file.to_str().unwrap(), "-o", target.to_str().unwrap()];
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        assert_file_content(target.join("src/{{cookiecutter.slug}}/a.txt"), "slug\n");
+        assert_file_content(target.join("{a,b}.txt"), "list\n");
+        assert_file_content(target.join("file_{1..2}.txt"), "range\n");
+        assert!(!target.join("a.txt").exists() && !target.join("file_1.txt").exists());
+    }
+
+    #[test]
+    fn test_run_snapshot_lone_source_file_round_trips_through_apply() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        let output_file = fs.path("docs.yml");
+        // Snapshots as `docs: { source: "body\n" }`, which is not a file reference
+        fs.create_file("project/docs/source", "body\n");
+
+        let args = vec![source.to_str().unwrap(), "--output", output_file.to_str().unwrap()];
+        let sub_m = create_snapshot_matches(args).expect("Snapshot subcommand not found");
+        assert!(run_snapshot(&sub_m).is_ok());
+
+        let target = fs.path("restored");
+        let args = vec![output_file.to_str().unwrap(), "-o", target.to_str().unwrap()];
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        assert_file_content(target.join("docs/source"), "body\n");
+    }
+
+    #[test]
+    fn test_run_snapshot_externalized_contents_round_trip_through_apply() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        let output_file = fs.path("templates/big.yml");
+        fs.create_dir("templates");
+        let generated = "{\"data\": [1, 2, 3]}\n".repeat(100);
+        fs.create_file("project/gen/schema.json", &generated);
+        fs.create_file("project/README.md", "# Demo\n");
+
+        let args = vec![
+            source.to_str().unwrap(),
+            "--output",
+            output_file.to_str().unwrap(),
+            "--externalize-threshold",
+            "1024",
+        ];
+        let sub_m = create_snapshot_matches(args).expect("Snapshot subcommand not found");
+        assert!(run_snapshot(&sub_m).is_ok());
+
+        let yaml = fs::read_to_string(&output_file).unwrap();
+        assert!(!yaml.contains("\"data\""));
+        assert!(yaml.contains("schema.json: !file_ref .skeletor_assets/"));
+        assert!(yaml.contains("README.md: |"));
+        assert!(fs.path("templates/.skeletor_assets").is_dir());
+
+        let target = fs.path("restored");
+        let args = vec![output_file.to_str().unwrap(), "-o", target.to_str().unwrap()];
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        assert_file_content(target.join("gen/schema.json"), &generated);
+        assert_file_content(target.join("README.md"), "# Demo\n");
+    }
+
+    #[test]
+    fn test_run_snapshot_records_source_children_at_top_level() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("foo");
+        let output_file = fs.path("foo.yml");
+        fs.create_file("foo/src/main.rs", "fn main() {}\n");
+        fs.create_file("foo/README.md", "# Foo\n");
+
+        let args = vec![source.to_str().unwrap(), "--output", output_file.to_str().unwrap()];
+        let sub_m = create_snapshot_matches(args).expect("Snapshot subcommand not found");
+        assert!(run_snapshot(&sub_m).is_ok());
+
+        // The source's own name is not a key, so the snapshot applies straight into a target
+        let snapshot: Value = serde_yaml::from_str(&fs::read_to_string(&output_file).unwrap()).unwrap();
+        let directories = snapshot["directories"].as_mapping().unwrap();
+        assert!(directories.get("foo").is_none());
+        assert!(directories.get("src").is_some() && directories.get("README.md").is_some());
+
+        let target = fs.path("fresh");
+        let args = vec![output_file.to_str().unwrap(), "-o", target.to_str().unwrap()];
+        let sub_m = create_apply_matches(args).expect("Apply subcommand not found");
+        assert_command_succeeds(|| crate::apply::run_apply(&sub_m));
+        assert_file_content(target.join("src/main.rs"), "fn main() {}\n");
+        assert_file_content(target.join("README.md"), "# Foo\n");
+        assert!(!target.join("foo").exists());
+    }
+
+    #[test]
+    fn test_run_snapshot_strict_fails_on_warnings() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        fs.create_file("project/main.rs", "fn main() {}");
+        let output_file = fs.path("out.yml");
+
+        // A missing config to inherit from is a warning, not an error
+        let mut args = vec![
+            source.to_str().unwrap(),
+            "--output",
+            output_file.to_str().unwrap(),
+            "--inherit-config",
+        ];
+        let sub_m = create_snapshot_matches(args.clone()).expect("Snapshot subcommand not found");
+        assert!(run_snapshot(&sub_m).is_ok());
+
+        args.push("--strict");
+        let sub_m = create_snapshot_matches(args).expect("Snapshot subcommand not found");
+        match run_snapshot(&sub_m) {
+            Err(SkeletorError::StrictWarnings { count }) => assert_eq!(count, 1),
+            other => panic!("Expected StrictWarnings error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_run_snapshot_with_inherit_config() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        let output_file = fs.path("output.yaml");
+
+        fs.create_file("project/src/index.js", "console.log('Hello');");
+        fs.create_file("project/debug.log", "noise");
+        fs.create_file(
+            "project/.skeletorrc",
+            r#"
+name: "demo-template"
+notes: "Inherited note"
+ignore_patterns:
+  - "*.log"
+directories: {}
+"#,
+        );
+
+        let args = vec![
+            source.to_str().unwrap(),
+            "--output",
+            output_file.to_str().unwrap(),
+            "--inherit-config",
+        ];
+        if let Some(sub_m) = create_snapshot_matches(args) {
+            assert!(run_snapshot(&sub_m).is_ok());
+        } else {
+            panic!("Snapshot subcommand not found");
+        }
+
+        let snapshot: Value = crate::utils::read_yaml_file(&output_file).unwrap();
+        assert_eq!(snapshot.get("name").and_then(Value::as_str), Some("demo-template"));
+        assert_eq!(
+            snapshot.get("notes").and_then(|n| n.get("description")).and_then(Value::as_str),
+            Some("Inherited note")
+        );
+        let directories = snapshot.get("directories").unwrap();
+        assert!(directories.get("src").is_some());
+        assert!(directories.get("debug.log").is_none());
+    }
+
+    #[test]
+    fn test_run_snapshot_cli_note_overrides_inherited_note() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        let output_file = fs.path("output.yaml");
+
+        fs.create_file("project/index.js", "console.log('Hello');");
+        fs.create_file("project/.skeletorrc", "notes: \"Inherited note\"\ndirectories: {}\n");
+
+        let args = vec![
+            source.to_str().unwrap(),
+            "--output",
+            output_file.to_str().unwrap(),
+            "--inherit-config",
+            "--note",
+            "CLI note",
+        ];
+        if let Some(sub_m) = create_snapshot_matches(args) {
+            assert!(run_snapshot(&sub_m).is_ok());
+        } else {
+            panic!("Snapshot subcommand not found");
+        }
+
+        let snapshot: Value = crate::utils::read_yaml_file(&output_file).unwrap();
+        assert_eq!(
+            snapshot.get("notes").and_then(|n| n.get("description")).and_then(Value::as_str),
+            Some("CLI note")
+        );
+    }
+
+    #[test]
+    fn test_run_snapshot_with_structured_notes_preserved() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        let output_file = fs.path("output.yaml");
+        fs.create_file("project/index.js", "console.log('Hello');");
+
+        let args = vec![
+            source.to_str().unwrap(),
+            "--output",
+            output_file.to_str().unwrap(),
+            "--note",
+            "author=Jane",
+            "--note",
+            "ticket=ABC-123",
+        ];
+        if let Some(sub_m) = create_snapshot_matches(args) {
+            assert!(run_snapshot(&sub_m).is_ok());
+        } else {
+            panic!("Snapshot subcommand not found");
+        }
+
+        // Re-snapshot with a different note; earlier keys are preserved
+        let args = vec![
+            source.to_str().unwrap(),
+            "--output",
+            output_file.to_str().unwrap(),
+            "--note",
+            "ticket=ABC-456",
+        ];
+        if let Some(sub_m) = create_snapshot_matches(args) {
+            assert!(run_snapshot(&sub_m).is_ok());
+        } else {
+            panic!("Snapshot subcommand not found");
+        }
+
+        let snapshot: Value = crate::utils::read_yaml_file(&output_file).unwrap();
+        let notes = snapshot.get("notes").unwrap();
+        assert_eq!(notes.get("author").and_then(Value::as_str), Some("Jane"));
+        assert_eq!(notes.get("ticket").and_then(Value::as_str), Some("ABC-456"));
+    }
+
+    #[test]
+    fn test_run_snapshot_as_tree_to_file() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        let output_file = fs.path("tree.txt");
+        fs.create_file("project/src/main.rs", "fn main() {}");
+        fs.create_file("project/debug.log", "noise");
+
+        let args = vec![
+            source.to_str().unwrap(),
+            "--as-tree",
+            "-i",
+            "*.log",
+            "-o",
+            output_file.to_str().unwrap(),
+        ];
+        if let Some(sub_m) = create_snapshot_matches(args) {
+            assert!(run_snapshot(&sub_m).is_ok());
+        } else {
+            panic!("Snapshot subcommand not found");
+        }
+
+        let tree = fs::read_to_string(&output_file).unwrap();
+        assert!(tree.starts_with("project\n"));
+        assert!(tree.contains("└── src/"));
+        assert!(tree.contains("main.rs"));
+        assert!(!tree.contains("debug.log"));
+    }
+
+    #[test]
+    fn test_run_snapshot_as_tree_defaults_to_stdout() {
+        let fs = TestFileSystem::new();
+        fs.create_file("src/main.rs", "fn main() {}");
+
+        let args = vec![fs.root_path.to_str().unwrap(), "--as-tree"];
+        if let Some(sub_m) = create_snapshot_matches(args) {
+            assert!(run_snapshot(&sub_m).is_ok());
+            assert!(!fs.root_path.join(".skeletorrc").exists());
+        } else {
+            panic!("Snapshot subcommand not found");
+        }
+    }
+
+    #[test]
+    fn test_run_snapshot_with_json_format() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("project");
+        let output_file = fs.path("output.yaml");
+        fs.create_file("project/index.js", "console.log('Hello');");
+
+        for extra in [None, Some("--dry-run")] {
+            let mut args = vec![
+                source.to_str().unwrap(),
+                "--output",
+                output_file.to_str().unwrap(),
+                "--format",
+                "json",
+            ];
+            args.extend(extra);
+            if let Some(sub_m) = create_snapshot_matches(args) {
+                assert_eq!(SnapshotConfig::from_matches(&sub_m).format, OutputFormat::Json);
+                assert!(run_snapshot(&sub_m).is_ok());
+            } else {
+                panic!("Snapshot subcommand not found");
+            }
+        }
+
+        assert!(output_file.exists());
+    }
+
     #[test]
     fn test_run_snapshot_with_existing_output_file() {
         let fs = TestFileSystem::new();
@@ -604,6 +2436,30 @@ src:
         }
     }
 
+    #[test]
+    fn test_existing_created_is_read_from_any_format() {
+        let fs = TestFileSystem::new();
+        let created = "2020-01-01T00:00:00Z";
+        let json = fs.create_file("snap.json", "{\n  \"created\": \"2020-01-01T00:00:00Z\",\n  \"directories\": {}\n}\n");
+        let toml = fs.create_file("snap.toml", "created = \"2020-01-01T00:00:00Z\"\n\n[directories]\n");
+        let malformed = fs.create_file(
+            "snap.yml",
+            "created: 2020-01-01T00:00:00Z\nupdated: [unclosed\ndirectories:\n  created: not a time\n",
+        );
+
+        for path in [&json, &toml, &malformed] {
+            assert_eq!(existing_created(path).as_deref(), Some(created), "{}", path.display());
+        }
+        let untimed = fs.create_file("untimed.yml", "directories:\n  created: {}\n");
+        assert_eq!(existing_created(&untimed), None);
+
+        fs.create_file("src/index.js", "console.log('Hello');");
+        let opts = SnapshotOptions::builder().output_path(&json).build();
+        let (_, snapshot) = take_snapshot(&fs.path("src"), &opts).unwrap();
+        assert_eq!(snapshot["created"].as_str(), Some(created));
+        assert_ne!(snapshot["updated"].as_str(), Some(created));
+    }
+
     #[test]
     fn test_run_snapshot_with_final_println() {
         let fs = TestFileSystem::new();
@@ -745,6 +2601,6 @@ temp/**
             .get_many::<String>("ignore_file")
             .map(|vals| vals.map(|v| v.to_string()));
 
-        collect_ignore_spec(root, ignore_values, ignore_files, reporter)
+        collect_ignore_spec(root, ignore_values, ignore_files, false, false, false, reporter)
     }
 }
@@ -0,0 +1,43 @@
+use crate::apply::extract_binary_files_from_yaml;
+use crate::errors::SkeletorError;
+use crate::tasks::PreviousSnapshot;
+use crate::utils::read_config_file;
+use serde_yaml::Value;
+use std::path::Path;
+use std::time::SystemTime;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// Loads the snapshot at `path` for `--append` to update, returning `None` when there
+/// is none yet, so the first run takes a full snapshot.
+///
+/// Fails when the file has no `directories` or no RFC 3339 `updated` timestamp to
+/// compare modification times against.
+pub fn load_previous_snapshot(path: &Path) -> Result<Option<PreviousSnapshot>, SkeletorError> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let doc = read_config_file(path)?;
+    let directories = doc
+        .get("directories")
+        .filter(|directories| directories.is_mapping())
+        .cloned()
+        .ok_or_else(|| SkeletorError::Config(format!("{}: no directories to append to", path.display())))?;
+    let updated = doc
+        .get("updated")
+        .and_then(Value::as_str)
+        .and_then(|updated| OffsetDateTime::parse(updated, &Rfc3339).ok())
+        .ok_or_else(|| {
+            SkeletorError::Config(format!(
+                "{}: no RFC 3339 `updated` timestamp to tell which files changed; take a full snapshot instead",
+                path.display()
+            ))
+        })?;
+
+    Ok(Some(PreviousSnapshot {
+        directories,
+        binary_files: extract_binary_files_from_yaml(&doc).into_iter().collect(),
+        updated: SystemTime::from(updated),
+    }))
+}
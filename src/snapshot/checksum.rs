@@ -0,0 +1,62 @@
+use crate::errors::SkeletorError;
+use serde::Serialize;
+use serde_yaml::{Mapping, Value};
+use sha2::{Digest, Sha256};
+
+/// Prefix naming the algorithm, so the format can change without ambiguity
+const ALGORITHM: &str = "sha256";
+
+/// SHA-256 of a `directories` tree, as `sha256:<hex>`.
+///
+/// Keys are sorted recursively before hashing, so the checksum depends only on
+/// the entries and their contents, not on mapping order or on the snapshot's
+/// timestamps and other metadata.
+pub fn directories_checksum(directories: &Value) -> Result<String, SkeletorError> {
+    sorted_checksum(&canonicalize(directories))
+}
+
+/// Checksum of a tree that already serializes with its keys in canonical order,
+/// hashed as it is serialized rather than from a buffered copy.
+pub fn sorted_checksum(directories: &impl Serialize) -> Result<String, SkeletorError> {
+    let mut hasher = Sha256::new();
+    serde_yaml::to_writer(&mut hasher, directories).map_err(|e| SkeletorError::Config(e.to_string()))?;
+    let hex: String = hasher.finalize().iter().map(|byte| format!("{:02x}", byte)).collect();
+    Ok(format!("{}:{}", ALGORITHM, hex))
+}
+
+/// Orders mapping keys as [`directories_checksum`] hashes them
+pub fn canonical_key_order(key: &Value) -> String {
+    serde_yaml::to_string(key).unwrap_or_default()
+}
+
+fn canonicalize(value: &Value) -> Value {
+    match value {
+        Value::Mapping(mapping) => {
+            let mut entries: Vec<(&Value, &Value)> = mapping.iter().collect();
+            entries.sort_by_cached_key(|(key, _)| canonical_key_order(key));
+            let mut sorted = Mapping::new();
+            for (key, child) in entries {
+                sorted.insert(key.clone(), canonicalize(child));
+            }
+            Value::Mapping(sorted)
+        }
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum_ignores_key_order_but_not_content() {
+        let a: Value = serde_yaml::from_str("src:\n  main.rs: fn main() {}\n  lib.rs: ''\nREADME.md: hi\n").unwrap();
+        let b: Value = serde_yaml::from_str("README.md: hi\nsrc:\n  lib.rs: ''\n  main.rs: fn main() {}\n").unwrap();
+        let c: Value = serde_yaml::from_str("README.md: hi!\nsrc:\n  lib.rs: ''\n  main.rs: fn main() {}\n").unwrap();
+
+        let checksum = directories_checksum(&a).unwrap();
+        assert!(checksum.starts_with("sha256:") && checksum.len() == "sha256:".len() + 64);
+        assert_eq!(directories_checksum(&b).unwrap(), checksum);
+        assert_ne!(directories_checksum(&c).unwrap(), checksum);
+    }
+}
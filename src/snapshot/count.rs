@@ -0,0 +1,40 @@
+use crate::errors::SkeletorError;
+use crate::tasks::{DirectoryWalker, WalkEntryKind};
+use std::path::Path;
+
+/// The totals a snapshot of a tree would record in its `stats`
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TreeCounts {
+    pub files: usize,
+    pub dirs: usize,
+    pub binary_files: Vec<String>,
+}
+
+/// Walks `base` the way `walker` walks it for a snapshot and adds what it finds to `counts`,
+/// without building the tree or keeping any file contents. Symlinks are not counted, as
+/// [`crate::tasks::compute_stats`] does not count them.
+pub fn count_tree(
+    walker: &mut DirectoryWalker<'_, '_>,
+    base: &Path,
+    depth: usize,
+    counts: &mut TreeCounts,
+) -> Result<(), SkeletorError> {
+    walker.enter(base)?;
+    for entry in walker.list(base, depth)? {
+        match entry.kind {
+            WalkEntryKind::Symlink(_) => {}
+            WalkEntryKind::CutoffDir => counts.dirs += 1,
+            WalkEntryKind::Dir => {
+                counts.dirs += 1;
+                count_tree(walker, &entry.path, depth + 1, counts)?;
+            }
+            WalkEntryKind::File => {
+                if walker.inspect_file(&entry, &mut counts.binary_files) {
+                    counts.files += 1;
+                }
+            }
+        }
+    }
+    walker.leave();
+    Ok(())
+}
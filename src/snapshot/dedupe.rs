@@ -0,0 +1,154 @@
+use crate::errors::SkeletorError;
+use serde_yaml::{Mapping, Value};
+use std::collections::HashMap;
+
+/// Contents shorter than this are cheaper to repeat than to alias
+const MIN_DEDUPE_LEN: usize = 16;
+const SENTINEL: &str = "__skeletor_dedupe_";
+
+/// Serializes a snapshot, storing each repeated file content once under a
+/// top-level `anchors:` block and referencing it from every copy with a YAML alias.
+///
+/// serde_yaml cannot emit anchors, so shared contents are serialized as unique
+/// sentinel scalars and rewritten to `&cN`/`*cN` afterwards. Loading the result
+/// resolves the aliases, so `apply` sees the same `directories` as before.
+pub fn to_deduped_yaml(snapshot: &Value) -> Result<String, SkeletorError> {
+    let to_yaml = |value: &Value| serde_yaml::to_string(value).map_err(|e| SkeletorError::Config(e.to_string()));
+
+    let Some(directories) = snapshot.get("directories") else {
+        return to_yaml(snapshot);
+    };
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    let mut clashes = false;
+    count_contents(directories, &mut counts, &mut clashes);
+    if clashes {
+        // A name or content already looks like a sentinel; fall back to plain output
+        return to_yaml(snapshot);
+    }
+
+    // Ids follow document order so the output is stable across runs
+    let mut ids: HashMap<&str, usize> = HashMap::new();
+    let mut anchors = Mapping::new();
+    let directories = replace_duplicates(directories, &counts, &mut ids, &mut anchors);
+    if anchors.is_empty() {
+        return to_yaml(snapshot);
+    }
+
+    // Anchors must precede their aliases, so the block goes right before `directories`
+    let mut document = Mapping::new();
+    for (key, value) in snapshot.as_mapping().into_iter().flatten() {
+        if key.as_str() == Some("directories") {
+            document.insert(Value::String("anchors".to_string()), Value::Mapping(anchors.clone()));
+            document.insert(key.clone(), directories.clone());
+        } else {
+            document.insert(key.clone(), value.clone());
+        }
+    }
+
+    let mut yaml = to_yaml(&Value::Mapping(document))?;
+    for id in 0..anchors.len() {
+        yaml = yaml
+            .replace(&format!("{}anchor_{}__: ", SENTINEL, id), &format!("c{}: &c{} ", id, id))
+            .replace(&format!(": {}alias_{}__\n", SENTINEL, id), &format!(": *c{}\n", id));
+    }
+    Ok(yaml)
+}
+
+fn count_contents<'a>(node: &'a Value, counts: &mut HashMap<&'a str, usize>, clashes: &mut bool) {
+    for (key, value) in node.as_mapping().into_iter().flatten() {
+        if key.as_str().is_some_and(|name| name.contains(SENTINEL)) {
+            *clashes = true;
+        }
+        match value {
+            Value::Mapping(_) => count_contents(value, counts, clashes),
+            Value::String(content) => {
+                if content.contains(SENTINEL) {
+                    *clashes = true;
+                }
+                if content.len() >= MIN_DEDUPE_LEN {
+                    *counts.entry(content.as_str()).or_default() += 1;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn replace_duplicates<'a>(
+    node: &'a Value,
+    counts: &HashMap<&'a str, usize>,
+    ids: &mut HashMap<&'a str, usize>,
+    anchors: &mut Mapping,
+) -> Value {
+    match node {
+        Value::Mapping(mapping) => Value::Mapping(
+            mapping
+                .iter()
+                .map(|(key, value)| (key.clone(), replace_duplicates(value, counts, ids, anchors)))
+                .collect(),
+        ),
+        Value::String(content) if counts.get(content.as_str()).is_some_and(|n| *n > 1) => {
+            let next_id = ids.len();
+            let id = *ids.entry(content.as_str()).or_insert_with(|| {
+                anchors.insert(
+                    Value::String(format!("{}anchor_{}__", SENTINEL, next_id)),
+                    Value::String(content.clone()),
+                );
+                next_id
+            });
+            Value::String(format!("{}alias_{}__", SENTINEL, id))
+        }
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dedupe_round_trips_and_shrinks() {
+        let license = "Permission is hereby granted, free of charge, to any person obtaining a copy\n".repeat(20);
+        let mut packages = Mapping::new();
+        for i in 0..50 {
+            let mut package = Mapping::new();
+            package.insert("LICENSE".into(), Value::String(license.clone()));
+            package.insert("index.js".into(), Value::String(format!("module.exports = {};\n", i)));
+            packages.insert(Value::String(format!("pkg{}", i)), Value::Mapping(package));
+        }
+        let mut snapshot = Mapping::new();
+        snapshot.insert("created".into(), "2024-01-01T00:00:00Z".into());
+        snapshot.insert("directories".into(), Value::Mapping(packages));
+        let snapshot = Value::Mapping(snapshot);
+
+        let plain = serde_yaml::to_string(&snapshot).unwrap();
+        let deduped = to_deduped_yaml(&snapshot).unwrap();
+
+        // 50 copies of a ~1.5 KiB license collapse to one
+        assert!(deduped.len() * 10 < plain.len(), "{} vs {}", deduped.len(), plain.len());
+        assert!(deduped.contains("c0: &c0 "));
+        assert!(deduped.contains("LICENSE: *c0\n"));
+
+        let reloaded: Value = serde_yaml::from_str(&deduped).unwrap();
+        assert_eq!(reloaded["directories"], snapshot["directories"]);
+        assert_eq!(reloaded["created"], snapshot["created"]);
+    }
+
+    #[test]
+    fn test_dedupe_leaves_unique_and_short_contents() {
+        let snapshot: Value = serde_yaml::from_str(
+            r#"
+            directories:
+              a.txt: "same"
+              b.txt: "same"
+              c.txt: "unique content that is long enough"
+            "#,
+        )
+        .unwrap();
+
+        let deduped = to_deduped_yaml(&snapshot).unwrap();
+        assert!(!deduped.contains("anchors"));
+        assert_eq!(deduped, serde_yaml::to_string(&snapshot).unwrap());
+    }
+}
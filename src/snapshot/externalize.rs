@@ -0,0 +1,98 @@
+use crate::errors::SkeletorError;
+use crate::tasks::file_ref_node;
+use serde_yaml::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+/// Directory, next to the snapshot file, that holds externalized file contents
+pub const ASSETS_DIR: &str = ".skeletor_assets";
+
+/// Moves every file content longer than `threshold` bytes into a sidecar under
+/// [`ASSETS_DIR`] in `snapshot_dir`, replacing it with a `!file_ref` node.
+///
+/// Sidecars are named by a hash of their content plus the file's extension, so
+/// identical contents share one sidecar. Returns how many entries were externalized.
+pub fn externalize_contents(
+    directories: &mut Value,
+    threshold: u64,
+    snapshot_dir: &Path,
+) -> Result<usize, SkeletorError> {
+    let mut externalized = 0;
+    let Some(mapping) = directories.as_mapping_mut() else {
+        return Ok(0);
+    };
+
+    for (key, value) in mapping.iter_mut() {
+        match value {
+            Value::Mapping(_) => externalized += externalize_contents(value, threshold, snapshot_dir)?,
+            Value::String(content) if content.len() as u64 > threshold => {
+                let reference = sidecar_name(key.as_str().unwrap_or_default(), content);
+                let path = snapshot_dir.join(&reference);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| SkeletorError::from_io_with_context(e, parent.to_path_buf()))?;
+                }
+                crate::utils::write_string_to_file(&path, content)?;
+                *value = file_ref_node(&reference);
+                externalized += 1;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(externalized)
+}
+
+/// Names a sidecar after the SHA-256 of its content, so the name is the same from one
+/// build to the next and identical contents share a file
+fn sidecar_name(file_name: &str, content: &str) -> String {
+    let digest: String = Sha256::digest(content.as_bytes())
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    format!("{}/{}{}", ASSETS_DIR, digest, extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::{file_ref_path, resolve_file_refs};
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_sidecar_name_is_a_content_digest() {
+        // First 8 bytes of SHA-256("hello"), fixed whatever the toolchain
+        assert_eq!(sidecar_name("greeting.txt", "hello"), format!("{}/2cf24dba5fb0a30e.txt", ASSETS_DIR));
+        assert_eq!(sidecar_name("other.txt", "hello"), sidecar_name("greeting.txt", "hello"));
+        assert_ne!(sidecar_name("greeting.txt", "hello!"), sidecar_name("greeting.txt", "hello"));
+    }
+
+    #[test]
+    fn test_externalize_and_resolve_round_trip() {
+        let fs = TestFileSystem::new();
+        let big = "x".repeat(64);
+        let mut directories: Value = serde_yaml::from_str(&format!(
+            "gen:\n  a.json: {big}\n  b.json: {big}\nsmall.txt: tiny\n"
+        ))
+        .unwrap();
+        let original = directories.clone();
+
+        let count = externalize_contents(&mut directories, 32, &fs.root_path).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(directories["small.txt"].as_str(), Some("tiny"));
+
+        // Identical contents share one sidecar
+        let reference = file_ref_path(&directories["gen"]["a.json"]).unwrap();
+        assert!(reference.starts_with(".skeletor_assets/") && reference.ends_with(".json"));
+        assert_eq!(file_ref_path(&directories["gen"]["b.json"]), Some(reference));
+        assert_eq!(std::fs::read_to_string(fs.root_path.join(reference)).unwrap(), big);
+
+        assert_eq!(resolve_file_refs(&directories, &fs.root_path).unwrap(), original);
+    }
+}
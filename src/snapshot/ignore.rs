@@ -1,9 +1,13 @@
 use crate::errors::SkeletorError;
-use crate::output::{DefaultReporter, Reporter};
+use crate::output::Reporter;
 use crate::utils::read_file_to_string;
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::WalkBuilder;
 use std::path::{Path, PathBuf};
 
+/// Ignore file picked up from the snapshot source root without being passed
+pub const DEFAULT_IGNORE_FILE: &str = ".skeletorignore";
+
 #[derive(Debug)]
 pub struct IgnoreSpec {
     pub matcher: Option<Gitignore>,
@@ -14,7 +18,7 @@ fn add_ignore_line(
     builder: &mut GitignoreBuilder,
     source: Option<PathBuf>,
     line: &str,
-    reporter: &DefaultReporter,
+    reporter: &dyn Reporter,
     patterns: &mut Vec<String>,
 ) -> Result<(), SkeletorError> {
     let trimmed = line.trim();
@@ -49,7 +53,7 @@ fn add_ignore_line(
 fn add_ignore_file(
     builder: &mut GitignoreBuilder,
     path: &Path,
-    reporter: &DefaultReporter,
+    reporter: &dyn Reporter,
     patterns: &mut Vec<String>,
 ) -> Result<(), SkeletorError> {
     if !path.exists() || !path.is_file() {
@@ -72,15 +76,108 @@ fn add_ignore_file(
     Ok(())
 }
 
+/// Rewrites a line from `<dir>/.gitignore` so it matches relative to the snapshot root,
+/// following git's rules: patterns containing a `/` are anchored to `dir`, others match
+/// at any depth beneath it.
+fn anchor_gitignore_line(dir: &str, line: &str) -> String {
+    let trimmed = line.trim();
+    if dir.is_empty() || trimmed.is_empty() || trimmed.starts_with('#') {
+        return trimmed.to_string();
+    }
+
+    let (negation, pattern) = match trimmed.strip_prefix('!') {
+        Some(rest) => ("!", rest),
+        None => ("", trimmed),
+    };
+    let escaped_dir: String = dir
+        .chars()
+        .flat_map(|c| {
+            let escape = matches!(c, '*' | '?' | '[' | ']' | '{' | '}' | '\\');
+            escape.then_some('\\').into_iter().chain(std::iter::once(c))
+        })
+        .collect();
+
+    if pattern.trim_end_matches('/').contains('/') {
+        format!("{}/{}/{}", negation, escaped_dir, pattern.trim_start_matches('/'))
+    } else {
+        format!("{}/{}/**/{}", negation, escaped_dir, pattern)
+    }
+}
+
+/// Layers every `.gitignore` under `root` (outside already-ignored directories),
+/// parents before children so deeper files take precedence as in git.
+fn add_nested_gitignores(
+    builder: &mut GitignoreBuilder,
+    root: &Path,
+    reporter: &dyn Reporter,
+    patterns: &mut Vec<String>,
+) -> Result<(), SkeletorError> {
+    let walker = WalkBuilder::new(root)
+        .hidden(false)
+        .parents(false)
+        .git_global(false)
+        .git_exclude(false)
+        .require_git(false)
+        .sort_by_file_path(|a, b| a.cmp(b))
+        .build();
+
+    let mut gitignores: Vec<PathBuf> = walker
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_name() == ".gitignore" && entry.path().is_file())
+        .map(|entry| entry.into_path())
+        .collect();
+    gitignores.sort_by_key(|path| path.components().count());
+
+    for path in gitignores {
+        let dir = path
+            .parent()
+            .and_then(|parent| parent.strip_prefix(root).ok())
+            .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        let content = read_file_to_string(&path)?;
+        for line in content.lines() {
+            add_ignore_line(
+                builder,
+                Some(path.clone()),
+                &anchor_gitignore_line(&dir, line),
+                reporter,
+                patterns,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Builds the snapshot ignore matcher. With `use_gitignore`, nested `.gitignore`
+/// files are layered first, then (with `default_ignore`) `<root>/.skeletorignore`,
+/// so explicit values and ignore files can refine them.
+/// With `ignore_case`, every pattern, from whichever source, matches regardless of case.
 pub fn collect_ignore_spec(
     root: &Path,
     ignore_values: Option<impl Iterator<Item = String>>,
     ignore_files: Option<impl Iterator<Item = String>>,
-    reporter: &DefaultReporter,
+    use_gitignore: bool,
+    default_ignore: bool,
+    ignore_case: bool,
+    reporter: &dyn Reporter,
 ) -> Result<IgnoreSpec, SkeletorError> {
     let mut builder = GitignoreBuilder::new(root);
+    // Only affects lines added afterwards, so it is set before any pattern
+    builder
+        .case_insensitive(ignore_case)
+        .map_err(|e| SkeletorError::InvalidIgnorePattern { pattern: e.to_string() })?;
     let mut patterns = Vec::new();
 
+    if use_gitignore {
+        add_nested_gitignores(&mut builder, root, reporter, &mut patterns)?;
+    }
+
+    let default_ignore_file = root.join(DEFAULT_IGNORE_FILE);
+    if default_ignore && default_ignore_file.is_file() {
+        add_ignore_file(&mut builder, &default_ignore_file, reporter, &mut patterns)?;
+    }
+
     if let Some(vals) = ignore_values {
         for val in vals {
             let candidate = Path::new(&val);
@@ -117,3 +214,109 @@ pub fn collect_ignore_spec(
         patterns,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::SilentReporter;
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_anchor_gitignore_line() {
+        assert_eq!(anchor_gitignore_line("", "*.log"), "*.log");
+        assert_eq!(anchor_gitignore_line("sub", "*.tmp"), "/sub/**/*.tmp");
+        assert_eq!(anchor_gitignore_line("sub", "/build"), "/sub/build");
+        assert_eq!(anchor_gitignore_line("sub", "docs/out/"), "/sub/docs/out/");
+        assert_eq!(anchor_gitignore_line("sub", "!keep.tmp"), "!/sub/**/keep.tmp");
+        assert_eq!(anchor_gitignore_line("sub", "# comment"), "# comment");
+    }
+
+    #[test]
+    fn test_nested_gitignores_are_layered() {
+        let fs = TestFileSystem::new();
+        fs.create_file(".gitignore", "*.log\n");
+        fs.create_file("sub/.gitignore", "*.tmp\n/build\n!keep.log\n");
+        let root = &fs.root_path;
+
+        let spec = collect_ignore_spec(
+            root,
+            Some(vec!["*.bak".to_string()].into_iter()),
+            None::<std::iter::Empty<String>>,
+            true,
+            false,
+            false,
+            &SilentReporter,
+        )
+        .unwrap();
+        let matcher = spec.matcher.unwrap();
+        let ignored = |path: &str, is_dir: bool| {
+            matcher
+                .matched_path_or_any_parents(Path::new(path), is_dir)
+                .is_ignore()
+        };
+
+        assert!(ignored("debug.log", false));
+        assert!(ignored("sub/a.tmp", false));
+        assert!(ignored("sub/deep/a.tmp", false));
+        assert!(!ignored("a.tmp", false));
+        assert!(ignored("sub/build", true));
+        assert!(!ignored("sub/deep/build", true));
+        assert!(!ignored("sub/keep.log", false));
+        assert!(ignored("notes.bak", false));
+    }
+
+    #[test]
+    fn test_ignore_case_applies_to_values_and_files() {
+        let fs = TestFileSystem::new();
+        let ignore_file = fs.create_file("patterns.txt", "*.TMP\n");
+
+        let ignored = |ignore_case: bool, path: &str| {
+            let spec = collect_ignore_spec(
+                &fs.root_path,
+                Some(vec!["*.LOG".to_string()].into_iter()),
+                Some(vec![ignore_file.to_string_lossy().into_owned()].into_iter()),
+                false,
+                false,
+                ignore_case,
+                &SilentReporter,
+            )
+            .unwrap();
+            spec.matcher.unwrap().matched(Path::new(path), false).is_ignore()
+        };
+
+        assert!(!ignored(false, "debug.log"));
+        assert!(!ignored(false, "scratch.tmp"));
+        assert!(ignored(false, "DEBUG.LOG"));
+        assert!(ignored(true, "debug.log"));
+        assert!(ignored(true, "scratch.tmp"));
+        assert!(!ignored(true, "debug.txt"));
+    }
+
+    #[test]
+    fn test_default_ignore_file_is_loaded_under_explicit_patterns() {
+        let fs = TestFileSystem::new();
+        fs.create_file(DEFAULT_IGNORE_FILE, "*.log\ndist/\n");
+
+        let spec = |values: Vec<&str>, default_ignore: bool| {
+            collect_ignore_spec(
+                &fs.root_path,
+                Some(values.into_iter().map(str::to_string)),
+                None::<std::iter::Empty<String>>,
+                false,
+                default_ignore,
+                false,
+                &SilentReporter,
+            )
+            .unwrap()
+        };
+
+        let loaded = spec(vec!["!keep.log"], true);
+        assert_eq!(loaded.patterns, vec!["*.log", "dist/", "!keep.log"]);
+        let matcher = loaded.matcher.unwrap();
+        assert!(matcher.matched(Path::new("debug.log"), false).is_ignore());
+        assert!(matcher.matched(Path::new("dist"), true).is_ignore());
+        assert!(!matcher.matched(Path::new("keep.log"), false).is_ignore());
+
+        assert!(spec(vec![], false).matcher.is_none());
+    }
+}
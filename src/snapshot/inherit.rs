@@ -0,0 +1,42 @@
+use super::notes::notes_from_value;
+use crate::errors::SkeletorError;
+use crate::utils::read_yaml_file;
+use serde_yaml::{Mapping, Value};
+use std::path::Path;
+
+/// Defaults seeded from an existing `.skeletorrc` inside the snapshot source.
+///
+/// Precedence: CLI `--note` keys override inherited note keys, while CLI ignore
+/// patterns are added on top of the inherited ones.
+#[derive(Debug, Default, PartialEq)]
+pub struct InheritedConfig {
+    pub ignore_patterns: Vec<String>,
+    pub notes: Mapping,
+    pub name: Option<String>,
+}
+
+/// Loads `.skeletorrc` from the source root, returning `None` when it does not exist.
+pub fn load_inherited_config(source: &Path) -> Result<Option<InheritedConfig>, SkeletorError> {
+    let path = source.join(".skeletorrc");
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let yaml_doc = read_yaml_file(&path)?;
+    let ignore_patterns = yaml_doc
+        .get("ignore_patterns")
+        .and_then(Value::as_sequence)
+        .map(|seq| {
+            seq.iter()
+                .filter_map(Value::as_str)
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(Some(InheritedConfig {
+        ignore_patterns,
+        notes: yaml_doc.get("notes").map(notes_from_value).unwrap_or_default(),
+        name: yaml_doc.get("name").and_then(Value::as_str).map(|s| s.to_string()),
+    }))
+}
@@ -0,0 +1,100 @@
+use serde_yaml::{Mapping, Value};
+
+/// Key used for freeform notes, keeping single `--note "text"` usage compatible.
+const DESCRIPTION_KEY: &str = "description";
+
+/// Parses repeatable `--note` values: `key=value` pairs become mapping entries,
+/// anything else is treated as freeform text stored under `description`.
+pub fn parse_note_args(notes: &[String]) -> Mapping {
+    let mut mapping = Mapping::new();
+    let mut freeform: Vec<&str> = Vec::new();
+
+    for note in notes {
+        match note.split_once('=') {
+            Some((key, value)) if is_note_key(key) => {
+                mapping.insert(
+                    Value::String(key.to_string()),
+                    Value::String(value.trim().to_string()),
+                );
+            }
+            _ => freeform.push(note),
+        }
+    }
+
+    if !freeform.is_empty() {
+        mapping.insert(
+            Value::String(DESCRIPTION_KEY.to_string()),
+            Value::String(freeform.join("\n")),
+        );
+    }
+
+    mapping
+}
+
+/// Normalizes a `notes` value read from a config; legacy string notes become `description`.
+pub fn notes_from_value(value: &Value) -> Mapping {
+    match value {
+        Value::Mapping(mapping) => mapping.clone(),
+        Value::String(text) => {
+            let mut mapping = Mapping::new();
+            mapping.insert(
+                Value::String(DESCRIPTION_KEY.to_string()),
+                Value::String(text.clone()),
+            );
+            mapping
+        }
+        _ => Mapping::new(),
+    }
+}
+
+/// Overlays `overrides` onto `base` key by key, keeping entries only present in `base`.
+pub fn merge_notes(mut base: Mapping, overrides: Mapping) -> Mapping {
+    for (key, value) in overrides {
+        base.insert(key, value);
+    }
+    base
+}
+
+fn is_note_key(key: &str) -> bool {
+    !key.is_empty() && !key.contains(char::is_whitespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get<'a>(mapping: &'a Mapping, key: &str) -> Option<&'a str> {
+        mapping.get(Value::String(key.to_string())).and_then(Value::as_str)
+    }
+
+    #[test]
+    fn test_parse_note_args_structured_and_freeform() {
+        let notes = parse_note_args(&[
+            "author=Jane".to_string(),
+            "ticket=ABC-123".to_string(),
+            "Initial snapshot".to_string(),
+        ]);
+
+        assert_eq!(get(&notes, "author"), Some("Jane"));
+        assert_eq!(get(&notes, "ticket"), Some("ABC-123"));
+        assert_eq!(get(&notes, "description"), Some("Initial snapshot"));
+    }
+
+    #[test]
+    fn test_parse_note_args_freeform_with_equals() {
+        // Text with spaces before '=' is freeform, not a key
+        let notes = parse_note_args(&["set a = b before release".to_string()]);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(get(&notes, "description"), Some("set a = b before release"));
+    }
+
+    #[test]
+    fn test_notes_from_legacy_string_and_merge() {
+        let existing = notes_from_value(&Value::String("Old note".to_string()));
+        assert_eq!(get(&existing, "description"), Some("Old note"));
+
+        let merged = merge_notes(existing, parse_note_args(&["author=Jane".to_string()]));
+        assert_eq!(get(&merged, "description"), Some("Old note"));
+        assert_eq!(get(&merged, "author"), Some("Jane"));
+    }
+}
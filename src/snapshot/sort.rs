@@ -0,0 +1,77 @@
+use serde_yaml::{Mapping, Value};
+use std::cmp::Ordering;
+
+/// How `--sorted` orders each directory's entries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Files and directories intermixed, by name
+    Name,
+    /// Directories first, then files; each group by name
+    DirsFirst,
+}
+
+impl SortOrder {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "name" => Some(Self::Name),
+            "dirs-first" => Some(Self::DirsFirst),
+            _ => None,
+        }
+    }
+
+    /// Compares two entries given as `(name, is_dir)`. Names compare byte by byte,
+    /// so the order is the same on every platform and locale.
+    pub fn compare(self, a: (&str, bool), b: (&str, bool)) -> Ordering {
+        let by_name = a.0.cmp(b.0);
+        match self {
+            Self::Name => by_name,
+            Self::DirsFirst => b.1.cmp(&a.1).then(by_name),
+        }
+    }
+}
+
+/// Reorders every mapping in a `directories` tree by `order`; entries with
+/// non-string keys keep their place after the named ones.
+pub fn sort_entries(directories: &mut Value, order: SortOrder) {
+    let Some(mapping) = directories.as_mapping_mut() else {
+        return;
+    };
+
+    let mut entries: Vec<(Value, Value)> = std::mem::take(mapping).into_iter().collect();
+    entries.sort_by(|(a, a_value), (b, b_value)| match (a.as_str(), b.as_str()) {
+        (Some(a), Some(b)) => order.compare((a, a_value.is_mapping()), (b, b_value.is_mapping())),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    let mut sorted = Mapping::new();
+    for (key, mut value) in entries {
+        sort_entries(&mut value, order);
+        sorted.insert(key, value);
+    }
+    *mapping = sorted;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(value: &Value) -> Vec<&str> {
+        value.as_mapping().unwrap().keys().filter_map(Value::as_str).collect()
+    }
+
+    #[test]
+    fn test_sort_entries_by_name_or_dirs_first() {
+        let tree: Value =
+            serde_yaml::from_str("zeta.txt: z\nsrc:\n  main.rs: ''\n  lib.rs: ''\nREADME.md: hi\nassets: {}\n").unwrap();
+
+        let mut by_name = tree.clone();
+        sort_entries(&mut by_name, SortOrder::Name);
+        assert_eq!(keys(&by_name), vec!["README.md", "assets", "src", "zeta.txt"]);
+        assert_eq!(keys(&by_name["src"]), vec!["lib.rs", "main.rs"]);
+
+        let mut dirs_first = tree;
+        sort_entries(&mut dirs_first, SortOrder::DirsFirst);
+        assert_eq!(keys(&dirs_first), vec!["assets", "src", "README.md", "zeta.txt"]);
+    }
+}
@@ -0,0 +1,158 @@
+use super::checksum::canonical_key_order;
+use super::sort::SortOrder;
+use crate::errors::SkeletorError;
+use crate::tasks::mtime::with_mtime;
+use crate::tasks::{symlink_node, DirectoryWalker, WalkEntryKind};
+use serde::ser::SerializeMap;
+use serde::{Serialize, Serializer};
+use serde_yaml::{Mapping, Value};
+use std::cell::RefCell;
+use std::path::Path;
+
+/// A `directories` tree that walks the filesystem while it is serialized, so only the
+/// directories on the current path are held in memory.
+///
+/// Entries are written in the order [`super::checksum::directories_checksum`] sorts them,
+/// so hashing a serialization gives the same checksum as the in-memory tree would,
+/// unless a [`SortOrder`] is given. Each serialization walks the source again.
+pub struct DirectoryStream<'a, 'p> {
+    walker: RefCell<DirectoryWalker<'a, 'p>>,
+    base: &'a Path,
+    preserve_mtime: bool,
+    sort: Option<SortOrder>,
+    summary: RefCell<StreamSummary>,
+    error: RefCell<Option<SkeletorError>>,
+}
+
+/// What one serialization of a [`DirectoryStream`] recorded
+#[derive(Debug, Default)]
+pub struct StreamSummary {
+    pub files: usize,
+    pub dirs: usize,
+    pub binary_files: Vec<String>,
+}
+
+impl<'a, 'p> DirectoryStream<'a, 'p> {
+    /// Streams `base` with `walker`; with `preserve_mtime`, files are written as `!file` nodes.
+    pub fn new(
+        walker: DirectoryWalker<'a, 'p>,
+        base: &'a Path,
+        preserve_mtime: bool,
+        sort: Option<SortOrder>,
+    ) -> Self {
+        Self {
+            walker: RefCell::new(walker),
+            base,
+            preserve_mtime,
+            sort,
+            summary: RefCell::new(StreamSummary::default()),
+            error: RefCell::new(None),
+        }
+    }
+
+    /// The counts and binary files of the serialization so far
+    pub fn into_summary(self) -> StreamSummary {
+        self.summary.into_inner()
+    }
+
+    /// The walk error behind a failed serialization, falling back to `error` itself
+    pub fn walk_error_or(&self, error: SkeletorError) -> SkeletorError {
+        self.error.borrow_mut().take().unwrap_or(error)
+    }
+}
+
+impl Serialize for DirectoryStream<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        StreamedDir {
+            stream: self,
+            path: self.base,
+            depth: 0,
+        }
+        .serialize(serializer)
+    }
+}
+
+struct StreamedDir<'s, 'a, 'p> {
+    stream: &'s DirectoryStream<'a, 'p>,
+    path: &'s Path,
+    depth: usize,
+}
+
+impl StreamedDir<'_, '_, '_> {
+    /// Keeps the walk error so it can be reported as itself rather than as a YAML error
+    fn fail<E: serde::ser::Error>(&self, error: SkeletorError) -> E {
+        let message = error.to_string();
+        *self.stream.error.borrow_mut() = Some(error);
+        E::custom(message)
+    }
+}
+
+impl Serialize for StreamedDir<'_, '_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let stream = self.stream;
+        let listed = {
+            let mut walker = stream.walker.borrow_mut();
+            walker.enter(self.path).and_then(|_| walker.list(self.path, self.depth))
+        };
+        let mut entries = listed.map_err(|e| self.fail(e))?;
+        match stream.sort {
+            Some(order) => entries.sort_by(|a, b| order.compare((&a.name, a.kind.is_dir()), (&b.name, b.kind.is_dir()))),
+            None => entries.sort_by_cached_key(|entry| canonical_key_order(&Value::String(entry.name.clone()))),
+        }
+
+        let mut map = serializer.serialize_map(Some(entries.len()))?;
+        for entry in &entries {
+            match &entry.kind {
+                WalkEntryKind::Symlink(target) => map.serialize_entry(&entry.name, &symlink_node(target))?,
+                WalkEntryKind::CutoffDir => {
+                    stream.summary.borrow_mut().dirs += 1;
+                    map.serialize_entry(&entry.name, &Mapping::new())?;
+                }
+                WalkEntryKind::Dir => {
+                    stream.summary.borrow_mut().dirs += 1;
+                    let child = StreamedDir {
+                        stream,
+                        path: &entry.path,
+                        depth: self.depth + 1,
+                    };
+                    map.serialize_entry(&entry.name, &child)?;
+                }
+                WalkEntryKind::File => {
+                    let contents = {
+                        let mut summary = stream.summary.borrow_mut();
+                        stream.walker.borrow_mut().read_file(entry, &mut summary.binary_files)
+                    };
+                    let Some(contents) = contents else {
+                        continue;
+                    };
+                    stream.summary.borrow_mut().files += 1;
+                    if stream.preserve_mtime {
+                        map.serialize_entry(&entry.name, &with_mtime(contents, &entry.path))?;
+                    } else {
+                        map.serialize_entry(&entry.name, &contents)?;
+                    }
+                }
+            }
+        }
+        stream.walker.borrow_mut().leave();
+        map.end()
+    }
+}
+
+/// A snapshot document whose `directories` are streamed after the metadata in `header`
+pub struct StreamedSnapshot<'s, 'a, 'p> {
+    pub header: &'s Mapping,
+    pub directories: &'s DirectoryStream<'a, 'p>,
+}
+
+impl Serialize for StreamedSnapshot<'_, '_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.header.len() + 1))?;
+        for (key, value) in self.header {
+            map.serialize_entry(key, value)?;
+        }
+        map.serialize_entry("directories", self.directories)?;
+        map.end()
+    }
+}
+
@@ -0,0 +1,76 @@
+use crate::tasks::{file_ref_path, symlink_target};
+use serde_yaml::Value;
+
+/// Renders a snapshot directory structure as a `tree`-style listing.
+///
+/// Directories are suffixed with `/`, symlinks shown as `name -> target`, and files
+/// whose `/`-separated path appears in `binary_files` are tagged `[binary]`;
+/// children follow the snapshot's ordering.
+pub fn render_tree(root_name: &str, dir_snapshot: &Value, binary_files: &[String]) -> String {
+    let mut out = format!("{}\n", root_name);
+    render_children(dir_snapshot, "", "", binary_files, &mut out);
+    out
+}
+
+fn render_children(node: &Value, prefix: &str, parent_path: &str, binary_files: &[String], out: &mut String) {
+    let Some(mapping) = node.as_mapping() else {
+        return;
+    };
+
+    let entries: Vec<(&str, &Value)> = mapping
+        .iter()
+        .filter_map(|(key, value)| key.as_str().map(|name| (name, value)))
+        .collect();
+
+    for (i, (name, value)) in entries.iter().enumerate() {
+        let is_last = i + 1 == entries.len();
+        let connector = if is_last { "└── " } else { "├── " };
+        let path = if parent_path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", parent_path, name)
+        };
+
+        if let Some(target) = symlink_target(value) {
+            out.push_str(&format!("{}{}{} -> {}\n", prefix, connector, name, target));
+        } else if matches!(value, Value::Mapping(_)) && file_ref_path(value).is_none() {
+            out.push_str(&format!("{}{}{}/\n", prefix, connector, name));
+            let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+            render_children(value, &child_prefix, &path, binary_files, out);
+        } else if binary_files.contains(&path) {
+            out.push_str(&format!("{}{}{} [binary]\n", prefix, connector, name));
+        } else {
+            out.push_str(&format!("{}{}{}\n", prefix, connector, name));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_tree_connectors() {
+        let snapshot: Value = serde_yaml::from_str(
+            r#"
+            src:
+              main.rs: ""
+              utils:
+                mod.rs: ""
+            README.md: ""
+            "#,
+        )
+        .unwrap();
+
+        let tree = render_tree("project", &snapshot, &["src/utils/mod.rs".to_string()]);
+        let expected = "\
+project
+├── src/
+│   ├── main.rs
+│   └── utils/
+│       └── mod.rs [binary]
+└── README.md
+";
+        assert_eq!(tree, expected);
+    }
+}
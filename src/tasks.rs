@@ -1,9 +1,26 @@
+pub mod binary;
+pub mod expand;
+pub mod mtime;
+pub mod newline;
+pub mod variables;
+
 use crate::errors::SkeletorError;
-use ignore::gitignore::Gitignore;
+use crate::output::{Reporter, SilentReporter, SimpleApplyResult};
+use globset::GlobSet;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
 use log::{info, warn};
+use serde_yaml::value::{Tag, TaggedValue};
 use serde_yaml::Value;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+pub use self::binary::{BinaryDetection, DetectionStrategy};
+pub use self::newline::{LineEndings, NewlinePolicy};
 
 /// Result of file and directory creation operations
 #[derive(Debug, Clone)]
@@ -14,6 +31,13 @@ pub struct CreationResult {
     pub skipped_files_list: Vec<String>,
     pub files_overwritten: usize,
     pub overwritten_files_list: Vec<String>,
+    pub files_preserved: usize,
+    pub preserved_files_list: Vec<String>,
+    pub files_unchanged: usize,
+    pub unchanged_files_list: Vec<String>,
+    /// `(path, reason)` for entries that could not be created
+    pub failed_files_list: Vec<(String, String)>,
+    pub symlinks_created: usize,
 }
 
 impl Default for CreationResult {
@@ -31,15 +55,73 @@ impl CreationResult {
             skipped_files_list: Vec::new(),
             files_overwritten: 0,
             overwritten_files_list: Vec::new(),
+            files_preserved: 0,
+            preserved_files_list: Vec::new(),
+            files_unchanged: 0,
+            unchanged_files_list: Vec::new(),
+            failed_files_list: Vec::new(),
+            symlinks_created: 0,
         }
     }
+
+    /// Folds `other` into this result, appending its lists after the current ones
+    pub fn merge(&mut self, other: CreationResult) {
+        self.files_created += other.files_created;
+        self.dirs_created += other.dirs_created;
+        self.files_skipped += other.files_skipped;
+        self.skipped_files_list.extend(other.skipped_files_list);
+        self.files_overwritten += other.files_overwritten;
+        self.overwritten_files_list.extend(other.overwritten_files_list);
+        self.files_preserved += other.files_preserved;
+        self.preserved_files_list.extend(other.preserved_files_list);
+        self.files_unchanged += other.files_unchanged;
+        self.unchanged_files_list.extend(other.unchanged_files_list);
+        self.failed_files_list.extend(other.failed_files_list);
+        self.symlinks_created += other.symlinks_created;
+    }
+
+    /// Converts into the summary a [`Reporter`] receives in `apply_complete`
+    pub fn into_apply_result(self, duration: Duration, tasks_total: usize) -> SimpleApplyResult {
+        let mut result = SimpleApplyResult::with_skipped_and_overwritten(
+            self.files_created,
+            self.dirs_created,
+            duration,
+            tasks_total,
+            self.files_skipped,
+            self.skipped_files_list,
+            self.files_overwritten,
+            self.overwritten_files_list,
+        );
+        result.files_preserved = self.files_preserved;
+        result.preserved_files_list = self.preserved_files_list;
+        result.files_unchanged = self.files_unchanged;
+        result.unchanged_files_list = self.unchanged_files_list;
+        result.failed_files_list = self.failed_files_list;
+        result.symlinks_created = self.symlinks_created;
+        result
+    }
 }
 
-/// A task to either create a directory or a file.
+/// A task to create a directory, a file, or a symlink pointing at a target.
 #[derive(Debug, PartialEq, Clone)]
 pub enum Task {
     Dir(PathBuf),
     File(PathBuf, String),
+    Symlink(PathBuf, String),
+}
+
+impl Task {
+    /// The path this task creates
+    pub fn path(&self) -> &Path {
+        match self {
+            Task::Dir(path) | Task::File(path, _) | Task::Symlink(path, _) => path,
+        }
+    }
+
+    /// Whether this task creates a directory
+    pub fn is_dir(&self) -> bool {
+        matches!(self, Task::Dir(_))
+    }
 }
 
 fn join_safe_path(base: &Path, key: &str) -> Result<PathBuf, SkeletorError> {
@@ -62,91 +144,315 @@ fn join_safe_path(base: &Path, key: &str) -> Result<PathBuf, SkeletorError> {
     Ok(base.join(key_path))
 }
 
+/// Like [`join_safe_path`], but lets `..` segments and absolute keys leave `base`
+fn join_escaping_path(base: &Path, key: &str) -> Result<PathBuf, SkeletorError> {
+    if key.is_empty() {
+        return Err(SkeletorError::invalid_path(key));
+    }
+    Ok(base.join(key))
+}
+
+/// Checks that every task's path stays inside `output_dir` once symlinks are resolved,
+/// failing with [`SkeletorError::Config`] naming the first entry that would land outside.
+///
+/// Keys are already kept from using `..` or absolute paths, but a directory that is a
+/// symlink to somewhere else would still carry the files under it out of `output_dir`.
+/// Symlink tasks are checked by where the link itself goes, not by its target.
+pub fn ensure_within_output(output_dir: &Path, tasks: &[Task]) -> Result<(), SkeletorError> {
+    let root = resolve_existing(output_dir);
+    for task in tasks {
+        let path = task.path();
+        let resolved = match (task, path.parent(), path.file_name()) {
+            (Task::Symlink(_, _), Some(parent), Some(name)) => resolve_existing(parent).join(name),
+            _ => resolve_existing(path),
+        };
+        if !resolved.starts_with(&root) {
+            let key = path.strip_prefix(output_dir).unwrap_or(path);
+            return Err(SkeletorError::Config(format!(
+                "'{}' resolves to {}, outside the output directory {}; pass --allow-escape to write it anyway",
+                key.display(),
+                resolved.display(),
+                root.display()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Canonicalizes the longest existing prefix of `path` and appends the rest unchanged
+fn resolve_existing(path: &Path) -> PathBuf {
+    let mut missing = Vec::new();
+    let mut existing = path;
+    loop {
+        // A relative path's last parent is empty, meaning the current directory
+        let probe = if existing.as_os_str().is_empty() { Path::new(".") } else { existing };
+        if let Ok(canonical) = fs::canonicalize(probe) {
+            return missing.iter().rev().fold(canonical, |resolved, part| resolved.join(part));
+        }
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => return path.to_path_buf(),
+        }
+    }
+}
+
 /// Traverses the YAML structure and returns a list of tasks to create directories and files.
+///
+/// Every mapping becomes a [`Task::Dir`], so an empty `logs: {}` is still created; a string,
+/// even an empty one, is always a file. The order is deterministic: depth-first, each
+/// directory before its contents, siblings sorted by name.
 pub fn traverse_structure(base: &Path, yaml: &Value) -> Result<Vec<Task>, SkeletorError> {
     let mut tasks = Vec::new();
-    let mut queue = Vec::new();
-    queue.push((base.to_path_buf(), yaml));
-
-    while let Some((current_path, node)) = queue.pop() {
-        if let Some(map) = node.as_mapping() {
-            for (key, value) in map {
-                if let Some(key_str) = key.as_str() {
-                    let new_path = join_safe_path(&current_path, key_str)?;
-                    match value {
-                        Value::Mapping(_) => {
-                            tasks.push(Task::Dir(new_path.clone()));
-                            queue.push((new_path, value));
-                        }
-                        Value::String(content) => {
-                            tasks.push(Task::File(new_path, content.clone()));
-                        }
-                        _ => {}
-                    }
+    traverse_structure_batched(base, yaml, usize::MAX, |batch| {
+        tasks.extend(batch);
+        Ok(())
+    })?;
+    Ok(tasks)
+}
+
+/// Like [`traverse_structure`], but accepts keys with `..` segments or absolute paths,
+/// so tasks may point outside `base`. Only for callers that opted out of that protection.
+pub fn traverse_structure_allowing_escape(base: &Path, yaml: &Value) -> Result<Vec<Task>, SkeletorError> {
+    let mut tasks = Vec::new();
+    traverse_entries(base, yaml, usize::MAX, join_escaping_path, |batch| {
+        tasks.extend(batch);
+        Ok(())
+    })?;
+    Ok(tasks)
+}
+
+/// Like [`traverse_structure`], but hands the tasks to `on_batch` in chunks of at most
+/// `batch_size` as they are found, so they are never all held in memory at once.
+///
+/// Tasks follow a depth-first walk with siblings sorted by name, so every directory's task
+/// comes before those of its contents, in this batch or an earlier one.
+/// An error (such as an unsafe path) stops the walk, but batches already handed over stay handled.
+/// Returns the total number of tasks.
+pub fn traverse_structure_batched(
+    base: &Path,
+    yaml: &Value,
+    batch_size: usize,
+    on_batch: impl FnMut(Vec<Task>) -> Result<(), SkeletorError>,
+) -> Result<usize, SkeletorError> {
+    traverse_entries(base, yaml, batch_size, join_safe_path, on_batch)
+}
+
+/// The walk behind [`traverse_structure_batched`], joining each key onto its parent with `join`
+fn traverse_entries(
+    base: &Path,
+    yaml: &Value,
+    batch_size: usize,
+    join: fn(&Path, &str) -> Result<PathBuf, SkeletorError>,
+    mut on_batch: impl FnMut(Vec<Task>) -> Result<(), SkeletorError>,
+) -> Result<usize, SkeletorError> {
+    let batch_size = batch_size.max(1);
+    let mut total = 0;
+    let mut tasks = Vec::new();
+    // Entries still to visit, kept in reverse so the next one in order is popped first
+    let mut stack = Vec::new();
+    push_sorted_entries(&mut stack, base, yaml);
+
+    while let Some((current_path, key_str, value)) = stack.pop() {
+        let new_path = join(&current_path, key_str)?;
+        match value {
+            Value::Mapping(_) if file_ref_path(value).is_none() => {
+                tasks.push(Task::Dir(new_path.clone()));
+                push_sorted_entries(&mut stack, &new_path, value);
+            }
+            Value::String(content) => {
+                tasks.push(Task::File(new_path, content.clone()));
+            }
+            _ => {
+                if let Some(content) = file_content(value) {
+                    tasks.push(Task::File(new_path, content.to_string()));
+                } else if let Some(target) = symlink_target(value) {
+                    tasks.push(Task::Symlink(new_path, target.to_string()));
+                } else if let Some(reference) = file_ref_path(value) {
+                    return Err(SkeletorError::Config(format!(
+                        "{:?} refers to external content '{}' that was not loaded; read the config from its file so references can be resolved",
+                        new_path, reference
+                    )));
                 }
             }
         }
+        if tasks.len() >= batch_size {
+            total += tasks.len();
+            on_batch(std::mem::take(&mut tasks))?;
+        }
     }
 
-    Ok(tasks)
+    if !tasks.is_empty() {
+        total += tasks.len();
+        on_batch(tasks)?;
+    }
+    Ok(total)
+}
+
+/// Pushes the string-keyed entries of `node` onto `stack` in reverse name order,
+/// so popping visits them sorted.
+fn push_sorted_entries<'a>(stack: &mut Vec<(PathBuf, &'a str, &'a Value)>, path: &Path, node: &'a Value) {
+    let Some(map) = node.as_mapping() else {
+        return;
+    };
+    let mut entries: Vec<(&str, &Value)> = map
+        .iter()
+        .filter_map(|(key, value)| Some((key.as_str()?, value)))
+        .collect();
+    entries.sort_unstable_by(|a, b| b.0.cmp(a.0));
+    stack.extend(entries.into_iter().map(|(key, value)| (path.to_path_buf(), key, value)));
+}
+
+/// Streams `yaml` into the filesystem under `base`, creating at most `batch_size` tasks
+/// at a time instead of planning the whole structure up front. Suited to very large
+/// configs, where the full task list would duplicate every file's content in memory.
+///
+/// Symlinks are held back and created after all batches, as with [`create_files_and_directories_with`].
+/// Each batch is checked with [`ensure_within_output`] just before it is created.
+/// Returns the combined result and the total number of tasks.
+#[allow(dead_code)]
+pub fn create_files_and_directories_batched(
+    base: &Path,
+    yaml: &Value,
+    batch_size: usize,
+    overwrite: bool,
+    keep: Option<&Gitignore>,
+    atomic: bool,
+) -> Result<(CreationResult, usize), SkeletorError> {
+    let options = FileWriteOptions {
+        overwrite,
+        atomic,
+        keep,
+        ..Default::default()
+    };
+    let mut result = CreationResult::new();
+    let mut symlinks = Vec::new();
+    let total = traverse_structure_batched(base, yaml, batch_size, |mut batch| {
+        ensure_within_output(base, &batch)?;
+        batch.retain(|task| {
+            let is_symlink = matches!(task, Task::Symlink(_, _));
+            if is_symlink {
+                symlinks.push(task.clone());
+            }
+            !is_symlink
+        });
+        let partial = create_files_and_directories_with(&batch, &options, &SilentReporter)?;
+        result.merge(partial);
+        Ok(())
+    })?;
+    result.merge(create_files_and_directories_with(&symlinks, &options, &SilentReporter)?);
+    Ok((result, total))
+}
+
+/// Creates the output directory and any missing parents before tasks run.
+///
+/// The root is not a config entry, so it is not counted in `dirs_created`.
+pub fn ensure_output_root(output_dir: &Path) -> Result<(), SkeletorError> {
+    fs::create_dir_all(output_dir)
+        .map_err(|e| SkeletorError::from_io_with_context(e, output_dir.to_path_buf()))
+}
+
+/// Name of the target-side file listing gitignore-style patterns of paths never to overwrite.
+pub const KEEP_FILE_NAME: &str = ".skeletorkeep";
+
+/// Suffix of the temporary files atomic writes rename into place
+const ATOMIC_TEMP_SUFFIX: &str = ".skeletor-tmp";
+
+/// Loads `.skeletorkeep` from the target directory, returning `None` when it does not exist.
+pub fn load_keep_matcher(target_dir: &Path) -> Result<Option<Gitignore>, SkeletorError> {
+    let keep_path = target_dir.join(KEEP_FILE_NAME);
+    if !keep_path.is_file() {
+        return Ok(None);
+    }
+
+    let mut builder = GitignoreBuilder::new(target_dir);
+    if let Some(e) = builder.add(&keep_path) {
+        return Err(SkeletorError::InvalidIgnorePattern {
+            pattern: format!("{} ({})", keep_path.display(), e),
+        });
+    }
+
+    builder
+        .build()
+        .map(Some)
+        .map_err(|e| SkeletorError::InvalidIgnorePattern {
+            pattern: format!("Failed to compile {} patterns: {}", KEEP_FILE_NAME, e),
+        })
+}
+
+/// Whether `path` is protected by `keep` (see [`load_keep_matcher`])
+pub fn is_kept(keep: Option<&Gitignore>, path: &Path) -> bool {
+    keep.is_some_and(|matcher| {
+        let relative = path.strip_prefix(matcher.path()).unwrap_or(path);
+        matcher
+            .matched_path_or_any_parents(relative, false)
+            .is_ignore()
+    })
 }
 
 /// Creates files and directories as specified by tasks; logs progress and respects the overwrite flag.
+///
+/// Shorthand for [`create_files_and_directories_with`] with atomic writes and otherwise
+/// default [`FileWriteOptions`], reporting nothing.
+#[allow(dead_code)]
 pub fn create_files_and_directories(
     tasks: &[Task],
     overwrite: bool,
+) -> Result<CreationResult, SkeletorError> {
+    let options = FileWriteOptions {
+        overwrite,
+        atomic: true,
+        ..Default::default()
+    };
+    create_files_and_directories_with(tasks, &options, &SilentReporter)
+}
+
+/// Creates files and directories as specified by tasks, writing files as `options` describe.
+/// Symlinks are created last, so no file in this run is ever written through one of them.
+/// As each task finishes, `reporter` receives its progress and a success or warning.
+/// Entries that cannot be created are listed in `failed_files_list` and the rest carry on,
+/// unless `fail_fast` is set, which returns the first failure as [`SkeletorError::TaskFailed`].
+pub fn create_files_and_directories_with(
+    tasks: &[Task],
+    options: &FileWriteOptions,
+    reporter: &dyn Reporter,
 ) -> Result<CreationResult, SkeletorError> {
     let mut result = CreationResult::new();
+    let mut progress = TaskProgress::new(reporter, tasks.len());
+    let mut symlinks = Vec::new();
 
     for (i, task) in tasks.iter().enumerate() {
+        let before = progress.checkpoint(&result);
         match task {
-            Task::Dir(path) => {
-                if let Err(e) = fs::create_dir_all(path) {
-                    warn!("Failed to create directory {:?}: {:?}", path, e);
-                } else {
-                    result.dirs_created += 1;
-                    info!("Created directory: {:?}", path);
-                }
-            }
-            Task::File(path, content) => {
-                let file_exists = path.exists();
-                
-                if !overwrite && file_exists {
-                    info!("Skipping file creation, already exists: {:?}", path);
-                    result.files_skipped += 1;
-                    result.skipped_files_list.push(path.display().to_string());
-                } else {
-                    if let Some(parent) = path.parent() {
-                        if let Err(e) = fs::create_dir_all(parent) {
-                            warn!(
-                                "Failed to create parent directory for file {:?}: {:?}",
-                                path, e
-                            );
-                            continue;
-                        }
-                    }
-                    if let Err(e) = fs::write(path, content) {
-                        warn!("Failed to write file {:?}: {:?}", path, e);
-                    } else {
-                        result.files_created += 1;
-                        
-                        if overwrite && file_exists {
-                            result.files_overwritten += 1;
-                            result.overwritten_files_list.push(path.display().to_string());
-                            info!("Overwritten file: {:?}", path);
-                        } else {
-                            info!("Created file: {:?}", path);
-                        }
-                    }
-                }
+            Task::Dir(path) => create_dir_task(path, &mut result),
+            Task::File(path, content) => write_file_task(path, content, options, &mut result),
+            Task::Symlink(_, _) => {
+                symlinks.push(task);
+                continue;
             }
         }
+        progress.finish(task, before, &result);
+        if options.fail_fast {
+            first_failure(&result, before.0)?;
+        }
 
         // **Log Progress Every 1000 Files to Avoid IO Overhead**
         if i % 1000 == 0 && i > 0 {
             info!("Processed {} out of {} tasks...", i, tasks.len());
         }
     }
+    for task in symlinks {
+        if let Task::Symlink(path, target) = task {
+            let before = progress.checkpoint(&result);
+            create_symlink_task(path, target, options, &mut result);
+            progress.finish(task, before, &result);
+            if options.fail_fast {
+                first_failure(&result, before.0)?;
+            }
+        }
+    }
 
     info!(
         "Task Complete: {} directories and {} files created.",
@@ -155,6 +461,551 @@ pub fn create_files_and_directories(
     Ok(result)
 }
 
+/// Parallel variant of [`create_files_and_directories_with`] for large templates.
+///
+/// Directories (and every file's parent) are created serially first, then files are
+/// written concurrently in contiguous chunks. Chunk results are merged in task order,
+/// so counts and lists are identical to the serial path. Written files are reported
+/// to `reporter` once all writer threads have finished, in task order.
+/// With `fail_fast`, writers stop taking new files once any write fails, and the
+/// earliest failure in task order is returned.
+pub fn create_files_and_directories_parallel(
+    tasks: &[Task],
+    options: &FileWriteOptions,
+    reporter: &dyn Reporter,
+) -> Result<CreationResult, SkeletorError> {
+    let fail_fast = options.fail_fast;
+    let mut result = CreationResult::new();
+    let mut progress = TaskProgress::new(reporter, tasks.len());
+
+    let mut files: Vec<(&PathBuf, &String)> = Vec::new();
+    let mut file_tasks = Vec::new();
+    let mut symlinks = Vec::new();
+    for task in tasks {
+        match task {
+            Task::Dir(path) => {
+                let before = progress.checkpoint(&result);
+                create_dir_task(path, &mut result);
+                progress.finish(task, before, &result);
+                if fail_fast {
+                    first_failure(&result, before.0)?;
+                }
+            }
+            Task::File(path, content) => {
+                files.push((path, content));
+                file_tasks.push(task);
+            }
+            Task::Symlink(_, _) => symlinks.push(task),
+        }
+    }
+
+    // Parents exist before any writer thread starts, so threads never race on mkdir
+    let mut parents: Vec<&Path> = files.iter().filter_map(|(path, _)| path.parent()).collect();
+    parents.sort();
+    parents.dedup();
+    for parent in parents {
+        if let Err(e) = fs::create_dir_all(parent) {
+            warn!("Failed to create parent directory {:?}: {:?}", parent, e);
+        }
+    }
+
+    let threads = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(4)
+        .min(files.len())
+        .max(1);
+    let chunk_size = ((files.len() + threads - 1) / threads).max(1);
+
+    let failed = AtomicBool::new(false);
+    let partials: Vec<CreationResult> = std::thread::scope(|scope| {
+        let handles: Vec<_> = files
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let failed = &failed;
+                scope.spawn(move || {
+                    let mut partial = CreationResult::new();
+                    for (path, content) in chunk {
+                        if fail_fast && failed.load(Ordering::Relaxed) {
+                            break;
+                        }
+                        write_file_task(path, content, options, &mut partial);
+                        if !partial.failed_files_list.is_empty() {
+                            failed.store(true, Ordering::Relaxed);
+                        }
+                    }
+                    partial
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("file writer thread panicked"))
+            .collect()
+    });
+    let (failed_before, skipped_before) = progress.checkpoint(&result);
+    for partial in partials {
+        result.merge(partial);
+    }
+    let failures: HashMap<&str, &str> = result.failed_files_list[failed_before..]
+        .iter()
+        .map(|(path, reason)| (path.as_str(), reason.as_str()))
+        .collect();
+    let skipped: HashSet<&str> = result.skipped_files_list[skipped_before..]
+        .iter()
+        .map(String::as_str)
+        .collect();
+    for task in file_tasks {
+        let path = task.path().display().to_string();
+        progress.report(task, failures.get(path.as_str()).copied(), skipped.contains(path.as_str()));
+    }
+    if fail_fast {
+        first_failure(&result, failed_before)?;
+    }
+    for task in symlinks {
+        if let Task::Symlink(path, target) = task {
+            let before = progress.checkpoint(&result);
+            create_symlink_task(path, target, options, &mut result);
+            progress.finish(task, before, &result);
+            if fail_fast {
+                first_failure(&result, before.0)?;
+            }
+        }
+    }
+
+    info!(
+        "Task Complete: {} directories and {} files created ({} threads).",
+        result.dirs_created, result.files_created, threads
+    );
+    Ok(result)
+}
+
+/// Returns the first failure recorded after the first `failed_before` as an error, if any
+fn first_failure(result: &CreationResult, failed_before: usize) -> Result<(), SkeletorError> {
+    match result.failed_files_list.get(failed_before) {
+        Some((path, reason)) => Err(SkeletorError::TaskFailed {
+            path: path.clone(),
+            reason: reason.clone(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Reports each finished task to a [`Reporter`], numbered in completion order
+struct TaskProgress<'a> {
+    reporter: &'a dyn Reporter,
+    total: usize,
+    done: usize,
+}
+
+impl<'a> TaskProgress<'a> {
+    fn new(reporter: &'a dyn Reporter, total: usize) -> Self {
+        Self {
+            reporter,
+            total,
+            done: 0,
+        }
+    }
+
+    /// Failure and skip counts before a task runs, to tell afterwards how it went
+    fn checkpoint(&self, result: &CreationResult) -> (usize, usize) {
+        (result.failed_files_list.len(), result.skipped_files_list.len())
+    }
+
+    fn finish(&mut self, task: &Task, (failed, skipped): (usize, usize), result: &CreationResult) {
+        let failure = result.failed_files_list.get(failed).map(|(_, reason)| reason.as_str());
+        self.report(task, failure, result.skipped_files_list.len() > skipped);
+    }
+
+    fn report(&mut self, task: &Task, failure: Option<&str>, skipped: bool) {
+        self.done += 1;
+        self.reporter
+            .progress(self.done, self.total, &task.path().display().to_string());
+        if let Some(reason) = failure {
+            self.reporter.task_warning(task, reason);
+        } else if skipped {
+            self.reporter.task_warning(task, "already exists (use --overwrite to replace it)");
+        } else {
+            self.reporter.task_success(task);
+        }
+    }
+}
+
+/// How [`create_files_and_directories_with`] and its parallel variant write files
+#[derive(Default)]
+pub struct FileWriteOptions<'a> {
+    /// Replace existing files instead of skipping them
+    pub overwrite: bool,
+    /// Rewrite existing files only when their content differs from the template
+    pub update: bool,
+    /// Write each file to a temporary file beside it and rename it into place,
+    /// so an interrupted run never leaves a partially written file behind
+    pub atomic: bool,
+    /// Return the first entry that cannot be created as an error instead of carrying on
+    pub fail_fast: bool,
+    /// Existing files matched here (see [`load_keep_matcher`]) are preserved even when overwriting
+    pub keep: Option<&'a Gitignore>,
+    /// Adjusts each file's final newline and line endings before it is written
+    pub newline: Option<&'a NewlinePolicy>,
+    /// Suffix of the copy an existing file is saved to before it is replaced;
+    /// the file is left alone if the copy fails
+    pub backup: Option<&'a str>,
+}
+
+fn create_dir_task(path: &Path, result: &mut CreationResult) {
+    if let Err(e) = fs::create_dir_all(path) {
+        warn!("Failed to create directory {:?}: {:?}", path, e);
+        result.failed_files_list.push((path.display().to_string(), e.to_string()));
+    } else {
+        result.dirs_created += 1;
+        info!("Created directory: {:?}", path);
+    }
+}
+
+fn write_file_task(
+    path: &Path,
+    content: &str,
+    options: &FileWriteOptions,
+    result: &mut CreationResult,
+) {
+    let file_exists = path.exists();
+    let content = match options.newline {
+        Some(policy) => policy.apply(path, content),
+        None => Cow::Borrowed(content),
+    };
+    
+    if file_exists && is_kept(options.keep, path) {
+        info!("Preserving file listed in {}: {:?}", KEEP_FILE_NAME, path);
+        result.files_preserved += 1;
+        result.preserved_files_list.push(path.display().to_string());
+    } else if options.update
+        && file_exists
+        && fs::read(path).is_ok_and(|existing| existing == content.as_bytes())
+    {
+        info!("Leaving unchanged file as is: {:?}", path);
+        result.files_unchanged += 1;
+        result.unchanged_files_list.push(path.display().to_string());
+    } else if !options.overwrite && !options.update && file_exists {
+        info!("Skipping file creation, already exists: {:?}", path);
+        result.files_skipped += 1;
+        result.skipped_files_list.push(path.display().to_string());
+    } else {
+        if let Some(parent) = path.parent() {
+            if let Err(e) = fs::create_dir_all(parent) {
+                warn!(
+                    "Failed to create parent directory for file {:?}: {:?}",
+                    path, e
+                );
+                result.failed_files_list.push((path.display().to_string(), e.to_string()));
+                return;
+            }
+        }
+        let backup = match options.backup.filter(|_| file_exists) {
+            Some(suffix) => {
+                let backup = backup_path(path, suffix);
+                if let Err(e) = fs::copy(path, &backup) {
+                    warn!("Failed to back up {:?} to {:?}: {:?}", path, backup, e);
+                    result
+                        .failed_files_list
+                        .push((path.display().to_string(), format!("could not back up to {}: {}", backup.display(), e)));
+                    return;
+                }
+                Some(backup)
+            }
+            None => None,
+        };
+        let written = if options.atomic {
+            write_atomically(path, content.as_bytes())
+        } else {
+            fs::write(path, content.as_ref())
+        };
+        if let Err(e) = written {
+            warn!("Failed to write file {:?}: {:?}", path, e);
+            result.failed_files_list.push((path.display().to_string(), e.to_string()));
+        } else {
+            result.files_created += 1;
+            
+            if file_exists {
+                result.files_overwritten += 1;
+                result.overwritten_files_list.push(match backup {
+                    Some(backup) => format!("{} (backup: {})", path.display(), backup.display()),
+                    None => path.display().to_string(),
+                });
+                info!("Overwritten file: {:?}", path);
+            } else {
+                info!("Created file: {:?}", path);
+            }
+        }
+    }
+}
+
+/// `path` with `suffix` appended to its file name, where `--backup` saves the file it replaces
+pub fn backup_path(path: &Path, suffix: &str) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(suffix);
+    PathBuf::from(backup)
+}
+
+/// Writes `content` to a temporary file next to `path`, then renames it over `path`.
+///
+/// The rename is atomic on the same filesystem, so readers see either the old file or
+/// the complete new one. An existing file's permissions carry over to its replacement.
+fn write_atomically(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp = path.with_file_name(format!(".{}.{}{}", name, std::process::id(), ATOMIC_TEMP_SUFFIX));
+    let written = fs::write(&temp, content)
+        .and_then(|_| match fs::metadata(path) {
+            Ok(existing) => fs::set_permissions(&temp, existing.permissions()),
+            Err(_) => Ok(()),
+        })
+        .and_then(|_| fs::rename(&temp, path));
+    if written.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    written
+}
+
+fn create_symlink_task(
+    path: &Path,
+    target: &str,
+    options: &FileWriteOptions,
+    result: &mut CreationResult,
+) {
+    let existing = fs::symlink_metadata(path).ok();
+    let display = path.display().to_string();
+
+    if let Some(metadata) = &existing {
+        if is_kept(options.keep, path) {
+            info!("Preserving entry listed in {}: {:?}", KEEP_FILE_NAME, path);
+            result.files_preserved += 1;
+            result.preserved_files_list.push(display);
+            return;
+        }
+        if options.update
+            && metadata.file_type().is_symlink()
+            && fs::read_link(path).is_ok_and(|current| current == Path::new(target))
+        {
+            info!("Leaving unchanged symlink as is: {:?}", path);
+            result.files_unchanged += 1;
+            result.unchanged_files_list.push(display);
+            return;
+        }
+        if !options.overwrite && !options.update {
+            info!("Skipping symlink creation, already exists: {:?}", path);
+            result.files_skipped += 1;
+            result.skipped_files_list.push(display);
+            return;
+        }
+        if metadata.is_dir() {
+            warn!("Refusing to replace directory {:?} with a symlink", path);
+            result
+                .failed_files_list
+                .push((display, "a directory already exists at this path".to_string()));
+            return;
+        }
+        if let Err(e) = fs::remove_file(path) {
+            warn!("Failed to replace {:?} with a symlink: {:?}", path, e);
+            result.failed_files_list.push((display, e.to_string()));
+            return;
+        }
+    }
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = fs::create_dir_all(parent) {
+            warn!("Failed to create parent directory for symlink {:?}: {:?}", path, e);
+            result.failed_files_list.push((display, e.to_string()));
+            return;
+        }
+    }
+    match make_symlink(Path::new(target), path) {
+        Ok(()) => {
+            result.symlinks_created += 1;
+            if existing.is_some() {
+                result.files_overwritten += 1;
+                result.overwritten_files_list.push(display);
+            }
+            info!("Created symlink: {:?} -> {:?}", path, target);
+        }
+        Err(e) => {
+            warn!("Failed to create symlink {:?}: {:?}", path, e);
+            result.failed_files_list.push((display, e.to_string()));
+        }
+    }
+}
+
+#[cfg(unix)]
+fn make_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+/// Windows distinguishes file and directory links; relative targets resolve from the link's folder
+#[cfg(windows)]
+fn make_symlink(target: &Path, link: &Path) -> std::io::Result<()> {
+    let resolved = link.parent().map(|dir| dir.join(target)).unwrap_or_else(|| target.to_path_buf());
+    if resolved.is_dir() {
+        std::os::windows::fs::symlink_dir(target, link)
+    } else {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn make_symlink(_target: &Path, _link: &Path) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+/// YAML tag marking a snapshot node as a symlink (`name: !symlink target`).
+pub const SYMLINK_TAG: &str = "symlink";
+
+/// Builds a snapshot node recording a symlink to `target` without following it.
+pub fn symlink_node(target: &Path) -> Value {
+    Value::Tagged(Box::new(TaggedValue {
+        tag: Tag::new(SYMLINK_TAG),
+        value: Value::String(target.to_string_lossy().replace("\\", "/")),
+    }))
+}
+
+/// Returns the link target when `value` is a symlink node.
+pub fn symlink_target(value: &Value) -> Option<&str> {
+    match value {
+        Value::Tagged(tagged) if tagged.tag == Tag::new(SYMLINK_TAG) => tagged.value.as_str(),
+        _ => None,
+    }
+}
+
+/// YAML tag marking a file whose content lives in a sidecar file (`name: !file_ref .skeletor_assets/ab12.txt`).
+pub const FILE_REF_TAG: &str = "file_ref";
+
+/// Builds a node referring to content stored at `reference`, relative to the config's directory.
+pub fn file_ref_node(reference: &str) -> Value {
+    Value::Tagged(Box::new(TaggedValue {
+        tag: Tag::new(FILE_REF_TAG),
+        value: Value::String(reference.to_string()),
+    }))
+}
+
+/// YAML tag of a hand-written file reference (`name: !source ./snippets/main.rs`).
+///
+/// A tag rather than a `{ source: ... }` mapping, which a directory holding just a file
+/// named `source` would be indistinguishable from.
+pub const SOURCE_TAG: &str = "source";
+
+/// Returns the referenced path when `value` is a file reference node: a `!file_ref`
+/// sidecar or a `!source` reference.
+pub fn file_ref_path(value: &Value) -> Option<&str> {
+    match value {
+        Value::Tagged(tagged) if tagged.tag == Tag::new(FILE_REF_TAG) || tagged.tag == Tag::new(SOURCE_TAG) => {
+            tagged.value.as_str()
+        }
+        _ => None,
+    }
+}
+
+/// YAML tag marking a file recorded with metadata
+/// (`name: !file { content: ..., mtime: 2024-01-01T00:00:00Z }`), written by `snapshot --preserve-mtime`.
+pub const FILE_TAG: &str = "file";
+
+/// Builds a file node carrying `content` and its modification time (RFC 3339).
+pub fn file_node(content: String, mtime: &str) -> Value {
+    let mut fields = serde_yaml::Mapping::new();
+    fields.insert(Value::String("content".to_string()), Value::String(content));
+    fields.insert(Value::String("mtime".to_string()), Value::String(mtime.to_string()));
+    Value::Tagged(Box::new(TaggedValue {
+        tag: Tag::new(FILE_TAG),
+        value: Value::Mapping(fields),
+    }))
+}
+
+/// Returns the content of a plain or `!file` file node.
+pub fn file_content(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(content) => Some(content),
+        Value::Tagged(tagged) if tagged.tag == Tag::new(FILE_TAG) => {
+            Some(tagged.value.get("content").and_then(Value::as_str).unwrap_or_default())
+        }
+        _ => None,
+    }
+}
+
+/// Returns the recorded modification time when `value` is a `!file` node that has one.
+pub fn file_mtime(value: &Value) -> Option<&str> {
+    match value {
+        Value::Tagged(tagged) if tagged.tag == Tag::new(FILE_TAG) => tagged.value.get("mtime")?.as_str(),
+        _ => None,
+    }
+}
+
+/// Replaces every file reference in `structure` with the content of the file it names,
+/// read relative to `config_dir`. References may not leave that directory.
+pub fn resolve_file_refs(structure: &Value, config_dir: &Path) -> Result<Value, SkeletorError> {
+    if let Some(reference) = file_ref_path(structure) {
+        let path = join_safe_path(config_dir, reference)?;
+        return crate::utils::read_file_to_string(&path)
+            .map(Value::String)
+            .map_err(|e| match e {
+                // A reference always names a file, whatever its path looks like
+                SkeletorError::DirectoryNotFound { path } => SkeletorError::FileNotFound { path },
+                other => other,
+            });
+    }
+    match structure {
+        Value::Mapping(mapping) => {
+            let mut resolved = serde_yaml::Mapping::new();
+            for (key, value) in mapping {
+                resolved.insert(key.clone(), resolve_file_refs(value, config_dir)?);
+            }
+            Ok(Value::Mapping(resolved))
+        }
+        other => Ok(other.clone()),
+    }
+}
+
+/// Something [`traverse_directory_with`] reports while walking
+#[derive(Debug)]
+pub enum WalkEvent<'a> {
+    /// An entry that was not ignored is being visited
+    Visited(&'a Path),
+    /// A file's contents, or a directory's listing when unreadable directories are skipped,
+    /// could not be read, so the entry is left out of the snapshot
+    Unreadable(&'a Path, &'a std::io::Error),
+    /// An entry whose name is not valid UTF-8 was left out under [`InvalidNamePolicy::Skip`]
+    InvalidName(&'a Path),
+    /// A file over the content size limit was recorded empty without being read
+    Oversized(&'a Path),
+    /// An entry was left out by an ignore pattern, given as written in the matcher;
+    /// entries inside an ignored directory are never visited and not reported
+    Ignored(&'a Path, &'a str),
+}
+
+/// What [`traverse_directory_with`] does with an entry whose name is not valid UTF-8
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidNamePolicy {
+    /// Leave the entry out and report it as a [`WalkEvent::InvalidName`]
+    Skip,
+    /// Fail with [`SkeletorError::InvalidFileName`]
+    #[default]
+    Error,
+    /// Record the name with invalid bytes replaced by U+FFFD, as older versions did
+    Lossy,
+}
+
+impl InvalidNamePolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "skip" => Some(Self::Skip),
+            "error" => Some(Self::Error),
+            "lossy" => Some(Self::Lossy),
+            _ => None,
+        }
+    }
+}
+
+/// Walks `base` and builds the snapshot structure plus the list of binary files.
+///
+/// Shorthand for [`traverse_directory_with`] with otherwise default [`TraverseOptions`].
+#[allow(dead_code)]
 pub fn traverse_directory(
     base: &Path,
     root: &Path,
@@ -162,75 +1013,395 @@ pub fn traverse_directory(
     ignore: Option<&Gitignore>,
     verbose: bool,
 ) -> Result<(Value, Vec<String>), SkeletorError> {
-    let mut mapping = serde_yaml::Mapping::new();
-    let mut binaries: Vec<String> = vec![];
-
-    for entry in fs::read_dir(base).map_err(|e| {
-        match e.kind() {
-            std::io::ErrorKind::NotFound => SkeletorError::directory_not_found(base.to_path_buf()),
-            _ => SkeletorError::from_io_with_context(e, base.to_path_buf())
-        }
-    })? {
-        let entry = entry?;
-        let file_name = entry.file_name();
-        let file_name_string = file_name.to_string_lossy().into_owned();
-        let path = entry.path();
-
-        // ✅ Normalize path to relative string
-        let mut relative_str = path
-            .strip_prefix(root)
-            .unwrap_or(&path)
-            .to_string_lossy()
-            .replace("\\", "/");
-
-        // ✅ If it's a directory, append `/` to match `.gitignore`
-        if path.is_dir() {
-            relative_str.push('/');
-        }
-
-        if let Some(matcher) = ignore {
-            let is_ignored = matcher
-                .matched_path_or_any_parents(Path::new(&relative_str), path.is_dir())
-                .is_ignore();
-            if is_ignored {
-                if verbose {
-                    // Use info logging for verbose ignore information
-                    info!("Ignoring: {:?}", relative_str);
+    let options = TraverseOptions {
+        include_contents,
+        ignore,
+        verbose,
+        ..Default::default()
+    };
+    traverse_directory_with(base, root, options, None)
+}
+
+/// How [`traverse_directory_with`] walks a directory and what it records
+#[derive(Default)]
+pub struct TraverseOptions<'a> {
+    /// Record file contents; without it every file is recorded empty
+    pub include_contents: bool,
+    /// Only files whose path relative to `root` or whose name matches have their
+    /// contents read, whatever `include_contents` says; the rest are recorded empty
+    pub contents_for: Option<&'a GlobSet>,
+    pub binary: BinaryDetection,
+    /// Files larger than this many bytes are recorded empty without being opened,
+    /// before binary detection runs, and reported as [`WalkEvent::Oversized`]
+    pub max_content_size: Option<u64>,
+    pub ignore: Option<&'a Gitignore>,
+    /// Log each ignored entry
+    pub verbose: bool,
+    /// Walk into linked files and directories instead of recording them as [`symlink_node`]s;
+    /// a link leading back into one of its own ancestors fails with [`SkeletorError::SymlinkCycle`]
+    pub follow_symlinks: bool,
+    /// Directories this many levels below `base` are recorded as empty mappings without
+    /// being read; `Some(0)` captures just the immediate children
+    pub max_depth: Option<usize>,
+    pub on_invalid_name: InvalidNamePolicy,
+}
+
+/// Walks `base` as `options` describe and builds the snapshot structure plus the list of
+/// binary files. Symlinks (to files, directories, or dangling) are recorded as
+/// [`symlink_node`]s unless `follow_symlinks` is set.
+/// `progress`, when given, is called with a [`WalkEvent`] for each entry as it is visited
+/// (ignored entries excluded) and for each file whose contents could not be read.
+#[allow(dead_code)]
+pub fn traverse_directory_with(
+    base: &Path,
+    root: &Path,
+    options: TraverseOptions,
+    progress: Option<&mut dyn FnMut(WalkEvent)>,
+) -> Result<(Value, Vec<String>), SkeletorError> {
+    DirectoryWalker::new(root, options, progress).walk(base, 0)
+}
+
+/// The walk behind [`traverse_directory_with`], also driven step by step by streamed snapshots
+pub(crate) struct DirectoryWalker<'a, 'p> {
+    root: &'a Path,
+    options: TraverseOptions<'a>,
+    progress: Option<&'p mut dyn FnMut(WalkEvent)>,
+    /// Canonical paths of the directories being walked, tracked only when following symlinks
+    ancestors: Vec<PathBuf>,
+    previous: Option<&'a PreviousSnapshot>,
+    skip_unreadable_dirs: bool,
+    /// A file to leave out, relative to `root`
+    excluded: Option<&'a str>,
+}
+
+/// What an earlier snapshot of the same source recorded, so an appending walk can
+/// reuse the contents of files that have not changed since
+pub(crate) struct PreviousSnapshot {
+    pub directories: Value,
+    /// Paths the earlier snapshot listed as binary, relative to the source
+    pub binary_files: HashSet<String>,
+    /// Its `updated` time; files modified after it are read again
+    pub updated: SystemTime,
+}
+
+impl PreviousSnapshot {
+    /// Files modified this close to `updated` are read again: filesystem timestamps can be
+    /// coarser than the clock, so an edit just after a snapshot may carry an earlier mtime
+    const MTIME_SLACK: Duration = Duration::from_secs(2);
+
+    /// The recorded contents of `entry`, when it is a file unmodified since `updated`
+    fn unchanged(&self, entry: &WalkEntry) -> Option<&str> {
+        let modified = fs::metadata(&entry.path).and_then(|metadata| metadata.modified()).ok()?;
+        if modified + Self::MTIME_SLACK > self.updated {
+            return None;
+        }
+        let recorded = entry
+            .relative
+            .split('/')
+            .try_fold(&self.directories, |node, name| node.as_mapping()?.get(name))?;
+        file_content(recorded)
+    }
+}
+
+/// A directory entry the walker keeps, classified but not yet read
+pub(crate) struct WalkEntry {
+    pub name: String,
+    pub path: PathBuf,
+    /// Path relative to the walk's root, `/`-separated, with a trailing `/` for directories
+    relative: String,
+    pub kind: WalkEntryKind,
+}
+
+pub(crate) enum WalkEntryKind {
+    /// A link recorded as a [`symlink_node`] to this target
+    Symlink(PathBuf),
+    /// A directory at the `max_depth` cutoff, recorded as an empty mapping
+    CutoffDir,
+    Dir,
+    File,
+}
+
+impl WalkEntryKind {
+    pub(crate) fn is_dir(&self) -> bool {
+        matches!(self, Self::CutoffDir | Self::Dir)
+    }
+}
+
+impl<'a, 'p> DirectoryWalker<'a, 'p> {
+    pub(crate) fn new(
+        root: &'a Path,
+        options: TraverseOptions<'a>,
+        progress: Option<&'p mut dyn FnMut(WalkEvent)>,
+    ) -> Self {
+        Self {
+            root,
+            options,
+            progress,
+            ancestors: Vec::new(),
+            previous: None,
+            skip_unreadable_dirs: false,
+            excluded: None,
+        }
+    }
+
+    /// Takes the contents of files unchanged since `previous` from it instead of reading them
+    pub(crate) fn reusing(mut self, previous: Option<&'a PreviousSnapshot>) -> Self {
+        self.previous = previous;
+        self
+    }
+
+    /// With `skip`, directories that cannot be listed for lack of permission are reported as
+    /// [`WalkEvent::Unreadable`] and left out instead of failing the walk
+    pub(crate) fn skipping_unreadable_dirs(mut self, skip: bool) -> Self {
+        self.skip_unreadable_dirs = skip;
+        self
+    }
+
+    /// Leaves out the file at `relative` (`/`-separated, relative to the root), such as the
+    /// snapshot's own output when it is written inside the source
+    pub(crate) fn excluding(mut self, relative: Option<&'a str>) -> Self {
+        self.excluded = relative;
+        self
+    }
+
+    pub(crate) fn walk(&mut self, base: &Path, depth: usize) -> Result<(Value, Vec<String>), SkeletorError> {
+        self.enter(base)?;
+
+        let mut mapping = serde_yaml::Mapping::new();
+        let mut binaries: Vec<String> = vec![];
+
+        for entry in self.list(base, depth)? {
+            let value = match entry.kind {
+                WalkEntryKind::Symlink(ref target) => symlink_node(target),
+                // At the cutoff: keep the directory's place in the shape, not its contents
+                WalkEntryKind::CutoffDir => Value::Mapping(serde_yaml::Mapping::new()),
+                WalkEntryKind::Dir => {
+                    let (sub_yaml, mut sub_binaries) = self.walk(&entry.path, depth + 1)?;
+                    binaries.append(&mut sub_binaries);
+                    sub_yaml
                 }
-                continue;
+                WalkEntryKind::File => match self.read_file(&entry, &mut binaries) {
+                    Some(contents) => Value::String(contents),
+                    None => continue,
+                },
+            };
+            mapping.insert(Value::String(entry.name), value);
+        }
+
+        self.leave();
+        Ok((Value::Mapping(mapping), binaries))
+    }
+
+    /// Marks `base` as being walked, failing if following symlinks led back into it
+    pub(crate) fn enter(&mut self, base: &Path) -> Result<(), SkeletorError> {
+        if self.options.follow_symlinks {
+            let canonical = fs::canonicalize(base).unwrap_or_else(|_| base.to_path_buf());
+            if self.ancestors.contains(&canonical) {
+                return Err(SkeletorError::SymlinkCycle {
+                    path: base.to_path_buf(),
+                    target: canonical,
+                });
             }
+            self.ancestors.push(canonical);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn leave(&mut self) {
+        if self.options.follow_symlinks {
+            self.ancestors.pop();
         }
+    }
 
-        if path.is_dir() {
-            let (sub_yaml, mut sub_binaries) = traverse_directory(&path, root, include_contents, ignore, verbose)?;
-            mapping.insert(Value::String(file_name_string), sub_yaml);
-            binaries.append(&mut sub_binaries);
-        } else if path.is_file() {
-            if include_contents {
-                match fs::read(&path) {
-                    Ok(bytes) => {
-                        if let Ok(text) = String::from_utf8(bytes.clone()) {
-                            mapping.insert(Value::String(file_name_string), Value::String(text));
-                        } else {
-                            binaries.push(relative_str.clone());
-                            mapping.insert(
-                                Value::String(file_name_string),
-                                Value::String(String::new()),
-                            );
+    /// Lists the entries of `base` to record, in directory order, reporting each as visited.
+    /// Ignored entries, the excluded file, skipped invalid names, skipped unreadable
+    /// directories and anything that is neither a file, a directory nor a recorded
+    /// symlink are left out.
+    pub(crate) fn list(&mut self, base: &Path, depth: usize) -> Result<Vec<WalkEntry>, SkeletorError> {
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(base).map_err(|e| {
+            match e.kind() {
+                std::io::ErrorKind::NotFound => SkeletorError::directory_not_found(base.to_path_buf()),
+                _ => SkeletorError::from_io_with_context(e, base.to_path_buf())
+            }
+        })? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let path = entry.path();
+            if file_name.to_str().is_none() {
+                match self.options.on_invalid_name {
+                    InvalidNamePolicy::Error => return Err(SkeletorError::InvalidFileName { path }),
+                    InvalidNamePolicy::Skip => {
+                        warn!("Skipping {:?}: name is not valid UTF-8", path);
+                        if let Some(callback) = self.progress.as_mut() {
+                            callback(WalkEvent::InvalidName(&path));
                         }
+                        continue;
                     }
-                    Err(e) => {
-                        // Use warning log for file read errors instead of direct eprintln
-                        warn!("Error reading file {:?}: {}", path, e);
+                    InvalidNamePolicy::Lossy => {}
+                }
+            }
+            let file_name_string = file_name.to_string_lossy().into_owned();
+
+            // ✅ Normalize path to relative string
+            let mut relative_str = path
+                .strip_prefix(self.root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace("\\", "/");
+
+            if self.excluded == Some(relative_str.as_str()) {
+                info!("Leaving out {:?}: it is the snapshot's own output", path);
+                continue;
+            }
+
+            if let Some(matcher) = self.options.ignore {
+                // Matched without a trailing `/`: `is_dir` already tells `dir/` patterns apart,
+                // and with the slash `build/*` would match `build/` itself, leaving nothing
+                // for a later `!build/keep.txt` to re-include
+                let matched = matcher.matched_path_or_any_parents(Path::new(&relative_str), path.is_dir());
+                if let Match::Ignore(pattern) = matched {
+                    if self.options.verbose {
+                        // Use info logging for verbose ignore information
+                        info!("Ignoring: {:?}", relative_str);
                     }
+                    if let Some(callback) = self.progress.as_mut() {
+                        callback(WalkEvent::Ignored(&path, pattern.original()));
+                    }
+                    continue;
                 }
+            }
+
+            if path.is_dir() {
+                relative_str.push('/');
+            }
+
+            if let Some(callback) = self.progress.as_mut() {
+                callback(WalkEvent::Visited(&path));
+            }
+
+            let kind = if !self.options.follow_symlinks && entry.file_type()?.is_symlink() {
+                let target = fs::read_link(&path)
+                    .map_err(|e| SkeletorError::from_io_with_context(e, path.clone()))?;
+                info!("Recording symlink {:?} -> {:?}", path, target);
+                WalkEntryKind::Symlink(target)
+            } else if path.is_dir() && self.options.max_depth.is_some_and(|max| depth >= max) {
+                WalkEntryKind::CutoffDir
+            } else if path.is_dir() {
+                if self.skip_unreadable_dirs {
+                    if let Err(e) = fs::read_dir(&path) {
+                        if e.kind() == std::io::ErrorKind::PermissionDenied {
+                            warn!("Skipping directory {:?}: {}", path, e);
+                            if let Some(callback) = self.progress.as_mut() {
+                                callback(WalkEvent::Unreadable(&path, &e));
+                            }
+                            continue;
+                        }
+                    }
+                }
+                WalkEntryKind::Dir
+            } else if path.is_file() {
+                WalkEntryKind::File
             } else {
-                mapping.insert(Value::String(file_name_string), Value::String(String::new()));
+                continue;
+            };
+            entries.push(WalkEntry {
+                name: file_name_string,
+                path,
+                relative: relative_str,
+                kind,
+            });
+        }
+
+        Ok(entries)
+    }
+
+    /// Reads a listed file's recorded contents: empty when contents are not captured or the
+    /// file is oversized or binary (binary files are added to `binaries`), `None` when it
+    /// could not be read and is left out.
+    pub(crate) fn read_file(&mut self, entry: &WalkEntry, binaries: &mut Vec<String>) -> Option<String> {
+        if let Some(previous) = self.previous {
+            if let Some(contents) = previous.unchanged(entry) {
+                if previous.binary_files.contains(&entry.relative) {
+                    binaries.push(entry.relative.clone());
+                }
+                return Some(contents.to_string());
+            }
+        }
+        let path = &entry.path;
+        let reads_contents = self.reads_contents(&entry.relative, &entry.name);
+        if let Some(len) = self.oversized_len(path).filter(|_| reads_contents) {
+            info!("Not reading {:?}: {} bytes is over the content size limit", path, len);
+            if let Some(callback) = self.progress.as_mut() {
+                callback(WalkEvent::Oversized(path));
+            }
+            Some(String::new())
+        } else if reads_contents {
+            match self.options.binary.read_text(path) {
+                Ok(Some(text)) => Some(text),
+                Ok(None) => {
+                    binaries.push(entry.relative.clone());
+                    Some(String::new())
+                }
+                Err(e) => {
+                    // Use warning log for file read errors instead of direct eprintln
+                    warn!("Error reading file {:?}: {}", path, e);
+                    if let Some(callback) = self.progress.as_mut() {
+                        callback(WalkEvent::Unreadable(path, &e));
+                    }
+                    None
+                }
+            }
+        } else {
+            Some(String::new())
+        }
+    }
+
+    /// Classifies a listed file as [`Self::read_file`] does, adding it to `binaries` when it
+    /// is binary, but without keeping its contents. Returns `false` when it could not be
+    /// read and is left out.
+    pub(crate) fn inspect_file(&mut self, entry: &WalkEntry, binaries: &mut Vec<String>) -> bool {
+        let path = &entry.path;
+        if !self.reads_contents(&entry.relative, &entry.name) {
+            return true;
+        }
+        if self.oversized_len(path).is_some() {
+            if let Some(callback) = self.progress.as_mut() {
+                callback(WalkEvent::Oversized(path));
             }
+            return true;
         }
+        match self.options.binary.is_binary(path) {
+            Ok(binary) => {
+                if binary {
+                    binaries.push(entry.relative.clone());
+                }
+                true
+            }
+            Err(e) => {
+                warn!("Error reading file {:?}: {}", path, e);
+                if let Some(callback) = self.progress.as_mut() {
+                    callback(WalkEvent::Unreadable(path, &e));
+                }
+                false
+            }
+        }
+    }
+
+    /// The file's size when it is over `max_content_size`
+    fn oversized_len(&self, path: &Path) -> Option<u64> {
+        let max = self.options.max_content_size?;
+        let len = fs::metadata(path).ok()?.len();
+        (len > max).then_some(len)
     }
 
-    Ok((Value::Mapping(mapping), binaries))
+    fn reads_contents(&self, relative: &str, name: &str) -> bool {
+        match self.options.contents_for {
+            Some(globs) => globs.is_match(relative) || globs.is_match(name),
+            None => self.options.include_contents,
+        }
+    }
 }
 
 /// Computes statistics (number of files and directories) from a YAML structure.
@@ -241,7 +1412,7 @@ pub fn compute_stats(yaml: &Value) -> (usize, usize) {
     if let Some(map) = yaml.as_mapping() {
         for (_, v) in map {
             match v {
-                Value::Mapping(_) => {
+                Value::Mapping(_) if file_ref_path(v).is_none() => {
                     dirs += 1;
                     let (sub_files, sub_dirs) = compute_stats(v);
                     files += sub_files;
@@ -250,6 +1421,9 @@ pub fn compute_stats(yaml: &Value) -> (usize, usize) {
                 Value::String(_) => {
                     files += 1;
                 }
+                _ if file_ref_path(v).is_some() || file_content(v).is_some() => {
+                    files += 1;
+                }
                 _ => {}
             }
         }
@@ -281,15 +1455,15 @@ mod tests {
 
         let expected_tasks = vec![
             Task::Dir(Path::new("./src").to_path_buf()),
-            Task::File(
-                Path::new("./src/index.js").to_path_buf(),
-                "console.log('Hello, world!');".to_string(),
-            ),
             Task::Dir(Path::new("./src/components").to_path_buf()),
             Task::File(
                 Path::new("./src/components/Header.js").to_path_buf(),
                 "// Header component".to_string(),
             ),
+            Task::File(
+                Path::new("./src/index.js").to_path_buf(),
+                "console.log('Hello, world!');".to_string(),
+            ),
         ];
 
         assert_eq!(tasks, expected_tasks);
@@ -320,6 +1494,36 @@ mod tests {
         assert!(test_dir.join("src/components/Header.js").exists());
     }
 
+    #[test]
+    fn test_traverse_directory_reports_progress() {
+        let fs = TestFileSystem::new();
+        let test_dir = &fs.root_path;
+        fs.create_file("src/index.js", "");
+        fs.create_file("debug.log", "");
+
+        let ignore = {
+            let mut builder = GitignoreBuilder::new(test_dir);
+            builder.add_line(None, "*.log").unwrap();
+            builder.build().unwrap()
+        };
+        let mut visited = Vec::new();
+        let options = TraverseOptions { ignore: Some(&ignore), ..Default::default() };
+        traverse_directory_with(
+            test_dir,
+            test_dir,
+            options,
+            Some(&mut |event: WalkEvent| {
+                if let WalkEvent::Visited(path) = event {
+                    visited.push(path.strip_prefix(test_dir).unwrap().to_path_buf());
+                }
+            }),
+        )
+        .unwrap();
+
+        visited.sort();
+        assert_eq!(visited, vec![PathBuf::from("src"), PathBuf::from("src/index.js")]);
+    }
+
     #[test]
     fn test_traverse_directory() {
         let fs = TestFileSystem::new();
@@ -421,6 +1625,40 @@ mod tests {
         assert_eq!(content, "original content");
     }
 
+    #[test]
+    fn test_atomic_writes_leave_no_temp_files() {
+        let fs = TestFileSystem::new();
+        let test_dir = &fs.root_path;
+        let existing = fs.create_file("out/existing.sh", "echo old");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&existing, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let tasks = vec![
+            Task::Dir(test_dir.join("out")),
+            Task::File(existing.clone(), "echo new".to_string()),
+            Task::File(test_dir.join("out/new.txt"), "fresh".to_string()),
+        ];
+        let result = create_files_and_directories(&tasks, true).unwrap();
+
+        assert_eq!(result.files_created, 2);
+        assert_file_content(&existing, "echo new");
+        assert_file_content(test_dir.join("out/new.txt"), "fresh");
+        let mut names: Vec<String> = std::fs::read_dir(test_dir.join("out"))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["existing.sh", "new.txt"]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(std::fs::metadata(&existing).unwrap().permissions().mode() & 0o777, 0o755);
+        }
+    }
+
     #[test]
     fn test_create_files_and_directories_with_overwrite() {
         let fs = TestFileSystem::new();
@@ -449,6 +1687,220 @@ mod tests {
         assert_eq!(content, "overwritten content");
     }
 
+    #[test]
+    fn test_create_files_and_directories_honors_keep_file() {
+        let fs = TestFileSystem::new();
+        let test_dir = &fs.root_path;
+
+        fs.create_file(".skeletorkeep", "# local customizations\n.env\nconfig/*.local\n");
+        fs.create_file(".env", "SECRET=local");
+        fs.create_file("config/app.local", "local");
+
+        let tasks = vec![
+            Task::File(test_dir.join(".env"), "SECRET=template".to_string()),
+            Task::File(test_dir.join("config/app.local"), "template".to_string()),
+            Task::File(test_dir.join("config/app.toml"), "template".to_string()),
+        ];
+
+        let keep = load_keep_matcher(test_dir).unwrap();
+        assert!(keep.is_some());
+        let options = FileWriteOptions { overwrite: true, keep: keep.as_ref(), ..Default::default() };
+        let result = create_files_and_directories_with(&tasks, &options, &SilentReporter).unwrap();
+
+        assert_eq!(result.files_preserved, 2);
+        assert_eq!(result.preserved_files_list.len(), 2);
+        assert_eq!(result.files_created, 1);
+        assert_eq!(result.files_overwritten, 0);
+        assert_eq!(std::fs::read_to_string(test_dir.join(".env")).unwrap(), "SECRET=local");
+        assert!(test_dir.join("config/app.toml").exists());
+    }
+
+    #[test]
+    fn test_create_files_and_directories_update_skips_identical() {
+        let fs = TestFileSystem::new();
+        let test_dir = &fs.root_path;
+        fs.create_file("same.txt", "same");
+        fs.create_file("drift.txt", "local edit");
+
+        let tasks = vec![
+            Task::File(test_dir.join("same.txt"), "same".to_string()),
+            Task::File(test_dir.join("drift.txt"), "template".to_string()),
+            Task::File(test_dir.join("new.txt"), "new".to_string()),
+        ];
+        let options = FileWriteOptions { update: true, ..Default::default() };
+        let result = create_files_and_directories_with(&tasks, &options, &SilentReporter).unwrap();
+
+        assert_eq!(result.files_unchanged, 1);
+        assert!(result.unchanged_files_list[0].ends_with("same.txt"));
+        assert_eq!(result.files_overwritten, 1);
+        assert!(result.overwritten_files_list[0].ends_with("drift.txt"));
+        assert_eq!(result.files_created, 2);
+        assert_eq!(result.files_skipped, 0);
+        assert_eq!(std::fs::read_to_string(test_dir.join("drift.txt")).unwrap(), "template");
+    }
+
+    /// Records the per-task reporter calls made during creation
+    #[derive(Default)]
+    struct RecordingReporter {
+        events: std::cell::RefCell<Vec<String>>,
+    }
+
+    impl Reporter for RecordingReporter {
+        fn operation_start(&self, _operation: &str, _details: &str) {}
+        fn progress(&self, current: usize, total: usize, _message: &str) {
+            self.events.borrow_mut().push(format!("{}/{}", current, total));
+        }
+        fn task_success(&self, task: &Task) {
+            let name = task.path().file_name().unwrap().to_string_lossy().into_owned();
+            self.events.borrow_mut().push(format!("ok {}", name));
+        }
+        fn task_warning(&self, task: &Task, _error: &str) {
+            let name = task.path().file_name().unwrap().to_string_lossy().into_owned();
+            self.events.borrow_mut().push(format!("warn {}", name));
+        }
+        fn warning(&self, _message: &str) {}
+        fn tip(&self, _message: &str) {}
+        fn dry_run_preview(&self, _tasks: &[Task]) {}
+        fn dry_run_preview_verbose(&self, _tasks: &[Task], _verbose: bool) {}
+        fn dry_run_preview_comprehensive(&self, _tasks: &[Task], _verbose: bool, _binary_files: &[String], _ignore_patterns: &[String], _verb: &str) {}
+        fn verbose_operation_preview(&self, _tasks: &[Task]) {}
+        fn apply_complete(&self, _result: &crate::output::SimpleApplyResult, _verbose: bool) {}
+        fn snapshot_complete(&self, _result: &crate::output::SimpleSnapshotResult) {}
+    }
+
+    #[test]
+    fn test_creation_reports_each_task() {
+        let fs = TestFileSystem::new();
+        fs.create_file("serial/src/existing.rs", "keep me");
+        fs.create_file("parallel/src/existing.rs", "keep me");
+
+        for (root, parallel) in [("serial", false), ("parallel", true)] {
+            let base = fs.path(root);
+            let tasks = vec![
+                Task::Dir(base.join("src")),
+                Task::File(base.join("src/main.rs"), "fn main() {}".to_string()),
+                Task::File(base.join("src/existing.rs"), "new".to_string()),
+            ];
+            let reporter = RecordingReporter::default();
+            let create = if parallel {
+                create_files_and_directories_parallel
+            } else {
+                create_files_and_directories_with
+            };
+            create(&tasks, &FileWriteOptions::default(), &reporter).unwrap();
+
+            assert_eq!(
+                *reporter.events.borrow(),
+                ["1/3", "ok src", "2/3", "ok main.rs", "3/3", "warn existing.rs"],
+                "{} creation",
+                root
+            );
+        }
+    }
+
+    #[test]
+    fn test_fail_fast_stops_at_first_failure() {
+        let fs = TestFileSystem::new();
+        let out = fs.path("out");
+        // A file where a parent directory should be makes the first write fail
+        fs.create_file("out/blocked", "not a directory");
+        let tasks = vec![
+            Task::File(out.join("blocked/a.txt"), "a".to_string()),
+            Task::File(out.join("b.txt"), "b".to_string()),
+        ];
+
+        for create in [create_files_and_directories_with, create_files_and_directories_parallel] {
+            let result = create(&tasks, &FileWriteOptions::default(), &SilentReporter).unwrap();
+            assert_eq!(result.failed_files_list.len(), 1);
+            assert_eq!(result.failed_files_list[0].0, out.join("blocked/a.txt").display().to_string());
+            assert_file_content(out.join("b.txt"), "b");
+            std::fs::remove_file(out.join("b.txt")).unwrap();
+
+            let options = FileWriteOptions { fail_fast: true, ..Default::default() };
+            match create(&tasks, &options, &SilentReporter) {
+                Err(SkeletorError::TaskFailed { path, .. }) => {
+                    assert_eq!(path, out.join("blocked/a.txt").display().to_string())
+                }
+                other => panic!("Expected TaskFailed error, got {:?}", other.map(|_| ())),
+            }
+        }
+        // The serial path stops before writing anything else
+        assert!(!out.join("b.txt").exists());
+    }
+
+    #[test]
+    fn test_parallel_creation_matches_serial() {
+        let fs = TestFileSystem::new();
+        let build_tasks = |base: &Path| -> Vec<Task> {
+            let mut tasks = Vec::new();
+            for dir in 0..50 {
+                let dir_path = base.join(format!("dir{:02}", dir));
+                tasks.push(Task::Dir(dir_path.clone()));
+                for file in 0..100 {
+                    tasks.push(Task::File(
+                        dir_path.join(format!("file{:03}.txt", file)),
+                        format!("{}-{}", dir, file),
+                    ));
+                }
+            }
+            tasks
+        };
+        let serial_root = fs.create_dir("serial");
+        let parallel_root = fs.create_dir("parallel");
+        // Pre-existing files exercise the skipped list ordering
+        for root in ["serial", "parallel"] {
+            fs.create_file(format!("{}/dir07/file003.txt", root), "existing");
+            fs.create_file(format!("{}/dir42/file099.txt", root), "existing");
+        }
+
+        let serial = create_files_and_directories(&build_tasks(&serial_root), false).unwrap();
+        let options = FileWriteOptions { atomic: true, ..Default::default() };
+        let parallel = create_files_and_directories_parallel(&build_tasks(&parallel_root), &options, &SilentReporter).unwrap();
+
+        assert_eq!(serial.files_created, 4998);
+        assert_eq!(parallel.files_created, serial.files_created);
+        assert_eq!(parallel.dirs_created, serial.dirs_created);
+        assert_eq!(parallel.files_skipped, serial.files_skipped);
+        let relative = |list: &[String], root: &Path| -> Vec<String> {
+            list.iter()
+                .map(|p| Path::new(p).strip_prefix(root).unwrap().display().to_string())
+                .collect()
+        };
+        assert_eq!(
+            relative(&parallel.skipped_files_list, &parallel_root),
+            relative(&serial.skipped_files_list, &serial_root)
+        );
+        assert_eq!(
+            std::fs::read_to_string(parallel_root.join("dir49/file099.txt")).unwrap(),
+            "49-99"
+        );
+    }
+
+    #[test]
+    fn test_create_files_and_directories_applies_newline_policy() {
+        let fs = TestFileSystem::new();
+        let test_dir = &fs.root_path;
+
+        let policy_yaml: Value = serde_yaml::from_str(r#""*.md": ensure"#).unwrap();
+        let policy = NewlinePolicy::from_yaml(&policy_yaml, test_dir).unwrap();
+        let tasks = vec![
+            Task::File(test_dir.join("README.md"), "# Title".to_string()),
+            Task::File(test_dir.join("VERSION"), "1.0.0".to_string()),
+        ];
+
+        let options = FileWriteOptions { newline: Some(&policy), ..Default::default() };
+        create_files_and_directories_with(&tasks, &options, &SilentReporter).unwrap();
+
+        assert_eq!(std::fs::read_to_string(test_dir.join("README.md")).unwrap(), "# Title\n");
+        assert_eq!(std::fs::read_to_string(test_dir.join("VERSION")).unwrap(), "1.0.0");
+    }
+
+    #[test]
+    fn test_load_keep_matcher_missing_file() {
+        let fs = TestFileSystem::new();
+        assert!(load_keep_matcher(&fs.root_path).unwrap().is_none());
+    }
+
     #[test]
     fn test_create_files_and_directories_with_directory_creation_failure() {
         let fs = TestFileSystem::new();
@@ -548,6 +2000,79 @@ mod tests {
         }
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_traverse_directory_records_symlinked_dir_as_link() {
+        let fs = TestFileSystem::new();
+        let test_dir = &fs.root_path;
+
+        fs.create_file("dotfiles/config/app.conf", "setting=1");
+        std::os::unix::fs::symlink("dotfiles/config", test_dir.join("config")).unwrap();
+
+        let (yaml_structure, _) = traverse_directory(test_dir, test_dir, true, None, false).unwrap();
+        let link = yaml_structure.get("config").unwrap();
+        assert_eq!(symlink_target(link), Some("dotfiles/config"));
+        assert!(link.as_mapping().is_none());
+
+        // Following symlinks expands the linked directory instead
+        let follow = TraverseOptions { include_contents: true, follow_symlinks: true, ..Default::default() };
+        let (followed, _) = traverse_directory_with(test_dir, test_dir, follow, None).unwrap();
+        let expanded = followed.get("config").and_then(Value::as_mapping).unwrap();
+        assert!(expanded.contains_key(Value::String("app.conf".into())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_round_trip_through_snapshot_and_apply() {
+        let fs = TestFileSystem::new();
+        let source = fs.create_dir("source");
+        fs.create_file("source/v2.3.0/notes.txt", "release");
+        std::os::unix::fs::symlink("v2.3.0", source.join("latest")).unwrap();
+        std::os::unix::fs::symlink("v2.3.0/notes.txt", source.join("NOTES")).unwrap();
+        std::os::unix::fs::symlink("missing", source.join("dangling")).unwrap();
+
+        let (structure, _) = traverse_directory(&source, &source, true, None, false).unwrap();
+        assert_eq!(symlink_target(&structure["NOTES"]), Some("v2.3.0/notes.txt"));
+        assert_eq!(symlink_target(&structure["dangling"]), Some("missing"));
+
+        let target = fs.path("target");
+        let tasks = traverse_structure(&target, &structure).unwrap();
+        assert!(tasks.contains(&Task::Symlink(target.join("latest"), "v2.3.0".to_string())));
+
+        let result = create_files_and_directories(&tasks, false).unwrap();
+        assert_eq!(result.symlinks_created, 3);
+        assert_eq!(fs::read_link(target.join("latest")).unwrap(), Path::new("v2.3.0"));
+        assert_eq!(fs::read_to_string(target.join("NOTES")).unwrap(), "release");
+
+        // Existing links are skipped unless overwriting
+        let again = create_files_and_directories(&tasks, false).unwrap();
+        assert_eq!(again.symlinks_created, 0);
+        assert_eq!(again.files_skipped, 4);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_traverse_directory_detects_symlink_cycle() {
+        let fs = TestFileSystem::new();
+        let test_dir = &fs.root_path;
+
+        fs.create_file("a/file.txt", "a");
+        fs.create_file("b/file.txt", "b");
+        std::os::unix::fs::symlink("../b", test_dir.join("a/to_b")).unwrap();
+        std::os::unix::fs::symlink("../a", test_dir.join("b/to_a")).unwrap();
+
+        let follow = TraverseOptions { follow_symlinks: true, ..Default::default() };
+        match traverse_directory_with(test_dir, test_dir, follow, None) {
+            Err(SkeletorError::SymlinkCycle { path, .. }) => {
+                assert!(path.ends_with("to_b/to_a") || path.ends_with("to_a/to_b"));
+            }
+            other => panic!("Expected SymlinkCycle error, got {:?}", other),
+        }
+
+        // Recording links instead of following them never loops
+        assert!(traverse_directory(test_dir, test_dir, false, None, false).is_ok());
+    }
+
     #[test]
     fn test_traverse_structure_with_non_mapping_values() {
         let structure: Value = serde_yaml::from_str(
@@ -570,6 +2095,37 @@ mod tests {
         assert_eq!(dir_tasks.len(), 1); // Just the "src" directory
     }
 
+    #[test]
+    fn test_expand_braces_before_traversal() {
+        let structure: Value = serde_yaml::from_str(
+            r#"
+            "{src,tests}":
+              "mod_{1..2}.rs": "// module"
+            notes.txt: "plain"
+            "#,
+        )
+        .unwrap();
+
+        let expanded = expand::expand_braces(&structure).unwrap();
+        let paths: Vec<PathBuf> = traverse_structure(Path::new("out"), &expanded)
+            .unwrap()
+            .iter()
+            .map(|task| task.path().to_path_buf())
+            .collect();
+        let expected: Vec<PathBuf> = ["notes.txt", "src", "src/mod_1.rs", "src/mod_2.rs", "tests", "tests/mod_1.rs", "tests/mod_2.rs"]
+            .iter()
+            .map(|path| Path::new("out").join(path))
+            .collect();
+        assert_eq!(paths, expected);
+
+        let malformed: Value = serde_yaml::from_str("\"file_{1..x}.txt\": ''").unwrap();
+        assert!(matches!(expand::expand_braces(&malformed), Err(SkeletorError::Config(_))));
+        // Without the opt-in, braces are part of the name
+        let literal = traverse_structure(Path::new("out"), &structure).unwrap();
+        assert_eq!(literal[0].path(), Path::new("out/notes.txt"));
+        assert_eq!(literal[1].path(), Path::new("out/{src,tests}"));
+    }
+
     #[test]
     fn test_traverse_structure_empty_input() {
         let empty_structure = Value::Mapping(serde_yaml::Mapping::new());
@@ -577,6 +2133,51 @@ mod tests {
         assert!(tasks.is_empty());
     }
 
+    #[test]
+    fn test_empty_directories_round_trip_distinct_from_empty_files() {
+        let fs = TestFileSystem::new();
+        let structure: Value = serde_yaml::from_str("logs: {}\nsrc:\n  module.rs: \"\"\n  cache: {}\n").unwrap();
+
+        let output = fs.path("output");
+        let tasks = traverse_structure(&output, &structure).unwrap();
+        assert!(tasks.contains(&Task::Dir(output.join("logs"))));
+        create_files_and_directories(&tasks, false).unwrap();
+        assert!(output.join("logs").is_dir());
+        assert!(output.join("src/cache").is_dir());
+        assert!(output.join("src/module.rs").is_file());
+        assert_file_content(output.join("src/module.rs"), "");
+
+        // Snapshotting the result records each kind the same way again
+        let (snapshot, _) = traverse_directory(&output, &output, true, None, false).unwrap();
+        assert_eq!(snapshot, structure);
+    }
+
+    #[test]
+    fn test_source_nodes_load_external_files() {
+        let fs = TestFileSystem::new();
+        fs.create_file("templates/snippets/main.rs", "fn main() {}\n");
+        let structure: Value = serde_yaml::from_str(
+            "src:\n  main.rs: !source ./snippets/main.rs\n  source:\n    notes.md: not a reference\n",
+        )
+        .unwrap();
+        assert_eq!(compute_stats(&structure), (2, 2));
+
+        let resolved = resolve_file_refs(&structure, &fs.path("templates")).unwrap();
+        let output = fs.path("output");
+        let tasks = traverse_structure(&output, &resolved).unwrap();
+        assert!(tasks.contains(&Task::File(output.join("src/main.rs"), "fn main() {}\n".to_string())));
+        assert!(tasks.contains(&Task::Dir(output.join("src/source"))));
+
+        // Unresolved references are never mistaken for directories
+        assert!(traverse_structure(&output, &structure).is_err());
+
+        let missing: Value = serde_yaml::from_str("LICENSE: !source ./snippets/LICENSE\n").unwrap();
+        assert!(matches!(
+            resolve_file_refs(&missing, &fs.path("templates")),
+            Err(SkeletorError::FileNotFound { path }) if path.ends_with("snippets/LICENSE")
+        ));
+    }
+
     #[test]
     fn test_traverse_structure_rejects_unsafe_paths() {
         let structure: Value = serde_yaml::from_str(
@@ -591,6 +2192,59 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_traverse_structure_batched_bounds_batches_for_huge_configs() {
+        // 200k top-level keys would hold 200k tasks when collected at once
+        let mut structure = serde_yaml::Mapping::new();
+        for i in 0..200_000 {
+            structure.insert(Value::String(format!("f{}", i)), Value::String(String::new()));
+        }
+        let structure = Value::Mapping(structure);
+
+        let mut batches = 0;
+        let mut largest = 0;
+        let total = traverse_structure_batched(Path::new("out"), &structure, 4096, |batch| {
+            batches += 1;
+            largest = largest.max(batch.len());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(total, 200_000);
+        assert_eq!(largest, 4096);
+        assert_eq!(batches, (200_000 + 4095) / 4096);
+    }
+
+    #[test]
+    fn test_batched_creation_matches_unbatched() {
+        let fs = TestFileSystem::new();
+        let structure: Value = serde_yaml::from_str(
+            r#"
+            src:
+              lib.rs: "pub fn demo() {}"
+              nested:
+                deep:
+                  mod.rs: "// deep"
+            README.md: "readme"
+            latest: !symlink src
+            "#,
+        )
+        .unwrap();
+        fs.create_file("batched/README.md", "existing");
+
+        let (result, total) =
+            create_files_and_directories_batched(&fs.path("batched"), &structure, 2, false, None, true).unwrap();
+
+        assert_eq!(total, traverse_structure(Path::new("."), &structure).unwrap().len());
+        assert_eq!(result.dirs_created, 3);
+        assert_eq!(result.files_created, 2);
+        assert_eq!(result.files_skipped, 1);
+        assert_file_content(fs.path("batched/src/nested/deep/mod.rs"), "// deep");
+        assert_file_content(fs.path("batched/README.md"), "existing");
+        #[cfg(unix)]
+        assert_eq!(result.symlinks_created, 1);
+    }
+
     #[test] 
     fn test_compute_stats_empty_structure() {
         let empty_yaml = Value::Mapping(serde_yaml::Mapping::new());
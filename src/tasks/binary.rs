@@ -0,0 +1,210 @@
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// How much of a file [`DetectionStrategy::NullByteSniff`] inspects
+const SNIFF_LEN: usize = 8 * 1024;
+
+/// How a file's contents are judged to be text or binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DetectionStrategy {
+    /// Read the whole file; anything that is not valid UTF-8 is binary
+    Utf8,
+    /// Treat a NUL byte in the first 8 KiB as binary without reading further;
+    /// other files are read in full and must still be valid UTF-8
+    NullByteSniff,
+}
+
+impl DetectionStrategy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "utf8" => Some(Self::Utf8),
+            "null-byte" => Some(Self::NullByteSniff),
+            _ => None,
+        }
+    }
+}
+
+/// Policy deciding which files snapshot records as binary (empty) instead of reading as text.
+///
+/// Extension lists are matched case-insensitively without the leading dot.
+/// The default reproduces the original behavior: every file is read and checked as UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BinaryDetection {
+    pub strategy: DetectionStrategy,
+    /// Always binary, never opened (e.g. `png`, `mp4`)
+    pub binary_extensions: Vec<String>,
+    /// Always read in full, skipping the NUL-byte sniff (still binary if not UTF-8)
+    pub text_extensions: Vec<String>,
+    /// Files larger than this many bytes are recorded as binary without being read
+    pub max_size: Option<u64>,
+}
+
+impl Default for BinaryDetection {
+    fn default() -> Self {
+        Self {
+            strategy: DetectionStrategy::Utf8,
+            binary_extensions: Vec::new(),
+            text_extensions: Vec::new(),
+            max_size: None,
+        }
+    }
+}
+
+impl BinaryDetection {
+    /// Reads `path` as text, or returns `None` when the policy classifies it as binary.
+    pub fn read_text(&self, path: &Path) -> io::Result<Option<String>> {
+        let Some((mut file, sniff)) = self.open_unless_binary(path)? else {
+            return Ok(None);
+        };
+
+        let mut bytes = Vec::new();
+        if sniff {
+            (&mut file).take(SNIFF_LEN as u64).read_to_end(&mut bytes)?;
+            if bytes.contains(&0) {
+                return Ok(None);
+            }
+        }
+        file.read_to_end(&mut bytes)?;
+        Ok(String::from_utf8(bytes).ok())
+    }
+
+    /// Whether [`Self::read_text`] would classify `path` as binary, reading it in chunks so
+    /// its contents are never held in memory
+    pub fn is_binary(&self, path: &Path) -> io::Result<bool> {
+        let Some((mut file, sniff)) = self.open_unless_binary(path)? else {
+            return Ok(true);
+        };
+
+        // Room for one chunk plus the start of a character split across chunks
+        let mut buffer = [0u8; SNIFF_LEN + 3];
+        let mut pending = 0;
+        let mut offset = 0;
+        loop {
+            let read = match file.read(&mut buffer[pending..]) {
+                Ok(read) => read,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            if read == 0 {
+                // A character cut off by the end of the file is not valid UTF-8
+                return Ok(pending > 0);
+            }
+            if sniff && offset < SNIFF_LEN {
+                let sniffed = read.min(SNIFF_LEN - offset);
+                if buffer[pending..pending + sniffed].contains(&0) {
+                    return Ok(true);
+                }
+            }
+            offset += read;
+
+            let filled = pending + read;
+            pending = match std::str::from_utf8(&buffer[..filled]) {
+                Ok(_) => 0,
+                Err(e) if e.error_len().is_none() => {
+                    buffer.copy_within(e.valid_up_to()..filled, 0);
+                    filled - e.valid_up_to()
+                }
+                Err(_) => return Ok(true),
+            };
+        }
+    }
+
+    /// Opens `path` unless its extension or size already make it binary, saying whether
+    /// its start should be sniffed for NUL bytes
+    fn open_unless_binary(&self, path: &Path) -> io::Result<Option<(File, bool)>> {
+        let extension = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        let listed = |list: &[String]| {
+            extension
+                .as_deref()
+                .is_some_and(|ext| list.iter().any(|item| item.eq_ignore_ascii_case(ext)))
+        };
+
+        if listed(&self.binary_extensions) {
+            return Ok(None);
+        }
+        let file = File::open(path)?;
+        if self
+            .max_size
+            .is_some_and(|max| file.metadata().is_ok_and(|metadata| metadata.len() > max))
+        {
+            return Ok(None);
+        }
+        let sniff = self.strategy == DetectionStrategy::NullByteSniff && !listed(&self.text_extensions);
+        Ok(Some((file, sniff)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_binary_detection_policies() {
+        let fs = TestFileSystem::new();
+        let text = fs.create_file("notes.txt", &"plain text\n".repeat(2000));
+        let sniffed = fs.create_binary_file("blob.dat", &[b'a', 0, b'b']);
+        let latin1 = fs.create_binary_file("legacy.txt", &[b'c', 0xE9, b'\n']);
+        let image = fs.create_file("logo.PNG", "not really a png");
+
+        let default = BinaryDetection::default();
+        assert!(default.read_text(&text).unwrap().is_some_and(|t| t.len() == 22000));
+        assert_eq!(default.read_text(&latin1).unwrap(), None);
+        assert_eq!(default.read_text(&image).unwrap().as_deref(), Some("not really a png"));
+
+        let policy = BinaryDetection {
+            strategy: DetectionStrategy::NullByteSniff,
+            binary_extensions: vec!["png".to_string()],
+            max_size: Some(1024),
+            ..BinaryDetection::default()
+        };
+        assert_eq!(policy.read_text(&sniffed).unwrap(), None);
+        assert_eq!(policy.read_text(&image).unwrap(), None);
+        // Over the size cap, so never read
+        assert_eq!(policy.read_text(&text).unwrap(), None);
+        // No NUL byte, but still not UTF-8
+        assert_eq!(policy.read_text(&latin1).unwrap(), None);
+    }
+
+    #[test]
+    fn test_is_binary_agrees_with_read_text() {
+        let fs = TestFileSystem::new();
+        // 'é' straddles the first chunk boundary, and a NUL byte past the sniffed start is valid UTF-8
+        let mut split = vec![b'a'; SNIFF_LEN - 1];
+        split.extend_from_slice("é and more\n".as_bytes());
+        split.push(0);
+        let files = [
+            fs.create_file("notes.txt", &"plain text\n".repeat(2000)),
+            fs.create_binary_file("split.txt", &split),
+            fs.create_binary_file("blob.dat", &[b'a', 0, b'b']),
+            fs.create_binary_file("legacy.txt", &[b'c', 0xE9, b'\n']),
+            fs.create_binary_file("cut.txt", &"café".as_bytes()[..4]),
+            fs.create_file("logo.PNG", "not really a png"),
+            fs.create_file("empty.txt", ""),
+        ];
+
+        let policies = [
+            BinaryDetection::default(),
+            BinaryDetection {
+                strategy: DetectionStrategy::NullByteSniff,
+                binary_extensions: vec!["png".to_string()],
+                max_size: Some(16 * 1024),
+                ..BinaryDetection::default()
+            },
+        ];
+        for policy in &policies {
+            for file in &files {
+                assert_eq!(
+                    policy.is_binary(file).unwrap(),
+                    policy.read_text(file).unwrap().is_none(),
+                    "{:?} under {:?}",
+                    file,
+                    policy.strategy
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,154 @@
+use crate::errors::SkeletorError;
+use serde_yaml::{Mapping, Value};
+
+/// Most names one key may expand to, so a typo like `{1..1000000}` fails instead of
+/// planning a million files
+const MAX_EXPANSION: usize = 10_000;
+
+/// Whether a config document opts in to brace expansion with a top-level `expand_braces: true`.
+///
+/// Off by default, so names that really contain braces (as in snapshots of cookiecutter
+/// templates) apply as written.
+pub fn braces_enabled(yaml_doc: &Value) -> Result<bool, SkeletorError> {
+    match yaml_doc.get("expand_braces") {
+        None => Ok(false),
+        Some(Value::Bool(enabled)) => Ok(*enabled),
+        Some(_) => Err(SkeletorError::invalid_config_entry("expand_braces", "expected true or false")),
+    }
+}
+
+/// Copies a `directories` tree with every key expanded by [`expand_key`], each name
+/// sharing the entry's value. Where two keys expand to the same name, the later wins.
+pub fn expand_braces(directories: &Value) -> Result<Value, SkeletorError> {
+    let Value::Mapping(map) = directories else {
+        return Ok(directories.clone());
+    };
+    let mut expanded = Mapping::with_capacity(map.len());
+    for (key, value) in map {
+        let value = expand_braces(value)?;
+        match key.as_str() {
+            Some(name) if name.contains('{') => {
+                for name in expand_key(name)? {
+                    expanded.insert(Value::String(name), value.clone());
+                }
+            }
+            _ => {
+                expanded.insert(key.clone(), value);
+            }
+        }
+    }
+    Ok(Value::Mapping(expanded))
+}
+
+/// Expands shell-style brace groups in a `directories` key: `file_{1..3}.txt` gives
+/// `file_1.txt`, `file_2.txt` and `file_3.txt`, and `{src,tests}` gives `src` and `tests`.
+///
+/// Ranges are inclusive, may count down, and are zero-padded when an end is written with a
+/// leading zero (`{01..10}`). Several groups in one key multiply out. A group with neither
+/// `..` nor a comma, and one right after a `$` (an unsubstituted `${name}`), is kept as
+/// written. Nested groups, stepped or non-numeric ranges and unclosed braces fail with
+/// [`SkeletorError::Config`]; a key without braces comes back unchanged.
+pub fn expand_key(key: &str) -> Result<Vec<String>, SkeletorError> {
+    let mut names = vec![String::new()];
+    let mut rest = key;
+
+    while let Some(open) = find_group(rest) {
+        let close = match rest[open + 1..].find(['{', '}']) {
+            Some(offset) if rest.as_bytes()[open + 1 + offset] == b'}' => open + 1 + offset,
+            Some(_) => return Err(invalid(key, "nested braces are not supported")),
+            None => return Err(invalid(key, "'{' is never closed")),
+        };
+        let group = &rest[open + 1..close];
+        let alternatives = if group.contains("..") {
+            range(group).map_err(|reason| invalid(key, &reason))?
+        } else if group.contains(',') {
+            group.split(',').map(str::to_string).collect()
+        } else {
+            vec![format!("{{{}}}", group)]
+        };
+
+        let prefix = &rest[..open];
+        if names.len() * alternatives.len() > MAX_EXPANSION {
+            return Err(invalid(key, &format!("it expands to more than {} names", MAX_EXPANSION)));
+        }
+        names = names
+            .iter()
+            .flat_map(|name| alternatives.iter().map(move |alternative| format!("{}{}{}", name, prefix, alternative)))
+            .collect();
+        rest = &rest[close + 1..];
+    }
+
+    Ok(names.into_iter().map(|name| name + rest).collect())
+}
+
+/// The byte offset of the next `{` that opens a group, skipping `${`
+fn find_group(text: &str) -> Option<usize> {
+    text.match_indices('{')
+        .map(|(index, _)| index)
+        .find(|&index| index == 0 || text.as_bytes()[index - 1] != b'$')
+}
+
+/// The names of an inclusive numeric range `start..end`
+fn range(group: &str) -> Result<Vec<String>, String> {
+    let not_a_range = || format!("'{{{}}}' is not a range of whole numbers like {{1..5}}", group);
+    let (start, end) = group.split_once("..").ok_or_else(not_a_range)?;
+    let (Ok(from), Ok(to)) = (start.parse::<i64>(), end.parse::<i64>()) else {
+        return Err(not_a_range());
+    };
+    if from.abs_diff(to) >= MAX_EXPANSION as u64 {
+        return Err(format!("it expands to more than {} names", MAX_EXPANSION));
+    }
+    let padded = |bound: &str| {
+        let digits = bound.trim_start_matches('-');
+        digits.len() > 1 && digits.starts_with('0')
+    };
+    let width = if padded(start) || padded(end) { start.len().max(end.len()) } else { 0 };
+
+    let numbers: Vec<i64> = if from <= to { (from..=to).collect() } else { (to..=from).rev().collect() };
+    Ok(numbers.into_iter().map(|n| format!("{:0width$}", n, width = width)).collect())
+}
+
+fn invalid(key: &str, reason: &str) -> SkeletorError {
+    SkeletorError::Config(format!("Cannot expand '{}': {}", key, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_key_ranges_and_lists() {
+        assert_eq!(expand_key("file_{1..3}.txt").unwrap(), vec!["file_1.txt", "file_2.txt", "file_3.txt"]);
+        assert_eq!(expand_key("v{3..1}").unwrap(), vec!["v3", "v2", "v1"]);
+        assert_eq!(expand_key("page{08..10}.md").unwrap(), vec!["page08.md", "page09.md", "page10.md"]);
+        assert_eq!(expand_key("{src,tests}").unwrap(), vec!["src", "tests"]);
+        assert_eq!(expand_key("{a,b}_{1..2}").unwrap(), vec!["a_1", "a_2", "b_1", "b_2"]);
+
+        // Keys without a group to expand are left alone
+        assert_eq!(expand_key("README.md").unwrap(), vec!["README.md"]);
+        assert_eq!(expand_key("${name}.rs").unwrap(), vec!["${name}.rs"]);
+        assert_eq!(expand_key("{single}").unwrap(), vec!["{single}"]);
+    }
+
+    #[test]
+    fn test_expand_braces_is_opt_in() {
+        let doc: Value = serde_yaml::from_str("directories: {}\n").unwrap();
+        assert!(!braces_enabled(&doc).unwrap());
+        let doc: Value = serde_yaml::from_str("expand_braces: true\n").unwrap();
+        assert!(braces_enabled(&doc).unwrap());
+        let doc: Value = serde_yaml::from_str("expand_braces: yes please\n").unwrap();
+        assert!(braces_enabled(&doc).is_err());
+
+        let directories: Value = serde_yaml::from_str("\"{src,tests}\":\n  \"mod_{1..2}.rs\": x\n").unwrap();
+        let expected: Value =
+            serde_yaml::from_str("src:\n  mod_1.rs: x\n  mod_2.rs: x\ntests:\n  mod_1.rs: x\n  mod_2.rs: x\n").unwrap();
+        assert_eq!(expand_braces(&directories).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_expand_key_rejects_malformed_groups() {
+        for key in ["file_{1..}.txt", "{a..c}", "{1..10..2}", "{1..{2,3}}", "open_{1,2", "{1..20000}"] {
+            assert!(matches!(expand_key(key), Err(SkeletorError::Config(_))), "{} should fail", key);
+        }
+    }
+}
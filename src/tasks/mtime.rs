@@ -0,0 +1,128 @@
+use super::{file_mtime, file_node, join_safe_path, CreationResult};
+use crate::errors::SkeletorError;
+use filetime::FileTime;
+use serde_yaml::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+/// Turns every plain file in `structure` into a `!file` node carrying the
+/// modification time of the matching file under `dir`.
+///
+/// Files whose time cannot be read are left as plain strings.
+pub fn record_mtimes(structure: &mut Value, dir: &Path) {
+    let Some(mapping) = structure.as_mapping_mut() else {
+        return;
+    };
+
+    for (key, value) in mapping.iter_mut() {
+        let Some(name) = key.as_str() else {
+            continue;
+        };
+        let path = dir.join(name);
+        match value {
+            Value::Mapping(_) => record_mtimes(value, &path),
+            Value::String(content) => {
+                let content = std::mem::take(content);
+                *value = with_mtime(content, &path);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `content` as a `!file` node carrying the modification time of `path`, or as a
+/// plain string when the time cannot be read.
+pub fn with_mtime(content: String, path: &Path) -> Value {
+    let mtime = fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| OffsetDateTime::from(modified).format(&Rfc3339).ok());
+    match mtime {
+        Some(mtime) => file_node(content, &mtime),
+        None => Value::String(content),
+    }
+}
+
+/// Sets the recorded modification time on each `!file` entry of `structure` under `base`.
+///
+/// Files the apply did not write (skipped, preserved or failed in `result`) keep their
+/// own time. Returns how many times were restored.
+pub fn restore_mtimes(
+    base: &Path,
+    structure: &Value,
+    result: &CreationResult,
+) -> Result<usize, SkeletorError> {
+    let untouched: HashSet<&str> = result
+        .skipped_files_list
+        .iter()
+        .chain(&result.preserved_files_list)
+        .chain(result.failed_files_list.iter().map(|(path, _)| path))
+        .map(String::as_str)
+        .collect();
+    restore_under(base, structure, &untouched)
+}
+
+fn restore_under(base: &Path, structure: &Value, untouched: &HashSet<&str>) -> Result<usize, SkeletorError> {
+    let Some(mapping) = structure.as_mapping() else {
+        return Ok(0);
+    };
+
+    let mut restored = 0;
+    for (key, value) in mapping {
+        let Some(name) = key.as_str() else {
+            continue;
+        };
+        let path = join_safe_path(base, name)?;
+        if let Value::Mapping(_) = value {
+            restored += restore_under(&path, value, untouched)?;
+            continue;
+        }
+        let Some(mtime) = file_mtime(value) else {
+            continue;
+        };
+        if untouched.contains(path.display().to_string().as_str()) || !path.is_file() {
+            continue;
+        }
+
+        let modified = OffsetDateTime::parse(mtime, &Rfc3339).map_err(|e| {
+            SkeletorError::Config(format!("Invalid mtime '{}' for {}: {}", mtime, path.display(), e))
+        })?;
+        filetime::set_file_mtime(&path, FileTime::from_system_time(modified.into()))
+            .map_err(|e| SkeletorError::from_io_with_context(e, path.clone()))?;
+        restored += 1;
+    }
+
+    Ok(restored)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tasks::{create_files_and_directories, traverse_structure};
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_mtimes_round_trip() {
+        let fs = TestFileSystem::new();
+        let source = fs.path("source");
+        let old = fs.create_file("source/src/old.rs", "// old");
+        let recorded = FileTime::from_unix_time(1_600_000_000, 0);
+        filetime::set_file_mtime(&old, recorded).unwrap();
+
+        let mut structure: Value = serde_yaml::from_str("src:\n  old.rs: // old\n").unwrap();
+        record_mtimes(&mut structure, &source);
+        assert_eq!(file_mtime(&structure["src"]["old.rs"]), Some("2020-09-13T12:26:40Z"));
+
+        let output = fs.path("output");
+        let tasks = traverse_structure(&output, &structure).unwrap();
+        let result = create_files_and_directories(&tasks, false).unwrap();
+        assert_eq!(restore_mtimes(&output, &structure, &result).unwrap(), 1);
+
+        let applied = output.join("src/old.rs");
+        assert_file_content(&applied, "// old");
+        assert_eq!(FileTime::from_last_modification_time(&std::fs::metadata(&applied).unwrap()), recorded);
+    }
+}
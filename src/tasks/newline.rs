@@ -0,0 +1,230 @@
+use crate::errors::SkeletorError;
+use globset::{Glob, GlobMatcher};
+use serde_yaml::Value;
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// How a file's final newline is handled when it is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NewlineMode {
+    /// Append a newline to non-empty content that lacks one
+    Ensure,
+    /// Remove all trailing line endings
+    Strip,
+    /// Write content exactly as configured
+    Preserve,
+}
+
+impl NewlineMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "ensure" => Some(Self::Ensure),
+            "strip" => Some(Self::Strip),
+            "preserve" => Some(Self::Preserve),
+            _ => None,
+        }
+    }
+
+    pub fn apply<'a>(&self, content: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::Ensure if !content.is_empty() && !content.ends_with('\n') => {
+                Cow::Owned(format!("{}\n", content))
+            }
+            Self::Strip => Cow::Borrowed(content.trim_end_matches(['\n', '\r'])),
+            _ => Cow::Borrowed(content),
+        }
+    }
+}
+
+/// Which line endings files are written with, whatever the template contains
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEndings {
+    /// Convert every line ending to `\n`
+    Lf,
+    /// Convert every line ending to `\r\n`
+    Crlf,
+    /// Write line endings exactly as configured
+    Preserve,
+}
+
+impl LineEndings {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "lf" => Some(Self::Lf),
+            "crlf" => Some(Self::Crlf),
+            "preserve" => Some(Self::Preserve),
+            _ => None,
+        }
+    }
+
+    /// Rewrites `content`'s line endings; content with a NUL byte is taken to be binary and left alone.
+    pub fn apply<'a>(&self, content: &'a str) -> Cow<'a, str> {
+        if *self == Self::Preserve || content.contains('\0') {
+            return Cow::Borrowed(content);
+        }
+        let lf = if content.contains('\r') {
+            Cow::Owned(content.replace("\r\n", "\n"))
+        } else {
+            Cow::Borrowed(content)
+        };
+        match self {
+            Self::Crlf if lf.contains('\n') => Cow::Owned(lf.replace('\n', "\r\n")),
+            _ => lf,
+        }
+    }
+}
+
+struct NewlineRule {
+    matcher: GlobMatcher,
+    match_full_path: bool,
+    specificity: usize,
+    mode: NewlineMode,
+}
+
+/// Per-file final newline rules from a config's `newline_policy:` section.
+///
+/// Keys are globs; patterns without a `/` match the file name, others match the
+/// path relative to the output directory. When several patterns match, the most
+/// specific one (most literal characters) wins, with later entries breaking ties.
+/// Unmatched files are preserved.
+///
+/// A policy can also convert every file's line endings (see [`LineEndings`]), after
+/// its final newline has been adjusted.
+pub struct NewlinePolicy {
+    root: PathBuf,
+    rules: Vec<NewlineRule>,
+    line_endings: LineEndings,
+}
+
+impl NewlinePolicy {
+    /// A policy with no final newline rules, resolving paths against `root`
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            rules: Vec::new(),
+            line_endings: LineEndings::Preserve,
+        }
+    }
+
+    /// Converts line endings in every file written under this policy
+    pub fn with_line_endings(mut self, line_endings: LineEndings) -> Self {
+        self.line_endings = line_endings;
+        self
+    }
+
+    /// Builds a policy from a `newline_policy` mapping, resolving paths against `root`.
+    pub fn from_yaml(value: &Value, root: &Path) -> Result<Self, SkeletorError> {
+        let mapping = value.as_mapping().ok_or_else(|| {
+            SkeletorError::Config("newline_policy must map glob patterns to ensure/strip/preserve".to_string())
+        })?;
+
+        let mut rules = Vec::new();
+        for (key, mode) in mapping {
+            let pattern = key.as_str().ok_or_else(|| {
+                SkeletorError::Config(format!("newline_policy pattern must be a string: {:?}", key))
+            })?;
+            let mode = mode.as_str().and_then(NewlineMode::parse).ok_or_else(|| {
+                SkeletorError::Config(format!(
+                    "newline_policy for '{}' must be one of ensure, strip, preserve",
+                    pattern
+                ))
+            })?;
+            let glob = Glob::new(pattern).map_err(|e| {
+                SkeletorError::Config(format!("Invalid newline_policy pattern '{}': {}", pattern, e))
+            })?;
+
+            rules.push(NewlineRule {
+                matcher: glob.compile_matcher(),
+                match_full_path: pattern.contains('/'),
+                specificity: pattern
+                    .chars()
+                    .filter(|c| !matches!(c, '*' | '?' | '[' | ']' | '{' | '}' | '!'))
+                    .count(),
+                mode,
+            });
+        }
+
+        Ok(Self {
+            rules,
+            ..Self::new(root)
+        })
+    }
+
+    /// Returns the mode of the most specific matching pattern, or `Preserve`.
+    pub fn mode_for(&self, path: &Path) -> NewlineMode {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        let file_name = relative.file_name().map(Path::new).unwrap_or(relative);
+
+        self.rules
+            .iter()
+            .filter(|rule| {
+                let candidate = if rule.match_full_path { relative } else { file_name };
+                rule.matcher.is_match(candidate)
+            })
+            .max_by_key(|rule| rule.specificity)
+            .map(|rule| rule.mode)
+            .unwrap_or(NewlineMode::Preserve)
+    }
+
+    /// The content to write at `path`: its final newline adjusted, then its line endings converted
+    pub fn apply<'a>(&self, path: &Path, content: &'a str) -> Cow<'a, str> {
+        match self.mode_for(path).apply(content) {
+            Cow::Borrowed(adjusted) => self.line_endings.apply(adjusted),
+            Cow::Owned(adjusted) => Cow::Owned(self.line_endings.apply(&adjusted).into_owned()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_specific_pattern_wins() {
+        let value: Value = serde_yaml::from_str(
+            r#"
+            "*": preserve
+            "*.md": ensure
+            "docs/CHANGELOG.md": strip
+            "#,
+        )
+        .unwrap();
+        let root = Path::new("/out");
+        let policy = NewlinePolicy::from_yaml(&value, root).unwrap();
+
+        assert_eq!(policy.mode_for(&root.join("README.md")), NewlineMode::Ensure);
+        assert_eq!(policy.mode_for(&root.join("docs/guide.md")), NewlineMode::Ensure);
+        assert_eq!(policy.mode_for(&root.join("docs/CHANGELOG.md")), NewlineMode::Strip);
+        assert_eq!(policy.mode_for(&root.join("main.rs")), NewlineMode::Preserve);
+    }
+
+    #[test]
+    fn test_mode_apply_and_invalid_mode() {
+        assert_eq!(NewlineMode::Ensure.apply("a"), "a\n");
+        assert_eq!(NewlineMode::Ensure.apply(""), "");
+        assert_eq!(NewlineMode::Strip.apply("a\r\n\n"), "a");
+        assert_eq!(NewlineMode::Preserve.apply("a"), "a");
+
+        let value: Value = serde_yaml::from_str(r#""*.md": always"#).unwrap();
+        assert!(matches!(
+            NewlinePolicy::from_yaml(&value, Path::new(".")),
+            Err(SkeletorError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_line_endings_convert_text_and_leave_binary_alone() {
+        assert_eq!(LineEndings::Lf.apply("a\r\nb\nc\r\n"), "a\nb\nc\n");
+        assert_eq!(LineEndings::Crlf.apply("a\r\nb\nc"), "a\r\nb\r\nc");
+        assert_eq!(LineEndings::Preserve.apply("a\r\nb\n"), "a\r\nb\n");
+        assert_eq!(LineEndings::Lf.apply("a\r\n\0b"), "a\r\n\0b");
+        assert_eq!(LineEndings::Crlf.apply("a\n\0b"), "a\n\0b");
+
+        // A newline added by the final newline rules follows the chosen style
+        let value: Value = serde_yaml::from_str(r#""*.md": ensure"#).unwrap();
+        let root = Path::new("/out");
+        let policy = NewlinePolicy::from_yaml(&value, root).unwrap().with_line_endings(LineEndings::Crlf);
+        assert_eq!(policy.apply(&root.join("README.md"), "# Title\nText"), "# Title\r\nText\r\n");
+        assert_eq!(policy.apply(&root.join("VERSION"), "1.0.0"), "1.0.0");
+    }
+}
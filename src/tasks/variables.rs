@@ -0,0 +1,267 @@
+use super::FILE_TAG;
+use crate::errors::SkeletorError;
+use serde_yaml::value::Tag;
+use serde_yaml::{Mapping, Value};
+use std::collections::{BTreeSet, HashMap};
+
+/// Reads a config's `variables:` mapping; scalar values are converted to strings.
+pub fn variables_from_value(value: &Value) -> Result<HashMap<String, String>, SkeletorError> {
+    let mapping = value.as_mapping().ok_or_else(|| {
+        SkeletorError::Config("variables must be a mapping of names to values".to_string())
+    })?;
+
+    let mut variables = HashMap::new();
+    for (key, value) in mapping {
+        let name = key.as_str().ok_or_else(|| {
+            SkeletorError::Config(format!("Variable name must be a string: {:?}", key))
+        })?;
+        let value = match value {
+            Value::String(s) => s.clone(),
+            Value::Number(n) => n.to_string(),
+            Value::Bool(b) => b.to_string(),
+            _ => {
+                return Err(SkeletorError::Config(format!(
+                    "Variable '{}' must be a string, number or boolean",
+                    name
+                )))
+            }
+        };
+        variables.insert(name.to_string(), value);
+    }
+    Ok(variables)
+}
+
+/// Combines a config document's `variables:` with `--var` arguments, which take precedence.
+pub fn collect_variables(
+    yaml_doc: &Value,
+    var_args: &[String],
+) -> Result<HashMap<String, String>, SkeletorError> {
+    let mut variables = yaml_doc
+        .get("variables")
+        .map(variables_from_value)
+        .transpose()?
+        .unwrap_or_default();
+    variables.extend(parse_var_args(var_args)?);
+    Ok(variables)
+}
+
+/// Parses repeatable `--var KEY=VALUE` arguments.
+pub fn parse_var_args(args: &[String]) -> Result<HashMap<String, String>, SkeletorError> {
+    let mut variables = HashMap::new();
+    for arg in args {
+        match arg.split_once('=') {
+            Some((name, value)) if is_variable_name(name) => {
+                variables.insert(name.to_string(), value.to_string());
+            }
+            _ => {
+                return Err(SkeletorError::Config(format!(
+                    "Invalid --var '{}': expected KEY=VALUE",
+                    arg
+                )))
+            }
+        }
+    }
+    Ok(variables)
+}
+
+/// Replaces `${name}` placeholders in file contents and in file/directory names.
+///
+/// `$${name}` escapes a literal `${name}`; placeholders that are not plain
+/// identifiers (e.g. `${a + b}`) are left untouched. Any unresolved name
+/// fails the whole pass with a [`SkeletorError::Config`] listing them.
+#[allow(dead_code)]
+pub fn substitute_variables(
+    structure: &Value,
+    variables: &HashMap<String, String>,
+) -> Result<Value, SkeletorError> {
+    substitute_variables_with_env(structure, variables, false)
+}
+
+/// Like [`substitute_variables`], but with `env` set a name missing from `variables`
+/// falls back to the environment variable of that name before it counts as unresolved.
+///
+/// This copies environment values (tokens, paths) into the generated files, so it is
+/// only done when the caller opts in with `--env-vars`.
+pub fn substitute_variables_with_env(
+    structure: &Value,
+    variables: &HashMap<String, String>,
+    env: bool,
+) -> Result<Value, SkeletorError> {
+    let lookup = Lookup { variables, env };
+    let mut missing = BTreeSet::new();
+    let substituted = substitute_value(structure, &lookup, &mut missing);
+
+    if missing.is_empty() {
+        Ok(substituted)
+    } else {
+        Err(SkeletorError::Config(format!(
+            "Unresolved template variables: {} (define them under `variables:` or pass --var KEY=VALUE)",
+            missing.into_iter().collect::<Vec<_>>().join(", ")
+        )))
+    }
+}
+
+/// Where placeholder values come from: the given variables, then optionally the environment
+struct Lookup<'a> {
+    variables: &'a HashMap<String, String>,
+    env: bool,
+}
+
+impl Lookup<'_> {
+    fn get(&self, name: &str) -> Option<String> {
+        match self.variables.get(name) {
+            Some(value) => Some(value.clone()),
+            None if self.env => std::env::var(name).ok(),
+            None => None,
+        }
+    }
+}
+
+fn substitute_value(
+    value: &Value,
+    variables: &Lookup,
+    missing: &mut BTreeSet<String>,
+) -> Value {
+    match value {
+        Value::Mapping(mapping) => {
+            let mut result = Mapping::new();
+            for (key, child) in mapping {
+                let key = match key {
+                    Value::String(name) => Value::String(substitute_str(name, variables, missing)),
+                    other => other.clone(),
+                };
+                result.insert(key, substitute_value(child, variables, missing));
+            }
+            Value::Mapping(result)
+        }
+        Value::String(content) => Value::String(substitute_str(content, variables, missing)),
+        // `!file` nodes keep their metadata; only the content is a template
+        Value::Tagged(tagged) if tagged.tag == Tag::new(FILE_TAG) => {
+            let mut tagged = tagged.clone();
+            if let Some(Value::String(content)) = tagged.value.get_mut("content") {
+                *content = substitute_str(content, variables, missing);
+            }
+            Value::Tagged(tagged)
+        }
+        other => other.clone(),
+    }
+}
+
+fn substitute_str(
+    input: &str,
+    variables: &Lookup,
+    missing: &mut BTreeSet<String>,
+) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        let escaped = rest[..start].ends_with('$');
+        output.push_str(&rest[..start - usize::from(escaped)]);
+        let after = &rest[start + 2..];
+
+        let Some(end) = after.find('}') else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+        let name = &after[..end];
+        let placeholder = &rest[start..start + 2 + end + 1];
+
+        if escaped || !is_variable_name(name) {
+            output.push_str(placeholder);
+        } else if let Some(value) = variables.get(name) {
+            output.push_str(&value);
+        } else {
+            missing.insert(name.to_string());
+            output.push_str(placeholder);
+        }
+        rest = &after[end + 1..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_substitute_contents_and_names() {
+        let structure: Value = serde_yaml::from_str(
+            r#"
+            ${module}:
+              mod.rs: "pub const NAME: &str = \"${project_name}\";"
+            script.js: "const msg = `${a + b}`; // $${project_name}"
+            "#,
+        )
+        .unwrap();
+
+        let result = substitute_variables(
+            &structure,
+            &vars(&[("module", "parser"), ("project_name", "demo")]),
+        )
+        .unwrap();
+
+        assert_eq!(
+            result["parser"]["mod.rs"].as_str(),
+            Some("pub const NAME: &str = \"demo\";")
+        );
+        assert_eq!(
+            result["script.js"].as_str(),
+            Some("const msg = `${a + b}`; // ${project_name}")
+        );
+    }
+
+    #[test]
+    fn test_unresolved_variables_are_listed() {
+        let structure: Value =
+            serde_yaml::from_str(r#"${dir}/: { "a.txt": "${name} ${other} ${name}" }"#).unwrap();
+
+        match substitute_variables(&structure, &vars(&[("dir", "x")])) {
+            Err(SkeletorError::Config(message)) => {
+                assert!(message.contains("Unresolved template variables: name, other"));
+            }
+            other => panic!("Expected Config error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_env_vars_fill_in_unresolved_names_only() {
+        std::env::set_var("SKELETOR_TEST_ENV_HOME", "/home/from-env");
+        std::env::set_var("SKELETOR_TEST_ENV_USER", "env-user");
+        let structure: Value =
+            serde_yaml::from_str(r#"profile: "${SKELETOR_TEST_ENV_HOME} ${SKELETOR_TEST_ENV_USER}""#).unwrap();
+        let defined = vars(&[("SKELETOR_TEST_ENV_USER", "var-user")]);
+
+        let result = substitute_variables_with_env(&structure, &defined, true).unwrap();
+        assert_eq!(result["profile"].as_str(), Some("/home/from-env var-user"));
+
+        // Without opting in, the environment is never read
+        assert!(substitute_variables(&structure, &defined).is_err());
+    }
+
+    #[test]
+    fn test_parse_var_args() {
+        let parsed = parse_var_args(&["project_name=demo".to_string(), "empty=".to_string()]).unwrap();
+        assert_eq!(parsed.get("project_name").map(String::as_str), Some("demo"));
+        assert_eq!(parsed.get("empty").map(String::as_str), Some(""));
+
+        assert!(parse_var_args(&["no-equals".to_string()]).is_err());
+    }
+}
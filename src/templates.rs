@@ -0,0 +1,65 @@
+use crate::config::template_dir_from_matches;
+use crate::errors::SkeletorError;
+use clap::ArgMatches;
+use std::path::Path;
+
+/// Runs the templates subcommand; `list` prints the names `apply` and `info` accept in place of a path.
+pub fn run_templates(matches: &ArgMatches) -> Result<(), SkeletorError> {
+    match matches.subcommand() {
+        Some(("list", sub_m)) => {
+            let Some(dir) = template_dir_from_matches(sub_m) else {
+                return Err(SkeletorError::Config(
+                    "No template directory: pass --template-dir or set SKELETOR_TEMPLATE_DIR".to_string(),
+                ));
+            };
+            let names = template_names(&dir)?;
+            if names.is_empty() {
+                if !crate::config::quiet_from_matches(sub_m) {
+                    println!("No templates in {}", dir.display());
+                }
+            } else {
+                for name in names {
+                    println!("{}", name);
+                }
+            }
+            Ok(())
+        }
+        _ => unreachable!("A templates subcommand is required"),
+    }
+}
+
+/// Names of the `<name>.yml` templates in `dir`, sorted; a missing directory has none.
+pub fn template_names(dir: &Path) -> Result<Vec<String>, SkeletorError> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(SkeletorError::from_io_with_context(e, dir.to_path_buf())),
+    };
+
+    let mut names: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "yml"))
+        .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_template_names_lists_yml_files() {
+        let fs = TestFileSystem::new();
+        fs.create_file("templates/web.yml", "directories: {}\n");
+        fs.create_file("templates/rust.yml", "directories: {}\n");
+        fs.create_file("templates/notes.txt", "not a template");
+        fs.create_file("templates/nested/cli.yml", "directories: {}\n");
+
+        assert_eq!(template_names(&fs.path("templates")).unwrap(), vec!["rust", "web"]);
+        assert!(template_names(&fs.path("missing")).unwrap().is_empty());
+    }
+}
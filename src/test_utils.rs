@@ -11,18 +11,8 @@ pub mod helpers {
     use clap::ArgMatches;
     use std::fs;
     use std::path::{Path, PathBuf};
-    use std::sync::{Mutex, OnceLock};
     use tempfile::{TempDir, tempdir};
 
-    static CWD_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
-
-    pub fn cwd_lock() -> std::sync::MutexGuard<'static, ()> {
-        CWD_LOCK
-            .get_or_init(|| Mutex::new(()))
-            .lock()
-            .expect("Failed to lock current directory mutex")
-    }
-
     /// Helper for creating CLI matches for a given subcommand with arguments
     pub fn create_cli_matches_for_subcommand(subcommand: &str, args: Vec<&str>) -> Option<ArgMatches> {
         let mut full_args = vec!["skeletor", subcommand];
@@ -47,6 +37,21 @@ pub mod helpers {
         create_cli_matches_for_subcommand("info", args)
     }
 
+    /// Helper for creating CLI matches for cat subcommand
+    pub fn create_cat_matches(args: Vec<&str>) -> Option<ArgMatches> {
+        create_cli_matches_for_subcommand("cat", args)
+    }
+
+    /// Helper for creating CLI matches for diff subcommand
+    pub fn create_diff_matches(args: Vec<&str>) -> Option<ArgMatches> {
+        create_cli_matches_for_subcommand("diff", args)
+    }
+
+    /// Helper for creating CLI matches for validate subcommand
+    pub fn create_validate_matches(args: Vec<&str>) -> Option<ArgMatches> {
+        create_cli_matches_for_subcommand("validate", args)
+    }
+
     /// Create a temporary directory with test files
     pub struct TestFileSystem {
         #[allow(dead_code)]
@@ -1,20 +1,48 @@
 //! Shared utility functions for reducing code duplication across modules
 //!
 //! This module provides common operations used by multiple modules:
-//! - File I/O with consistent error handling
-//! - YAML parsing with proper error conversion
+//! - File I/O with consistent error handling, including transparent gzip decoding
+//! - YAML parsing with proper error conversion and bounded alias expansion
+//! - Config parsing from YAML, JSON or TOML, normalized to YAML values
 //! - Output formatting utilities
 
 use crate::errors::SkeletorError;
+use flate2::read::GzDecoder;
+use serde::de::{self, DeserializeSeed, Deserializer, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
 use serde_yaml::Value;
+use std::cell::Cell;
+use std::fmt;
 use std::fs;
+use std::io::{self, Read};
 use std::path::Path;
 
-/// Read a file to string with consistent error handling
+/// Leading bytes of every gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Read a file to string with consistent error handling.
+///
+/// Gzip-compressed files are recognized by their magic bytes and decompressed.
 pub fn read_file_to_string<P: AsRef<Path>>(path: P) -> Result<String, SkeletorError> {
     let path = path.as_ref();
-    fs::read_to_string(path)
-        .map_err(|e| SkeletorError::from_io_with_context(e, path.to_path_buf()))
+    let decode = || -> io::Result<String> {
+        let bytes = fs::read(path)?;
+        let bytes = if bytes.starts_with(&GZIP_MAGIC) {
+            let mut decoded = Vec::new();
+            GzDecoder::new(bytes.as_slice()).read_to_end(&mut decoded)?;
+            decoded
+        } else {
+            bytes
+        };
+        String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    };
+    decode().map_err(|e| SkeletorError::from_io_with_context(e, path.to_path_buf()))
+}
+
+/// Whether `path` names a gzip file (`.gz` extension, any case)
+pub fn is_gzip_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"))
 }
 
 /// Write string to file with consistent error handling
@@ -24,10 +52,159 @@ pub fn write_string_to_file<P: AsRef<Path>>(path: P, content: &str) -> Result<()
         .map_err(|e| SkeletorError::from_io_with_context(e, path.to_path_buf()))
 }
 
-/// Parse YAML string with consistent error handling
+/// Aliases may expand a document to at most this many times its source length
+/// (counting one unit per node plus each scalar's bytes)...
+const ALIAS_EXPANSION_FACTOR: usize = 100;
+/// ...or to this many units, whichever is larger, so small templates can reuse anchors freely
+const MIN_ALIAS_EXPANSION_BUDGET: usize = 64 * 1024 * 1024;
+/// Error message marking an exhausted [`ExpansionBudget`]
+const ALIAS_BUDGET_EXCEEDED: &str = "anchors and aliases expand the document past its size limit";
+
+/// Parse YAML string with consistent error handling.
+///
+/// Anchors and aliases (`&name` / `*name`) are resolved into concrete copies, so callers
+/// never see an alias. Documents whose aliases would expand beyond a size bounded by
+/// the source length are rejected with [`SkeletorError::Config`] before anything is built.
 pub fn parse_yaml_string(yaml_str: &str) -> Result<Value, SkeletorError> {
-    serde_yaml::from_str(yaml_str)
-        .map_err(|e| SkeletorError::invalid_yaml(e.to_string()))
+    // An alias cannot be written without `*`, so alias-free documents skip the extra pass
+    if yaml_str.contains('*') {
+        check_alias_expansion(yaml_str)?;
+    }
+    serde_yaml::from_str(yaml_str).map_err(|e| {
+        duplicate_key_error(&e.to_string())
+            .or_else(|| alias_limit_error(&e.to_string()))
+            .unwrap_or_else(|| SkeletorError::invalid_yaml(e.to_string()))
+    })
+}
+
+/// Walks the document as it would be expanded, without building it, and fails once
+/// the expansion outgrows its budget. Syntax errors are left for the real parse to report.
+fn check_alias_expansion(yaml_str: &str) -> Result<(), SkeletorError> {
+    let budget = Cell::new(MIN_ALIAS_EXPANSION_BUDGET.max(yaml_str.len().saturating_mul(ALIAS_EXPANSION_FACTOR)));
+    match ExpansionBudget(&budget).deserialize(serde_yaml::Deserializer::from_str(yaml_str)) {
+        Err(e) => alias_limit_error(&e.to_string()).map_or(Ok(()), Err),
+        Ok(()) => Ok(()),
+    }
+}
+
+/// Turns an exceeded expansion limit, ours or serde_yaml's own nesting and repetition
+/// limits, into a config error explaining the likely cause.
+fn alias_limit_error(message: &str) -> Option<SkeletorError> {
+    let reason = if message.contains(ALIAS_BUDGET_EXCEEDED) {
+        ALIAS_BUDGET_EXCEEDED
+    } else if message.contains("repetition limit exceeded") {
+        "aliases are expanded too many times"
+    } else if message.contains("recursion limit exceeded") {
+        "nesting, including nesting through aliases, is more than 128 levels deep"
+    } else {
+        return None;
+    };
+    Some(SkeletorError::Config(format!(
+        "YAML too large to load safely: {}; check for aliases (*name) that repeat large or nested anchors",
+        reason
+    )))
+}
+
+/// Visits every node a document expands to, charging one unit per node plus the length
+/// of each scalar against the remaining budget.
+struct ExpansionBudget<'a>(&'a Cell<usize>);
+
+impl ExpansionBudget<'_> {
+    fn charge<E: de::Error>(&self, units: usize) -> Result<(), E> {
+        let remaining = self
+            .0
+            .get()
+            .checked_sub(units + 1)
+            .ok_or_else(|| E::custom(ALIAS_BUDGET_EXCEEDED))?;
+        self.0.set(remaining);
+        Ok(())
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for ExpansionBudget<'_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ExpansionBudget<'_> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any YAML value")
+    }
+
+    fn visit_bool<E: de::Error>(self, _: bool) -> Result<(), E> {
+        self.charge(0)
+    }
+
+    fn visit_i64<E: de::Error>(self, _: i64) -> Result<(), E> {
+        self.charge(0)
+    }
+
+    fn visit_u64<E: de::Error>(self, _: u64) -> Result<(), E> {
+        self.charge(0)
+    }
+
+    fn visit_f64<E: de::Error>(self, _: f64) -> Result<(), E> {
+        self.charge(0)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<(), E> {
+        self.charge(value.len())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        self.charge(0)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        self.charge(0)?;
+        while seq.next_element_seed(ExpansionBudget(self.0))?.is_some() {}
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        self.charge(0)?;
+        while map.next_key_seed(ExpansionBudget(self.0))?.is_some() {
+            map.next_value_seed(ExpansionBudget(self.0))?;
+        }
+        Ok(())
+    }
+
+    /// Tagged nodes such as `!file` and `!symlink`
+    fn visit_enum<A: EnumAccess<'de>>(self, data: A) -> Result<(), A::Error> {
+        let (tag, contents) = data.variant::<String>()?;
+        self.charge(tag.len())?;
+        contents.newtype_variant_seed(ExpansionBudget(self.0))
+    }
+}
+
+/// Turns a parser's duplicate mapping key rejection into a config error naming the key's path.
+///
+/// Rather than silently keeping the last value, serde_yaml and serde_json refuse
+/// the document, reporting `<path>: duplicate entry with key "k"`.
+fn duplicate_key_error(message: &str) -> Option<SkeletorError> {
+    const MARKER: &str = "duplicate entry with key ";
+    let start = message.find(MARKER)?;
+    let parent = message[..start].trim_end_matches(": ");
+    let key = message[start + MARKER.len()..]
+        .split(" at line ")
+        .next()
+        .unwrap_or_default()
+        .trim_matches('"');
+    let path = if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    };
+
+    Some(SkeletorError::Config(format!(
+        "duplicate key '{}': only one definition would be kept; rename or merge the entries",
+        path
+    )))
 }
 
 /// Read and parse YAML file in one operation
@@ -36,6 +213,88 @@ pub fn read_yaml_file<P: AsRef<Path>>(path: P) -> Result<Value, SkeletorError> {
     parse_yaml_string(&content)
 }
 
+/// Serialization formats accepted for config files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Yaml,
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    /// Detects the format from the file extension, looking past a trailing `.gz`;
+    /// unknown extensions are read as YAML
+    pub fn from_path(path: &Path) -> Self {
+        let path = if is_gzip_path(path) {
+            Path::new(path.file_stem().unwrap_or_default())
+        } else {
+            path
+        };
+        match path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase())
+            .as_deref()
+        {
+            Some("json") => Self::Json,
+            Some("toml") => Self::Toml,
+            _ => Self::Yaml,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Yaml => "YAML",
+            Self::Json => "JSON",
+            Self::Toml => "TOML",
+        }
+    }
+}
+
+/// Parse a config document in the given format into the YAML value used internally
+pub fn parse_config_string(content: &str, format: ConfigFormat) -> Result<Value, SkeletorError> {
+    match format {
+        ConfigFormat::Yaml => parse_yaml_string(content),
+        ConfigFormat::Json => serde_json::from_str(content).map_err(|e| {
+            match duplicate_key_error(&e.to_string()) {
+                // JSON is valid YAML, and serde_yaml's report includes the parent path
+                Some(error) => match parse_yaml_string(content) {
+                    Err(yaml_error @ SkeletorError::Config(_)) => yaml_error,
+                    _ => error,
+                },
+                None => SkeletorError::invalid_config(format.name(), e.to_string()),
+            }
+        }),
+        ConfigFormat::Toml => toml::from_str::<toml::Value>(content)
+            .map(toml_to_yaml)
+            .map_err(|e| SkeletorError::invalid_config(format.name(), e.to_string())),
+    }
+}
+
+/// Read a config file, choosing the parser from its extension
+pub fn read_config_file<P: AsRef<Path>>(path: P) -> Result<Value, SkeletorError> {
+    let path = path.as_ref();
+    let content = read_file_to_string(path)?;
+    parse_config_string(&content, ConfigFormat::from_path(path))
+}
+
+fn toml_to_yaml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::Number(i.into()),
+        toml::Value::Float(f) => Value::Number(f.into()),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(items) => Value::Sequence(items.into_iter().map(toml_to_yaml).collect()),
+        toml::Value::Table(table) => Value::Mapping(
+            table
+                .into_iter()
+                .map(|(key, value)| (Value::String(key), toml_to_yaml(value)))
+                .collect(),
+        ),
+    }
+}
+
 // Output utilities for consistent formatting
 // Note: For consistent output formatting, use the output.rs module's Reporter system
 // which provides DefaultReporter and SilentReporter with professional CLI formatting.
@@ -53,6 +312,22 @@ mod tests {
         assert_eq!(content, "Hello, world!");
     }
 
+    #[test]
+    fn test_read_file_to_string_decompresses_gzip() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let fs = TestFileSystem::new();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"{\"directories\": {}}").unwrap();
+        let file_path = fs.path("template.json.gz");
+        std::fs::write(&file_path, encoder.finish().unwrap()).unwrap();
+
+        assert_eq!(read_file_to_string(&file_path).unwrap(), "{\"directories\": {}}");
+        assert_eq!(ConfigFormat::from_path(&file_path), ConfigFormat::Json);
+        assert_eq!(ConfigFormat::from_path(Path::new("template.gz")), ConfigFormat::Yaml);
+    }
+
     #[test]
     fn test_write_string_to_file() {
         let fs = TestFileSystem::new();
@@ -102,4 +377,54 @@ mod tests {
         let result = read_yaml_file(&file_path);
         assert!(result.is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_read_config_file_json_and_toml() {
+        let fs = TestFileSystem::new();
+        let json_path = fs.create_file(
+            "config.json",
+            r#"{ "directories": { "src": { "main.rs": "fn main() {}" } } }"#,
+        );
+        let toml_path = fs.create_file(
+            "config.toml",
+            "[directories.src]\n\"main.rs\" = \"fn main() {}\"\n\n[stats]\nfiles = 1\n",
+        );
+
+        let json = read_config_file(&json_path).unwrap();
+        let toml = read_config_file(&toml_path).unwrap();
+        assert_eq!(json["directories"], toml["directories"]);
+        assert_eq!(toml["directories"]["src"]["main.rs"].as_str(), Some("fn main() {}"));
+        assert_eq!(toml["stats"]["files"].as_u64(), Some(1));
+    }
+
+    #[test]
+    fn test_parse_config_error_names_format() {
+        let error = parse_config_string("{ not json", ConfigFormat::Json).unwrap_err();
+        assert!(error.to_string().starts_with("invalid JSON configuration"));
+
+        let error = parse_config_string("directories = [", ConfigFormat::Toml).unwrap_err();
+        assert!(error.to_string().starts_with("invalid TOML configuration"));
+
+        assert_eq!(ConfigFormat::from_path(Path::new(".skeletorrc")), ConfigFormat::Yaml);
+        assert_eq!(ConfigFormat::from_path(Path::new("t.JSON")), ConfigFormat::Json);
+    }
+
+    #[test]
+    fn test_duplicate_keys_name_the_path() {
+        let yaml = "directories:\n  src:\n    main.rs: a\n    lib.rs: b\n    main.rs: c\n";
+        match parse_yaml_string(yaml) {
+            Err(SkeletorError::Config(message)) => {
+                assert!(message.contains("'directories.src.main.rs'"), "{}", message);
+            }
+            other => panic!("Expected Config error, got {:?}", other),
+        }
+
+        let json = r#"{"directories": {"src": {"main.rs": "a", "main.rs": "b"}}}"#;
+        match parse_config_string(json, ConfigFormat::Json) {
+            Err(SkeletorError::Config(message)) => {
+                assert!(message.contains("'directories.src.main.rs'"), "{}", message);
+            }
+            other => panic!("Expected Config error, got {:?}", other),
+        }
+    }
+}
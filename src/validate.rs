@@ -0,0 +1,206 @@
+mod schema;
+
+use crate::config::{
+    default_config_from_matches, directories_mapping, is_absolute_key, quiet_from_matches, resolve_includes,
+    resolve_path, type_name, working_dir_from_matches,
+};
+use crate::errors::SkeletorError;
+use crate::tasks::{compute_stats, file_content, file_ref_path, symlink_target};
+use clap::ArgMatches;
+use serde_yaml::{Mapping, Value};
+use std::path::PathBuf;
+use self::schema::TemplateSchema;
+
+/// Runs the validate subcommand: checks a config's structure without applying it.
+pub fn run_validate(matches: &ArgMatches) -> Result<(), SkeletorError> {
+    let working_dir = working_dir_from_matches(matches);
+    let input_path = match matches.get_one::<String>("config") {
+        Some(path) => resolve_path(working_dir.as_deref(), PathBuf::from(path)),
+        None => default_config_from_matches(matches),
+    };
+    let schema = matches
+        .get_one::<String>("schema")
+        .map(|path| TemplateSchema::load(&resolve_path(working_dir.as_deref(), PathBuf::from(path))))
+        .transpose()?;
+
+    let mut yaml_doc: Value = crate::utils::read_config_file(&input_path)?;
+    resolve_includes(&mut yaml_doc, &input_path)?;
+    let issues = validate_structure(&yaml_doc)?;
+    let violations = schema.map(|schema| schema.violations(&yaml_doc)).unwrap_or_default();
+
+    if !issues.is_empty() || !violations.is_empty() {
+        println!("Problems in {:?}:", input_path);
+        for issue in issues.iter().chain(&violations) {
+            if let SkeletorError::InvalidConfigEntry { path, reason } = issue {
+                println!("  {}: {}", path, reason);
+            }
+        }
+        return Err(if issues.is_empty() {
+            SkeletorError::SchemaViolations { count: violations.len() }
+        } else {
+            SkeletorError::InvalidStructure { count: issues.len() }
+        });
+    }
+
+    if !quiet_from_matches(matches) {
+        let (files, dirs) = compute_stats(&yaml_doc["directories"]);
+        println!("{:?} is valid: {} files, {} directories", input_path, files, dirs);
+    }
+    Ok(())
+}
+
+/// Returns a [`SkeletorError::InvalidConfigEntry`] for each entry that `apply` would silently drop.
+///
+/// Fails outright when `directories` is missing or is not a mapping.
+fn validate_structure(yaml_doc: &Value) -> Result<Vec<SkeletorError>, SkeletorError> {
+    let directories = directories_mapping(yaml_doc)?;
+
+    let mut issues = Vec::new();
+    collect_issues(directories, "directories", &mut issues);
+    Ok(issues)
+}
+
+fn collect_issues(mapping: &Mapping, path: &str, issues: &mut Vec<SkeletorError>) {
+    for (key, value) in mapping {
+        let Some(name) = key.as_str() else {
+            issues.push(SkeletorError::invalid_config_entry(
+                path,
+                format!("entry names must be strings, found {} key", type_name(key)),
+            ));
+            continue;
+        };
+        let entry_path = format!("{}.{}", path, name);
+        if is_absolute_key(name) {
+            issues.push(SkeletorError::invalid_config_entry(
+                entry_path,
+                "absolute path; keys must be relative to the output directory",
+            ));
+            continue;
+        }
+        match value {
+            Value::Mapping(children) if file_ref_path(value).is_none() => collect_issues(children, &entry_path, issues),
+            Value::String(_) => {}
+            // Symlinks, sidecar references and `!file` nodes recorded by snapshot are intentional, not malformed
+            _ if symlink_target(value).is_some()
+                || file_ref_path(value).is_some()
+                || file_content(value).is_some() => {}
+            other => issues.push(SkeletorError::invalid_config_entry(
+                entry_path,
+                format!("expected string or mapping, found {}", type_name(other)),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::helpers::*;
+
+    #[test]
+    fn test_validate_reports_each_dropped_entry() {
+        let yaml_doc: Value = serde_yaml::from_str(
+            r#"
+            directories:
+              src:
+                main.rs: "fn main() {}"
+                version: 2
+                empty:
+              flags: [a, b]
+              enabled: true
+            "#,
+        )
+        .unwrap();
+
+        let issues: Vec<(String, String)> = validate_structure(&yaml_doc)
+            .unwrap()
+            .into_iter()
+            .map(|issue| match issue {
+                SkeletorError::InvalidConfigEntry { path, reason } => (path, reason),
+                other => panic!("Expected InvalidConfigEntry, got {:?}", other),
+            })
+            .collect();
+        let expected = [
+            ("directories.src.version", "expected string or mapping, found integer"),
+            ("directories.src.empty", "expected string or mapping, found null"),
+            ("directories.flags", "expected string or mapping, found sequence"),
+            ("directories.enabled", "expected string or mapping, found boolean"),
+        ];
+        assert_eq!(
+            issues,
+            expected.map(|(path, reason)| (path.to_string(), reason.to_string()))
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_absolute_keys() {
+        let yaml_doc: Value =
+            serde_yaml::from_str("directories:\n  /etc/passwd: x\n  src:\n    'C:\\tool.exe': x\n").unwrap();
+
+        let paths: Vec<String> = validate_structure(&yaml_doc)
+            .unwrap()
+            .into_iter()
+            .filter_map(|issue| match issue {
+                SkeletorError::InvalidConfigEntry { path, .. } => Some(path),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(paths, vec!["directories./etc/passwd", "directories.src.C:\\tool.exe"]);
+    }
+
+    #[test]
+    fn test_validate_requires_directories_mapping() {
+        let yaml_doc: Value = serde_yaml::from_str("directories: [a]").unwrap();
+        assert!(matches!(
+            validate_structure(&yaml_doc),
+            Err(SkeletorError::InvalidConfigEntry { path, .. }) if path == "directories"
+        ));
+
+        let yaml_doc: Value = serde_yaml::from_str("other: 1").unwrap();
+        assert!(matches!(
+            validate_structure(&yaml_doc),
+            Err(SkeletorError::MissingConfigKey { .. })
+        ));
+    }
+
+    #[test]
+    fn test_run_validate() {
+        let fs = TestFileSystem::new();
+        let valid = fs.create_test_config("valid.yml");
+        let invalid = fs.create_config_from_content("invalid.yml", "directories:\n  port: 8080\n");
+
+        if let Some(sub_m) = create_validate_matches(vec![valid.to_str().unwrap()]) {
+            assert_command_succeeds(|| run_validate(&sub_m));
+        } else {
+            panic!("Validate subcommand not found");
+        }
+
+        let sub_m = create_validate_matches(vec![invalid.to_str().unwrap()]).unwrap();
+        match run_validate(&sub_m) {
+            Err(SkeletorError::InvalidStructure { count }) => assert_eq!(count, 1),
+            other => panic!("Expected InvalidStructure error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_run_validate_with_schema() {
+        let fs = TestFileSystem::new();
+        let schema = fs.create_file("schema.yml", "required:\n  description: string\n  version: string\n");
+        let template = fs.create_config_from_content("template.yml", "version: 2\ndirectories:\n  README.md: hi\n");
+
+        let args = vec![template.to_str().unwrap(), "--schema", schema.to_str().unwrap()];
+        let sub_m = create_validate_matches(args).unwrap();
+        match run_validate(&sub_m) {
+            Err(SkeletorError::SchemaViolations { count }) => assert_eq!(count, 2),
+            other => panic!("Expected SchemaViolations error, got {:?}", other),
+        }
+
+        let template = fs.create_config_from_content(
+            "described.yml",
+            "description: A demo\nversion: '1.0'\ndirectories:\n  README.md: hi\n",
+        );
+        let args = vec![template.to_str().unwrap(), "--schema", schema.to_str().unwrap()];
+        let sub_m = create_validate_matches(args).unwrap();
+        assert_command_succeeds(|| run_validate(&sub_m));
+    }
+}
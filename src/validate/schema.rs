@@ -0,0 +1,121 @@
+use crate::config::type_name;
+use crate::errors::SkeletorError;
+use serde_yaml::Value;
+use std::path::Path;
+
+/// Types a schema may require; `number` accepts integers and floats, `any` only presence
+const TYPES: [&str; 8] = ["string", "integer", "float", "number", "boolean", "sequence", "mapping", "any"];
+
+/// Top-level keys a template must declare, read from a schema file such as
+///
+/// ```yaml
+/// required:
+///   description: string
+///   version: string
+///   tags: sequence
+/// ```
+///
+/// `required` may also be a plain list of names, which only checks that they are present.
+#[derive(Debug)]
+pub struct TemplateSchema {
+    required: Vec<(String, &'static str)>,
+}
+
+impl TemplateSchema {
+    /// Reads the schema at `path`; an unknown type or a malformed `required` fails with
+    /// [`SkeletorError::Config`] naming the file.
+    pub fn load(path: &Path) -> Result<Self, SkeletorError> {
+        let document: Value = crate::utils::read_config_file(path)?;
+        Self::from_value(&document).map_err(|reason| SkeletorError::Config(format!("{}: {}", path.display(), reason)))
+    }
+
+    fn from_value(document: &Value) -> Result<Self, String> {
+        let required = match document.get("required") {
+            Some(Value::Mapping(keys)) => keys
+                .iter()
+                .map(|(key, kind)| {
+                    let key = key.as_str().ok_or("required key names must be strings")?;
+                    let kind = kind
+                        .as_str()
+                        .and_then(|kind| TYPES.iter().find(|known| **known == kind))
+                        .ok_or_else(|| format!("'{}' must have one of the types {}", key, TYPES.join(", ")))?;
+                    Ok((key.to_string(), *kind))
+                })
+                .collect::<Result<_, String>>()?,
+            Some(Value::Sequence(keys)) => keys
+                .iter()
+                .map(|key| Ok((key.as_str().ok_or("required key names must be strings")?.to_string(), "any")))
+                .collect::<Result<_, String>>()?,
+            _ => return Err("a schema needs a `required` mapping of key names to types, or a list of names".to_string()),
+        };
+        Ok(Self { required })
+    }
+
+    /// Every required key that `yaml_doc` is missing or has with the wrong type, as
+    /// [`SkeletorError::InvalidConfigEntry`] issues in schema order
+    pub fn violations(&self, yaml_doc: &Value) -> Vec<SkeletorError> {
+        self.required
+            .iter()
+            .filter_map(|(key, kind)| {
+                let reason = match yaml_doc.get(key.as_str()) {
+                    None => format!("missing; the schema requires a {}", kind),
+                    Some(value) if !has_type(value, kind) => format!("expected {}, found {}", kind, type_name(value)),
+                    Some(_) => return None,
+                };
+                Some(SkeletorError::invalid_config_entry(key.as_str(), reason))
+            })
+            .collect()
+    }
+}
+
+fn has_type(value: &Value, kind: &str) -> bool {
+    match kind {
+        "any" => true,
+        "number" => value.is_number(),
+        _ => type_name(value) == kind,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn violations(schema: &str, document: &str) -> Vec<(String, String)> {
+        let schema = TemplateSchema::from_value(&serde_yaml::from_str(schema).unwrap()).unwrap();
+        schema
+            .violations(&serde_yaml::from_str(document).unwrap())
+            .into_iter()
+            .map(|issue| match issue {
+                SkeletorError::InvalidConfigEntry { path, reason } => (path, reason),
+                other => panic!("Expected InvalidConfigEntry, got {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_schema_reports_every_missing_and_mistyped_key() {
+        let schema = "required:\n  description: string\n  version: string\n  revision: number\n  tags: sequence\n";
+        let document = "version: 2\nrevision: 1.5\ndirectories: {}\n";
+
+        let expected = [
+            ("description", "missing; the schema requires a string"),
+            ("version", "expected string, found integer"),
+            ("tags", "missing; the schema requires a sequence"),
+        ];
+        assert_eq!(
+            violations(schema, document),
+            expected.map(|(key, reason)| (key.to_string(), reason.to_string()))
+        );
+        assert!(violations(schema, "description: d\nversion: '1.0'\nrevision: 3\ntags: []\n").is_empty());
+    }
+
+    #[test]
+    fn test_schema_accepts_a_list_of_names_and_rejects_unknown_types() {
+        assert_eq!(violations("required: [description]", "description: 1\n"), vec![]);
+        assert_eq!(violations("required: [description]", "other: 1\n").len(), 1);
+
+        let unknown = TemplateSchema::from_value(&serde_yaml::from_str("required:\n  version: strng\n").unwrap());
+        assert!(unknown.unwrap_err().contains("'version' must have one of the types"));
+        assert!(TemplateSchema::from_value(&serde_yaml::from_str("keys: [a]").unwrap()).is_err());
+    }
+}
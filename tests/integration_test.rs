@@ -198,6 +198,32 @@ stats:
     assert!(stdout.contains("2024-01-01"));
 }
 
+/// Test info --json prints one parseable object with absent fields as null
+#[test]
+fn test_cli_info_json() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("config.yml");
+    fs::write(
+        &config_file,
+        "created: \"2024-01-01T00:00:00Z\"\ndirectories:\n  src:\n    main.rs: \"fn main() {}\"\nstats:\n  files: 1\n  directories: 1\n",
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "info", "--json", config_file.to_str().unwrap()])
+        .output()
+        .expect("Failed to run skeletor info --json");
+    assert!(output.status.success(), "Info command failed: {}",
+            String::from_utf8_lossy(&output.stderr));
+
+    let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(info["created"], "2024-01-01T00:00:00Z");
+    assert_eq!(info["stats"]["files"], 1);
+    assert_eq!(info["stats"]["directories"], 1);
+    assert!(info["updated"].is_null());
+    assert!(info.get("ignore_patterns").is_some_and(serde_json::Value::is_null));
+}
+
 /// Test error handling for missing config file
 #[test]
 fn test_cli_error_missing_config() {
@@ -282,6 +308,61 @@ directories:
     assert!(output_dir.join("test_file.txt").exists(), "test_file.txt should exist in output directory");
 }
 
+/// Test that --fail-on-skip exits with status 3 and lists skipped files
+#[test]
+fn test_cli_apply_fail_on_skip_exit_code() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("test.yml");
+    let output_dir = temp_dir.path().join("output_skip");
+
+    fs::write(&config_file, "directories:\n  test.txt: \"template\"\n").unwrap();
+    fs::create_dir_all(&output_dir).unwrap();
+    fs::write(output_dir.join("test.txt"), "existing content").unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "apply",
+            config_file.to_str().unwrap(),
+            "-o", output_dir.to_str().unwrap(),
+            "--fail-on-skip", "--format", "plain"
+        ])
+        .output()
+        .expect("Failed to run skeletor apply with --fail-on-skip");
+
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("skipped:"), "Skipped files should be listed: {}", stdout);
+    assert_eq!(fs::read_to_string(output_dir.join("test.txt")).unwrap(), "existing content");
+}
+
+/// Test that --format github emits workflow commands for skipped files and errors
+#[test]
+fn test_cli_apply_github_format() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("test.yml");
+    let output_dir = temp_dir.path().join("output_github");
+
+    fs::write(&config_file, "directories:\n  test.txt: \"template\"\n  new.txt: \"\"\n").unwrap();
+    fs::create_dir_all(&output_dir).unwrap();
+    fs::write(output_dir.join("test.txt"), "existing content").unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run", "--", "apply",
+            config_file.to_str().unwrap(),
+            "-o", output_dir.to_str().unwrap(),
+            "--fail-on-skip", "--format", "github"
+        ])
+        .output()
+        .expect("Failed to run skeletor apply with --format github");
+
+    assert_eq!(output.status.code(), Some(3));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("::warning file="), "Skipped file should be a warning: {}", stdout);
+    assert!(stdout.contains("::notice::Created 1 files"), "Summary should be a notice: {}", stdout);
+    assert!(stdout.contains("::error::"), "Failure should be an error: {}", stdout);
+}
+
 /// Test that overwrite flag still works with output flag
 #[test]
 fn test_cli_apply_output_with_overwrite() {
@@ -333,3 +414,334 @@ fn test_cli_version() {
     // Verify it's a valid semver-like pattern (major.minor.patch)
     assert!(stdout.contains(env!("CARGO_PKG_VERSION")));
 }
+
+/// Test that apply --stdin reads the template from a pipe
+#[test]
+fn test_cli_apply_from_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let temp_dir = tempdir().unwrap();
+    let output_dir = temp_dir.path().join("from_stdin");
+
+    let mut child = Command::new("cargo")
+        .args(["run", "--", "apply", "--stdin", "-o", output_dir.to_str().unwrap()])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run skeletor apply --stdin");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"directories:\n  src:\n    main.rs: \"fn main() {}\"\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+
+    assert!(output.status.success(), "apply --stdin failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fs::read_to_string(output_dir.join("src/main.rs")).unwrap(), "fn main() {}");
+}
+
+/// Test that --quiet leaves stdout empty while failures still set the exit code
+#[test]
+fn test_cli_quiet() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("quiet.yml");
+    let output_dir = temp_dir.path().join("quiet_output");
+    fs::write(&config_file, "directories:\n  src:\n    main.rs: \"fn main() {}\"\n").unwrap();
+
+    let apply = |extra: &[&str]| {
+        let mut args = vec!["run", "--", "--quiet", "apply", config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap()];
+        args.extend_from_slice(extra);
+        Command::new("cargo").args(&args).output().expect("Failed to run skeletor --quiet apply")
+    };
+
+    let output = apply(&[]);
+    assert!(output.status.success(), "quiet apply failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(output.stdout.is_empty(), "unexpected stdout: {}", String::from_utf8_lossy(&output.stdout));
+    assert!(output_dir.join("src/main.rs").is_file());
+
+    // Re-applying skips the existing file; --fail-on-skip still fails with its exit code
+    let output = apply(&["--fail-on-skip"]);
+    assert_eq!(output.status.code(), Some(3));
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("skipped"));
+}
+
+#[test]
+fn test_cli_apply_dry_run_cache_hit_respects_format_and_quiet() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("cached.yml");
+    let output_dir = temp_dir.path().join("cached_output");
+    let cache_dir = temp_dir.path().join("cache");
+    fs::write(&config_file, "directories:\n  README.md: \"# demo\"\n").unwrap();
+
+    let dry_run = |global: &[&str], extra: &[&str]| {
+        let mut args = vec!["run", "--"];
+        args.extend_from_slice(global);
+        args.extend_from_slice(&["apply", config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap()]);
+        args.extend_from_slice(&["--dry-run", "--cache", cache_dir.to_str().unwrap()]);
+        args.extend_from_slice(extra);
+        let output = Command::new("cargo").args(&args).output().expect("Failed to run skeletor apply");
+        assert!(output.status.success(), "dry run failed: {}", String::from_utf8_lossy(&output.stderr));
+        output
+    };
+
+    // Each run's options are part of the fingerprint, so prime the cache for each
+    dry_run(&["--quiet"], &[]);
+    let output = dry_run(&["--quiet"], &[]);
+    assert!(output.stdout.is_empty(), "unexpected stdout: {}", String::from_utf8_lossy(&output.stdout));
+
+    dry_run(&[], &["--format", "json"]);
+    let output = dry_run(&[], &["--format", "json"]);
+    let hit: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(hit["unchanged"], serde_json::Value::Bool(true));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_apply_post_apply_hooks() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("hooks.yml");
+    let output_dir = temp_dir.path().join("hooks_output");
+    fs::write(
+        &config_file,
+        "directories:\n  README.md: \"# demo\"\nhooks:\n  post_apply:\n    - ls > listing.txt\n    - exit 7\n",
+    )
+    .unwrap();
+
+    let apply = |extra: &[&str]| {
+        let mut args = vec!["run", "--", "apply", config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap()];
+        args.extend_from_slice(extra);
+        Command::new("cargo").args(&args).output().expect("Failed to run skeletor apply")
+    };
+
+    let output = apply(&["--dry-run"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("ls > listing.txt"));
+    assert!(!output_dir.exists());
+
+    // Hooks are opt-in: a plain apply only points them out
+    let output = apply(&[]);
+    assert!(output.status.success(), "apply failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("--run-hooks"));
+    assert!(!output_dir.join("listing.txt").exists());
+
+    // Hooks run in the output directory, and a failing one fails the apply
+    let output = apply(&["--overwrite", "--run-hooks"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("exit 7"));
+    assert_eq!(fs::read_to_string(output_dir.join("listing.txt")).unwrap().trim(), "README.md\nlisting.txt");
+}
+
+#[test]
+fn test_cli_diff_exit_code_reports_drift() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("template.yml");
+    let project_dir = temp_dir.path().join("project");
+    fs::write(&config_file, "directories:\n  README.md: \"# demo\"\n  src:\n    main.rs: fn main() {}\n").unwrap();
+    fs::create_dir_all(project_dir.join("src")).unwrap();
+    fs::write(project_dir.join("README.md"), "# demo").unwrap();
+
+    let diff = |extra: &[&str]| {
+        let mut args = vec!["run", "--", "diff", config_file.to_str().unwrap(), project_dir.to_str().unwrap()];
+        args.extend_from_slice(extra);
+        Command::new("cargo").args(&args).output().expect("Failed to run skeletor diff")
+    };
+
+    let output = diff(&["--verbose"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout.contains("+ 1 new files, ~ 0 changed, = 1 identical, 0 dirs to create"));
+    assert!(stdout.contains(&format!("+ {}", project_dir.join("src/main.rs").display())));
+    assert!(stdout.contains(&format!("= {}", project_dir.join("README.md").display())));
+
+    fs::write(project_dir.join("src/main.rs"), "fn main() {}").unwrap();
+    let output = diff(&[]);
+    assert!(output.status.success(), "diff failed: {}", String::from_utf8_lossy(&output.stderr));
+}
+
+#[test]
+fn test_cli_gzipped_snapshot_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    fs::create_dir_all(source_dir.join("src")).unwrap();
+    fs::write(source_dir.join("src/main.rs"), "fn main() {}\n").unwrap();
+    fs::write(source_dir.join("README.md"), "# Round trip\n").unwrap();
+
+    let skeletor = |args: &[&str]| {
+        let output = Command::new("cargo")
+            .args(["run", "--"])
+            .args(args)
+            .output()
+            .expect("Failed to run skeletor");
+        assert!(output.status.success(), "skeletor {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+        output
+    };
+
+    for name in ["template.yml", "template.yml.gz"] {
+        let template = temp_dir.path().join(name);
+        skeletor(&["snapshot", source_dir.to_str().unwrap(), "-o", template.to_str().unwrap()]);
+        let output_dir = temp_dir.path().join(format!("{}-out", name));
+        skeletor(&["apply", template.to_str().unwrap(), "-o", output_dir.to_str().unwrap()]);
+        let info = skeletor(&["info", template.to_str().unwrap()]);
+        assert!(String::from_utf8_lossy(&info.stdout).contains("Snapshot generated from folder"));
+    }
+
+    let compressed = fs::read(temp_dir.path().join("template.yml.gz")).unwrap();
+    assert_eq!(&compressed[..2], &[0x1f, 0x8b]);
+    assert!(fs::read_to_string(temp_dir.path().join("template.yml")).unwrap().starts_with("created:"));
+    for file in ["src/main.rs", "README.md"] {
+        assert_eq!(
+            fs::read(temp_dir.path().join("template.yml-out").join(file)).unwrap(),
+            fs::read(temp_dir.path().join("template.yml.gz-out").join(file)).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_cli_apply_clean_removes_files_not_in_template() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("template.yml");
+    let output_dir = temp_dir.path().join("project");
+    fs::write(&config_file, "directories:\n  src:\n    main.rs: fn main() {}\n").unwrap();
+    fs::create_dir_all(output_dir.join("src")).unwrap();
+    fs::write(output_dir.join("src/old.rs"), "// stale").unwrap();
+    fs::create_dir_all(output_dir.join(".git")).unwrap();
+    fs::write(output_dir.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+
+    let apply = |extra: &[&str]| {
+        let mut args = vec!["run", "--", "apply", config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap()];
+        args.extend_from_slice(extra);
+        Command::new("cargo").args(&args).output().expect("Failed to run skeletor apply")
+    };
+
+    // Deleting requires opting into overwriting as well
+    let output = apply(&["--clean"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--overwrite"));
+
+    let output = apply(&["--clean", "--overwrite", "--dry-run"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("old.rs"));
+    assert!(output_dir.join("src/old.rs").exists());
+
+    let output = apply(&["--clean", "--overwrite"]);
+    assert!(output.status.success(), "apply failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("old.rs"));
+    assert!(!output_dir.join("src/old.rs").exists());
+    assert_eq!(fs::read_to_string(output_dir.join("src/main.rs")).unwrap(), "fn main() {}");
+    // The repository the output lives in is not the template's to clean up
+    assert!(!String::from_utf8_lossy(&output.stdout).contains("HEAD"));
+    assert_eq!(fs::read_to_string(output_dir.join(".git/HEAD")).unwrap(), "ref: refs/heads/main\n");
+}
+
+#[test]
+fn test_cli_apply_warns_about_binary_files_from_snapshot() {
+    let temp_dir = tempdir().unwrap();
+    let source_dir = temp_dir.path().join("source");
+    fs::create_dir_all(source_dir.join("assets")).unwrap();
+    fs::write(source_dir.join("assets/logo.png"), [0x89, 0x50, 0x4e, 0x47, 0x00, 0x00]).unwrap();
+    fs::write(source_dir.join("README.md"), "# Logo\n").unwrap();
+    let template = temp_dir.path().join("template.yml");
+    let output_dir = temp_dir.path().join("project");
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "snapshot", source_dir.to_str().unwrap(), "-o", template.to_str().unwrap()])
+        .output()
+        .expect("Failed to run skeletor snapshot");
+    assert!(output.status.success());
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "apply", template.to_str().unwrap(), "-o", output_dir.to_str().unwrap(), "--format", "plain"])
+        .output()
+        .expect("Failed to run skeletor apply");
+    assert!(output.status.success(), "apply failed: {}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Binary files not recreated: 1"), "{}", stdout);
+    assert!(stdout.contains("binary: assets/logo.png"), "{}", stdout);
+    assert_eq!(fs::read(output_dir.join("assets/logo.png")).unwrap(), b"");
+}
+
+#[test]
+fn test_cli_apply_resolves_bare_names_in_template_dir() {
+    let temp_dir = tempdir().unwrap();
+    let templates = temp_dir.path().join("templates");
+    fs::create_dir_all(&templates).unwrap();
+    fs::write(templates.join("rust.yml"), "directories:\n  src:\n    main.rs: \"fn main() {}\"\n").unwrap();
+    let output_dir = temp_dir.path().join("project");
+
+    let skeletor = |args: &[&str]| {
+        Command::new("cargo")
+            .args(["run", "--"])
+            .args(args)
+            .env("SKELETOR_TEMPLATE_DIR", &templates)
+            .output()
+            .expect("Failed to run skeletor")
+    };
+
+    let output = skeletor(&["apply", "rust", "-o", output_dir.to_str().unwrap()]);
+    assert!(output.status.success(), "apply failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fs::read_to_string(output_dir.join("src/main.rs")).unwrap(), "fn main() {}");
+
+    let output = skeletor(&["templates", "list"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "rust\n");
+}
+
+#[test]
+fn test_cli_apply_env_vars_fill_unresolved_placeholders() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("template.yml");
+    let output_dir = temp_dir.path().join("project");
+    fs::write(
+        &config_file,
+        "directories:\n  profile.txt: \"${SKELETOR_IT_HOME} ${SKELETOR_IT_USER}\"\n",
+    )
+    .unwrap();
+
+    let apply = |extra: &[&str]| {
+        let mut args = vec!["run", "--", "apply", config_file.to_str().unwrap(), "-o", output_dir.to_str().unwrap()];
+        args.extend_from_slice(extra);
+        Command::new("cargo")
+            .args(&args)
+            .env("SKELETOR_IT_HOME", "/home/tester")
+            .env("SKELETOR_IT_USER", "from-env")
+            .output()
+            .expect("Failed to run skeletor apply")
+    };
+
+    // The environment is only read when asked for
+    let output = apply(&["--var", "SKELETOR_IT_USER=from-var"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("SKELETOR_IT_HOME"));
+
+    let output = apply(&["--var", "SKELETOR_IT_USER=from-var", "--env-vars"]);
+    assert!(output.status.success(), "apply failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fs::read_to_string(output_dir.join("profile.txt")).unwrap(), "/home/tester from-var");
+}
+
+#[test]
+fn test_cli_apply_finds_skeletorrc_in_a_parent_directory() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(temp_dir.path().join(".skeletorrc"), "directories:\n  notes.md: from the parent\n").unwrap();
+    let nested = temp_dir.path().join("packages/web");
+    fs::create_dir_all(&nested).unwrap();
+
+    let apply = |extra: &[&str]| {
+        let mut args = vec!["run", "--", "apply", "--working-dir", nested.to_str().unwrap()];
+        args.extend_from_slice(extra);
+        Command::new("cargo").args(&args).output().expect("Failed to run skeletor apply")
+    };
+
+    let output = apply(&["--no-ascend"]);
+    assert!(!output.status.success());
+    assert!(!nested.join("notes.md").exists());
+
+    // Found above, but the files still land in the working directory
+    let output = apply(&[]);
+    assert!(output.status.success(), "apply failed: {}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(fs::read_to_string(nested.join("notes.md")).unwrap(), "from the parent");
+    assert!(!temp_dir.path().join("notes.md").exists());
+}
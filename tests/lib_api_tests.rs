@@ -1,4 +1,5 @@
-use skeletor::{SkeletorConfig, apply_config};
+use skeletor::{ApplyOptions, SkeletorConfig, SkeletorError, SnapshotOptions, TaskKind, apply_config, apply_config_with, apply_config_with_reporter, plan_tasks, Reporter, SimpleApplyResult, SimpleSnapshotResult, Task, snapshot_to_writer, take_snapshot};
+use std::path::Path;
 use tempfile::tempdir;
 
 #[test]
@@ -29,6 +30,7 @@ directories:
     assert!(target_path.join("src/main.rs").exists());
     assert!(target_path.join("src/lib.rs").exists());
     assert!(target_path.join("tests/test.rs").exists());
+    assert!(result.planned_tasks.is_none());
 }
 
 #[test]
@@ -48,7 +50,313 @@ directories:
     assert_eq!(result.files_created, 0);
     assert_eq!(result.dirs_created, 0);
     assert_eq!(result.tasks_total, 2); // 1 dir + 1 file
+
+    let planned = result.planned_tasks.expect("dry run should list planned tasks");
+    assert_eq!(planned.len(), result.tasks_total);
+    assert!(planned
+        .iter()
+        .any(|t| t.kind == TaskKind::Dir && t.path == target_path.join("test_dir")));
+    assert!(planned
+        .iter()
+        .any(|t| t.kind == TaskKind::File && t.path == target_path.join("test_dir/test_file.txt")));
     
     // Check that no files were actually created
     assert!(!target_path.join("test_dir").exists());
-}
\ No newline at end of file
+    assert_eq!((result.would_create, result.would_skip), (1, 0));
+}
+
+#[test]
+fn test_library_dry_run_counts_existing_files() {
+    let temp_dir = tempdir().unwrap();
+    let target_path = temp_dir.path();
+    std::fs::create_dir_all(target_path.join("src")).unwrap();
+    std::fs::write(target_path.join("src/main.rs"), "fn main() {}").unwrap();
+    std::fs::write(target_path.join("README.md"), "local notes").unwrap();
+    std::fs::write(target_path.join(".skeletorkeep"), "README.md\n").unwrap();
+
+    let config = SkeletorConfig::from_yaml_str(r#"
+directories:
+  README.md: "Project readme"
+  src:
+    main.rs: "fn main() {}"
+    lib.rs: ""
+"#).unwrap();
+
+    let result = apply_config(&config, target_path, false, true).unwrap();
+    assert_eq!((result.would_create, result.would_skip), (1, 2));
+
+    // Overwriting replaces existing files, except those .skeletorkeep keeps
+    let result = apply_config(&config, target_path, true, true).unwrap();
+    assert_eq!((result.would_create, result.would_skip), (2, 1));
+
+    let result = apply_config(&config, target_path, false, false).unwrap();
+    assert_eq!((result.would_create, result.would_skip), (0, 0));
+}
+#[test]
+fn test_library_take_snapshot() {
+    let temp_dir = tempdir().unwrap();
+    let source = temp_dir.path();
+    std::fs::create_dir_all(source.join("src")).unwrap();
+    std::fs::write(source.join("src/main.rs"), "fn main() {}").unwrap();
+    std::fs::write(source.join("debug.log"), "noise").unwrap();
+
+    let opts = SnapshotOptions {
+        ignore_patterns: vec!["*.log".to_string()],
+        notes: vec!["Captured from tests".to_string()],
+        ..SnapshotOptions::default()
+    };
+    let (result, snapshot) = take_snapshot(source, &opts).unwrap();
+
+    assert_eq!(result.files_processed, 1);
+    assert_eq!(result.dirs_processed, 1);
+    assert!(result.output_path.is_none());
+
+    for key in ["created", "updated", "generated_comments", "generated_with", "notes", "stats", "checksum", "directories"] {
+        assert!(snapshot.get(key).is_some(), "missing key: {}", key);
+    }
+    let directories = &snapshot["directories"];
+    assert_eq!(directories["src"]["main.rs"].as_str(), Some("fn main() {}"));
+    assert!(directories.get("debug.log").is_none());
+
+    // Nothing is written to disk
+    assert!(!source.join(".skeletorrc").exists());
+    assert_eq!(result.bytes_written, None);
+}
+
+#[test]
+fn test_library_snapshot_statistics() {
+    let temp_dir = tempdir().unwrap();
+    let source = temp_dir.path();
+    std::fs::create_dir_all(source.join("assets/icons")).unwrap();
+    std::fs::write(source.join("README.md"), "# demo").unwrap();
+    std::fs::write(source.join("assets/logo.png"), [0x89, b'P', b'N', b'G', 0x00, 0xFF]).unwrap();
+    std::fs::write(source.join("assets/icons/app.svg"), "<svg/>").unwrap();
+
+    let (result, _) = take_snapshot(source, &SnapshotOptions::default()).unwrap();
+
+    assert_eq!(result.files_processed, 3);
+    assert_eq!(result.dirs_processed, 2);
+    assert_eq!(result.binary_files_excluded, 1);
+    assert_eq!(result.binary_files_list, vec!["assets/logo.png".to_string()]);
+}
+
+#[test]
+fn test_library_working_dir() {
+    let temp_dir = tempdir().unwrap();
+    let config = SkeletorConfig::from_yaml_str(r#"
+directories:
+  src:
+    lib.rs: "pub fn demo() {}"
+"#).unwrap();
+
+    // Relative targets and sources resolve against the working dir, not the process CWD
+    let result = skeletor::apply_config_in(&config, temp_dir.path(), Path::new("project"), false, false).unwrap();
+    assert_eq!(result.files_created, 1);
+    assert!(temp_dir.path().join("project/src/lib.rs").is_file());
+
+    let opts = SnapshotOptions {
+        working_dir: Some(temp_dir.path().to_path_buf()),
+        ..SnapshotOptions::default()
+    };
+    let (result, snapshot) = take_snapshot(Path::new("project"), &opts).unwrap();
+    assert_eq!(result.files_processed, 1);
+    assert_eq!(snapshot["directories"]["src"]["lib.rs"].as_str(), Some("pub fn demo() {}"));
+}
+
+#[test]
+fn test_library_snapshot_to_writer() {
+    let temp_dir = tempdir().unwrap();
+    std::fs::create_dir_all(temp_dir.path().join("src")).unwrap();
+    std::fs::write(temp_dir.path().join("src/main.rs"), "fn main() {}").unwrap();
+
+    // Any io::Write sink works; here an in-memory buffer
+    let mut buffer: Vec<u8> = Vec::new();
+    let result = snapshot_to_writer(temp_dir.path(), &SnapshotOptions::default(), &mut buffer).unwrap();
+    assert_eq!(result.files_processed, 1);
+
+    let snapshot: serde_yaml::Value = serde_yaml::from_slice(&buffer).unwrap();
+    assert_eq!(snapshot["directories"]["src"]["main.rs"].as_str(), Some("fn main() {}"));
+    assert_eq!(snapshot["stats"]["files"].as_u64(), Some(1));
+}
+
+#[test]
+fn test_library_apply_config_with_transform() {
+    let temp_dir = tempdir().unwrap();
+    let config = SkeletorConfig::from_yaml_str(r#"
+variables:
+  name: demo
+directories:
+  src:
+    main.rs: "fn main() {}"
+"#).unwrap();
+
+    let opts = ApplyOptions {
+        target_dir: temp_dir.path().to_path_buf(),
+        ..ApplyOptions::default()
+    };
+    // Injected entries are substituted like the config's own
+    let result = apply_config_with(&config, &opts, |directories| {
+        directories["src"]
+            .as_mapping_mut()
+            .unwrap()
+            .insert("${name}.rs".into(), "// ${name}".into());
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(result.files_created, 2);
+    assert_eq!(std::fs::read_to_string(temp_dir.path().join("src/demo.rs")).unwrap(), "// demo");
+
+    // A failing transform aborts before anything is planned
+    let result = apply_config_with(&config, &opts, |_| Err(SkeletorError::Config("rejected".to_string())));
+    assert!(matches!(result, Err(SkeletorError::Config(message)) if message == "rejected"));
+}
+
+#[test]
+fn test_library_apply_config_batched() {
+    let temp_dir = tempdir().unwrap();
+    let mut files = String::from("directories:\n  pkg:\n");
+    for i in 0..2000 {
+        files.push_str(&format!("    file{}.txt: \"{}\"\n", i, i));
+    }
+    let config = SkeletorConfig::from_yaml_str(&files).unwrap();
+
+    let opts = ApplyOptions {
+        target_dir: temp_dir.path().to_path_buf(),
+        batch_size: Some(256),
+        ..ApplyOptions::default()
+    };
+    let result = apply_config_with(&config, &opts, |_| Ok(())).unwrap();
+
+    assert_eq!(result.tasks_total, 2001);
+    assert_eq!(result.files_created, 2000);
+    assert_eq!(result.dirs_created, 1);
+    assert_eq!(std::fs::read_to_string(temp_dir.path().join("pkg/file1999.txt")).unwrap(), "1999");
+}
+
+#[test]
+fn test_library_apply_reports_failed_files() {
+    let config = SkeletorConfig::from_yaml_str("directories:\n  blocked:\n    inner.txt: \"x\"\n  ok.txt: \"ok\"\n").unwrap();
+
+    for batch_size in [None, Some(1)] {
+        let temp_dir = tempdir().unwrap();
+        // A file where the config wants a directory
+        std::fs::write(temp_dir.path().join("blocked"), "in the way").unwrap();
+        let opts = ApplyOptions {
+            target_dir: temp_dir.path().to_path_buf(),
+            batch_size,
+            ..ApplyOptions::default()
+        };
+        let result = apply_config_with(&config, &opts, |_| Ok(())).unwrap();
+
+        assert_eq!(std::fs::read_to_string(temp_dir.path().join("ok.txt")).unwrap(), "ok");
+        assert!(!result.failed_files.is_empty(), "batch size {:?}", batch_size);
+        assert!(result.failed_files.iter().all(|(path, reason)| path.starts_with(temp_dir.path().join("blocked")) && !reason.is_empty()));
+    }
+
+    let temp_dir = tempdir().unwrap();
+    let result = apply_config(&config, temp_dir.path(), false, false).unwrap();
+    assert!(result.failed_files.is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_library_apply_stays_within_target_dir() {
+    let temp_dir = tempdir().unwrap();
+    let target = temp_dir.path().join("target");
+    let outside = temp_dir.path().join("outside");
+    std::fs::create_dir_all(&target).unwrap();
+    std::fs::create_dir_all(&outside).unwrap();
+    // A directory the config writes into is a symlink leading out of the target
+    std::os::unix::fs::symlink(&outside, target.join("src")).unwrap();
+    let config = SkeletorConfig::from_yaml_str("directories:\n  src:\n    main.rs: \"fn main() {}\"\n").unwrap();
+
+    for batch_size in [None, Some(1)] {
+        let opts = ApplyOptions {
+            target_dir: target.clone(),
+            batch_size,
+            ..ApplyOptions::default()
+        };
+        let result = apply_config_with(&config, &opts, |_| Ok(()));
+        assert!(
+            matches!(&result, Err(SkeletorError::Config(message)) if message.contains("outside the output directory")),
+            "batch size {:?}",
+            batch_size
+        );
+        assert!(!outside.join("main.rs").exists());
+    }
+    assert!(apply_config(&config, &target, false, true).is_err());
+}
+
+#[test]
+fn test_library_plan_tasks_order() {
+    let config = SkeletorConfig::from_yaml_str(
+        "variables:\n  name: demo\ndirectories:\n  src:\n    ${name}.rs: \"// ${name}\"\n    nested:\n      mod.rs: \"\"\n  README.md: \"# readme\"\n",
+    )
+    .unwrap();
+    let target = Path::new("out");
+    let tasks = plan_tasks(&config, target).unwrap();
+
+    assert_eq!(tasks.len(), 5);
+    assert!(tasks.iter().any(|task| *task == Task::File(target.join("src/demo.rs"), "// demo".to_string())));
+    for (index, task) in tasks.iter().enumerate() {
+        if let Some(parent) = task.path().parent().filter(|parent| *parent != target) {
+            let dir = tasks.iter().position(|other| other.is_dir() && other.path() == parent);
+            assert!(dir.is_some_and(|dir| dir < index), "{} planned before its directory", task.path().display());
+        }
+    }
+    // Nothing is written
+    assert!(!target.exists());
+}
+
+/// Records the calls a custom reporter receives
+#[derive(Default)]
+struct RecordingReporter {
+    events: std::cell::RefCell<Vec<String>>,
+}
+
+impl Reporter for RecordingReporter {
+    fn operation_start(&self, _operation: &str, _details: &str) {}
+    fn progress(&self, current: usize, total: usize, _message: &str) {
+        self.events.borrow_mut().push(format!("progress {}/{}", current, total));
+    }
+    fn task_success(&self, task: &Task) {
+        self.events.borrow_mut().push(format!("success {}", task.path().file_name().unwrap().to_string_lossy()));
+    }
+    fn task_warning(&self, _task: &Task, _error: &str) {}
+    fn warning(&self, _message: &str) {}
+    fn tip(&self, _message: &str) {}
+    fn dry_run_preview(&self, tasks: &[Task]) {
+        self.events.borrow_mut().push(format!("preview {}", tasks.len()));
+    }
+    fn dry_run_preview_verbose(&self, _tasks: &[Task], _verbose: bool) {}
+    fn dry_run_preview_comprehensive(&self, _tasks: &[Task], _verbose: bool, _binary_files: &[String], _ignore_patterns: &[String], _verb: &str) {}
+    fn verbose_operation_preview(&self, _tasks: &[Task]) {}
+    fn apply_complete(&self, result: &SimpleApplyResult, _verbose: bool) {
+        self.events.borrow_mut().push(format!("complete {} files", result.files_created));
+    }
+    fn snapshot_complete(&self, _result: &SimpleSnapshotResult) {}
+}
+
+#[test]
+fn test_library_apply_config_with_reporter() {
+    let temp_dir = tempdir().unwrap();
+    let config = SkeletorConfig::from_yaml_str("directories:\n  src:\n    main.rs: 'fn main() {}'\n").unwrap();
+    let mut opts = ApplyOptions {
+        target_dir: temp_dir.path().to_path_buf(),
+        dry_run: true,
+        ..ApplyOptions::default()
+    };
+
+    let reporter = RecordingReporter::default();
+    apply_config_with_reporter(&config, &opts, &reporter).unwrap();
+    assert_eq!(*reporter.events.borrow(), vec!["preview 2"]);
+
+    opts.dry_run = false;
+    let reporter = RecordingReporter::default();
+    let result = apply_config_with_reporter(&config, &opts, &reporter).unwrap();
+    assert_eq!(result.files_created, 1);
+    assert_eq!(
+        *reporter.events.borrow(),
+        vec!["progress 1/2", "success src", "progress 2/2", "success main.rs", "complete 1 files"]
+    );
+}